@@ -6,13 +6,20 @@ export class RESTClient {
 	}
 
 	async getReferenceTimeMilliseconds(): Promise<ReferenceTimeResponse> {
-		const response = await RESTClient.getWithTimings(`${this.apiBaseURL}/reference-time-milliseconds`);
-
-		const milliseconds = JSON.parse(response.text);
-		if (typeof milliseconds !== 'number') {
-			throw new RESTError(`Invalid reference time response: '${milliseconds}'`);
+		const originateTimestamp = Math.round(performance.now());
+		const response = await RESTClient.getWithTimings(
+			`${this.apiBaseURL}/time-sync?originate_timestamp_in_milliseconds=${originateTimestamp}`,
+		);
+
+		const timeSync = JSON.parse(response.text);
+		const receiveTimestamp = timeSync?.receive_timestamp_in_milliseconds;
+		const transmitTimestamp = timeSync?.transmit_timestamp_in_milliseconds;
+		if (typeof receiveTimestamp !== 'number' || typeof transmitTimestamp !== 'number') {
+			throw new RESTError(`Invalid reference time response: '${response.text}'`);
 		}
 
+		// The server's processing time is negligible, so the reference time is taken from the middle of it.
+		const milliseconds = (receiveTimestamp + transmitTimestamp) / 2;
 		return new ReferenceTimeResponse(milliseconds, response.sentAtMilliseconds, response.receivedAtMilliseconds);
 	}
 
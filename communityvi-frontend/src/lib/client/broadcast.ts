@@ -19,7 +19,8 @@ export interface ClientJoinedBroadcast extends BroadcastMessage {
 
 export interface MediumStateChangedBroadcast extends BroadcastMessage {
 	readonly changed_by_name: string;
-	readonly changed_by_id: number;
+	/** `null` if the server changed the medium by itself, e.g. while a client is buffering. */
+	readonly changed_by_id: number | null;
 	readonly medium: VersionedMediumBroadcast;
 }
 
@@ -98,7 +98,11 @@ export class Peer {
 		return new Peer(broadcast.sender_id, broadcast.sender_name);
 	}
 
-	static fromMediumStateChangedBroadcast(broadcast: MediumStateChangedBroadcast): Peer {
+	static fromMediumStateChangedBroadcast(broadcast: MediumStateChangedBroadcast): Peer | undefined {
+		if (broadcast.changed_by_id === null) {
+			return undefined;
+		}
+
 		return new Peer(broadcast.changed_by_id, broadcast.changed_by_name);
 	}
 
@@ -108,7 +112,11 @@ export class Peer {
 	}
 }
 
-export type MediumStateChanged = MediumChangedByPeer | MediumChangedByOurself | MediumTimeAdjusted;
+export type MediumStateChanged =
+	| MediumChangedByPeer
+	| MediumChangedByServer
+	| MediumChangedByOurself
+	| MediumTimeAdjusted;
 
 export class MediumChangedByPeer {
 	readonly changedBy: Peer;
@@ -120,6 +128,14 @@ export class MediumChangedByPeer {
 	}
 }
 
+export class MediumChangedByServer {
+	readonly medium?: Medium;
+
+	constructor(medium?: Medium) {
+		this.medium = medium;
+	}
+}
+
 export class MediumChangedByOurself {
 	readonly medium?: Medium;
 
@@ -165,7 +181,7 @@ export class VersionedMedium {
 				return new VersionedMedium(empty.version);
 			}
 			default:
-				throw new Error(`Invalid MediumResponse type: '${response.type}'`);
+				return VersionedMedium.unsupported(response.version, response.type);
 		}
 	}
 
@@ -192,10 +208,16 @@ export class VersionedMedium {
 				return new VersionedMedium(empty.version);
 			}
 			default:
-				throw new Error(`Invalid MediumBroadcast type: '${broadcast.type}'`);
+				return VersionedMedium.unsupported(broadcast.version, broadcast.type);
 		}
 	}
 
+	/** Media this client can't play, e.g. URL or live media, are treated like no medium, but keep their version. */
+	private static unsupported(version: number, type: string): VersionedMedium {
+		console.info('Ignoring unsupported medium type:', type);
+		return new VersionedMedium(version);
+	}
+
 	constructor(version: number, medium?: Medium) {
 		this.version = version;
 		this.medium = medium;
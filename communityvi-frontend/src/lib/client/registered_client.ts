@@ -25,6 +25,7 @@ import {
 	Medium,
 	VersionedMedium,
 	MediumChangedByPeer,
+	MediumChangedByServer,
 	MediumTimeAdjusted,
 	PlayingPlaybackState,
 	MediumChangedByOurself,
@@ -194,7 +195,8 @@ export default class RegisteredClient {
 				break;
 
 			default:
-				throw new UnknownBroadcastError(broadcast);
+				// The server also broadcasts e.g. typing, reactions and polls, which this client doesn't support yet.
+				console.info('Ignoring unsupported broadcast:', broadcast.type);
 		}
 	}
 
@@ -246,7 +248,11 @@ export default class RegisteredClient {
 		}
 
 		const changer = Peer.fromMediumStateChangedBroadcast(mediumStateChangedBroadcast);
-		this.mediumStateChangedMessageBroker.notify(new MediumChangedByPeer(changer, versionedMedium.medium));
+		const change =
+			changer === undefined
+				? new MediumChangedByServer(versionedMedium.medium)
+				: new MediumChangedByPeer(changer, versionedMedium.medium);
+		this.mediumStateChangedMessageBroker.notify(change);
 	}
 
 	private static connectionDidReceiveUnassignableResponse(response: ServerResponse): void {
@@ -263,15 +269,4 @@ export default class RegisteredClient {
 	}
 }
 
-export class UnknownBroadcastError extends Error {
-	readonly broadcast: BroadcastMessage;
-
-	constructor(broadcast: BroadcastMessage) {
-		super(`Unknown broadcast received: ${broadcast.type}.`);
-
-		this.name = UnknownBroadcastError.name;
-		this.broadcast = broadcast;
-	}
-}
-
 export type DisconnectCallback = (reason: CloseReason) => void;
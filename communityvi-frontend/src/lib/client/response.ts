@@ -2,6 +2,7 @@ import type {MediumType} from '$lib/client/request';
 
 export interface HelloMessage extends SuccessMessage {
 	readonly id: number;
+	readonly session_token: string;
 	readonly clients: Array<ClientResponse>;
 	readonly current_medium: VersionedMediumResponse;
 }
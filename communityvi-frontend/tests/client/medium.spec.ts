@@ -15,6 +15,7 @@ import {
 	Medium,
 	MediumChangedByOurself,
 	MediumChangedByPeer,
+	MediumChangedByServer,
 	MediumTimeAdjusted,
 	Peer,
 	PlayingPlaybackState,
@@ -58,6 +59,26 @@ describe('RegisteredClient medium handling', () => {
 			);
 		});
 
+		it('notifies subscribers when the server changes something about the medium', () => {
+			const mockConnection = mock<Connection>();
+			let connectionDelegate: ConnectionDelegate | undefined;
+			mockConnection.setDelegate.mockImplementationOnce(delegate => (connectionDelegate = delegate));
+			const client = RegisteredClientBuilder.default().id(42).connection(mockConnection).build();
+			const subscriber = vi.fn();
+
+			client.subscribeToMediumStateChanges(subscriber);
+			connectionDelegate?.connectionDidReceiveBroadcast(<MediumStateChangedBroadcast>{
+				type: BroadcastType.MediumStateChanged,
+				changed_by_id: null,
+				changed_by_name: 'Server',
+				medium: <VersionedMediumBroadcast>{
+					type: MediumType.Empty,
+				},
+			});
+
+			expect(subscriber).toHaveBeenCalledWith(new MediumChangedByServer(undefined));
+		});
+
 		it('ignores broadcasts when we have changed the medium', () => {
 			const mockConnection = mock<Connection>();
 			let connectionDelegate: ConnectionDelegate | undefined;
@@ -0,0 +1,20 @@
+CREATE TABLE bookmark
+(
+	uuid        blob                               not null
+		constraint bookmark_pk
+			primary key,
+	room_uuid   blob                               not null
+		constraint bookmark_room__fk
+			references room (uuid)
+			on delete cascade,
+	medium_id   text                               not null,
+	position_ms integer                            not null,
+	label       text                               not null,
+	created_at  datetime default current_timestamp not null,
+	constraint check_non_negative_position
+		check (position_ms >= 0),
+	constraint check_non_empty_label
+		check (length(label) > 0)
+);
+
+CREATE INDEX IF NOT EXISTS bookmark_medium_index ON bookmark (room_uuid, medium_id, position_ms);
@@ -0,0 +1,9 @@
+ALTER TABLE chat_message ADD COLUMN kind text default 'message' not null
+	constraint check_valid_kind
+		check (kind in ('message', 'reaction'));
+ALTER TABLE chat_message ADD COLUMN medium_id text;
+ALTER TABLE chat_message ADD COLUMN medium_position_ms integer
+	constraint check_non_negative_medium_position
+		check (medium_position_ms >= 0);
+
+CREATE INDEX IF NOT EXISTS chat_message_medium_index ON chat_message (room_uuid, medium_id, medium_position_ms);
@@ -0,0 +1 @@
+ALTER TABLE chat_message ADD COLUMN edited_at datetime;
@@ -0,0 +1,26 @@
+CREATE TABLE private_message
+(
+	uuid                blob                               not null
+		constraint private_message_pk
+			primary key,
+	room_uuid           blob                               not null
+		constraint private_message_room__fk
+			references room (uuid)
+			on delete cascade,
+	sender_user_uuid    blob                               null
+		constraint private_message_sender__fk
+			references user (uuid)
+			on delete set null,
+	sender_name         text                               not null,
+	recipient_user_uuid blob                               null
+		constraint private_message_recipient__fk
+			references user (uuid)
+			on delete set null,
+	recipient_name      text                               not null,
+	message             text                               not null,
+	created_at          datetime default current_timestamp not null,
+	constraint check_non_empty_message
+		check (length(message) > 0)
+);
+
+CREATE INDEX IF NOT EXISTS private_message_created_at_index ON private_message (room_uuid, created_at DESC);
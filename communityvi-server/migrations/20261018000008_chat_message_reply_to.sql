@@ -0,0 +1,4 @@
+ALTER TABLE chat_message ADD COLUMN reply_to_uuid blob
+	constraint chat_message_reply_to__fk
+		references chat_message (uuid)
+		on delete set null;
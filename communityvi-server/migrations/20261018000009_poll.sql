@@ -0,0 +1,53 @@
+CREATE TABLE poll
+(
+	uuid                 blob                               not null
+		constraint poll_pk
+			primary key,
+	room_uuid            blob                               not null
+		constraint poll_room__fk
+			references room (uuid)
+			on delete cascade,
+	kind                 text                               not null,
+	question             text                               not null,
+	created_by_user_uuid blob                               null
+		constraint poll_created_by__fk
+			references user (uuid)
+			on delete set null,
+	created_by_name      text                               not null,
+	medium_version       integer                            null,
+	deadline             datetime                           not null,
+	closed_at            datetime                           null,
+	created_at           datetime default current_timestamp not null,
+	constraint check_valid_kind
+		check (kind = 'custom' or kind = 'skip' or kind = 'pause'),
+	constraint check_non_empty_question
+		check (length(question) > 0)
+);
+
+CREATE TABLE poll_option
+(
+	poll_uuid    blob    not null
+		constraint poll_option_poll__fk
+			references poll (uuid)
+			on delete cascade,
+	option_index integer not null,
+	label        text    not null,
+	constraint poll_option_pk
+		primary key (poll_uuid, option_index),
+	constraint check_non_empty_label
+		check (length(label) > 0)
+);
+
+-- Votes are kept after the voter has left, so there is no foreign key to the user.
+CREATE TABLE poll_vote
+(
+	poll_uuid       blob                               not null,
+	voter_user_uuid blob                               not null,
+	option_index    integer                            not null,
+	voted_at        datetime default current_timestamp not null,
+	constraint poll_vote_pk
+		primary key (poll_uuid, voter_user_uuid),
+	constraint poll_vote_option__fk
+		foreign key (poll_uuid, option_index) references poll_option (poll_uuid, option_index)
+			on delete cascade
+);
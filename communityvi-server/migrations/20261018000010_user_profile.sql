@@ -0,0 +1,6 @@
+ALTER TABLE user ADD COLUMN presence text not null default 'active'
+	constraint check_presence
+		check (presence in ('active', 'away', 'do_not_disturb'));
+ALTER TABLE user ADD COLUMN color text;
+ALTER TABLE user ADD COLUMN avatar text;
+ALTER TABLE user ADD COLUMN pronouns text;
@@ -0,0 +1,36 @@
+use crate::types::date_time::DateTime;
+use crate::types::uuid::Uuid;
+use chrono::Duration;
+
+/// A named position in a medium, see [`crate::room::medium::Medium::id`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bookmark {
+	pub uuid: Uuid,
+	pub room_uuid: Uuid,
+	pub medium_id: String,
+	pub position: Duration,
+	pub label: String,
+	pub created_at: DateTime,
+}
+
+impl TryFrom<libsql::Row> for Bookmark {
+	type Error = anyhow::Error;
+
+	fn try_from(row: libsql::Row) -> Result<Self, Self::Error> {
+		let uuid = row.get_value(0)?;
+		let room_uuid = row.get_value(1)?;
+		let medium_id = row.get(2)?;
+		let position_ms = row.get(3)?;
+		let label = row.get(4)?;
+		let created_at = row.get_value(5)?;
+
+		Ok(Self {
+			uuid: uuid.try_into()?,
+			room_uuid: room_uuid.try_into()?,
+			medium_id,
+			position: Duration::milliseconds(position_ms),
+			label,
+			created_at: created_at.try_into()?,
+		})
+	}
+}
@@ -0,0 +1,40 @@
+use crate::bookmark::model;
+use crate::database::Connection;
+use crate::database::error::DatabaseError;
+use crate::types::date_time::DateTime;
+use crate::types::uuid::Uuid;
+use async_trait::async_trait;
+use chrono::Duration;
+use static_assertions::assert_obj_safe;
+
+#[cfg(test)]
+mod tests;
+
+#[async_trait]
+pub trait BookmarkRepository: Send + Sync + 'static {
+	async fn create(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		medium_id: &str,
+		position: Duration,
+		label: &str,
+		created_at: DateTime,
+	) -> Result<model::Bookmark, DatabaseError>;
+	/// Returns `false` if there was no such bookmark in the room.
+	async fn remove(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		bookmark_uuid: Uuid,
+	) -> Result<bool, DatabaseError>;
+	/// All bookmarks of a medium in the room, ordered by position.
+	async fn get_all_for_medium(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		medium_id: &str,
+	) -> Result<Vec<model::Bookmark>, DatabaseError>;
+}
+
+assert_obj_safe!(BookmarkRepository);
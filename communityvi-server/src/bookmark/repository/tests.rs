@@ -0,0 +1,161 @@
+#[generic_tests::define(attrs(tokio::test))]
+mod bookmark_tests {
+	use crate::bookmark::model::Bookmark;
+	use crate::database::error::DatabaseError;
+	use crate::database::libsql::test_utils::LibSqlTestFactory;
+	use crate::database::test::TestFactory;
+	use crate::database::{Connection, Repository};
+	use crate::room::model::Room;
+	use chrono::{Duration, Utc};
+
+	#[tokio::test]
+	async fn creates_bookmark<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let room = create_room(repository.as_ref(), &mut *connection, "lobby").await;
+		let created_at = Utc::now().into();
+
+		let Bookmark {
+			uuid,
+			room_uuid,
+			medium_id,
+			position,
+			label,
+			created_at: bookmark_created_at,
+		} = repository
+			.bookmark()
+			.create(
+				&mut *connection,
+				room.uuid,
+				"live:Rocket Launch",
+				Duration::milliseconds(1337),
+				"Liftoff",
+				created_at,
+			)
+			.await
+			.expect("Failed to create bookmark");
+
+		assert_eq!(4, uuid.get_version_num());
+		assert_eq!(room.uuid, room_uuid);
+		assert_eq!("live:Rocket Launch", medium_id);
+		assert_eq!(Duration::milliseconds(1337), position);
+		assert_eq!("Liftoff", label);
+		assert_eq!(created_at.timestamp(), bookmark_created_at.timestamp());
+	}
+
+	#[tokio::test]
+	async fn rejects_empty_label<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let room = create_room(repository.as_ref(), &mut *connection, "general").await;
+
+		let result = repository
+			.bookmark()
+			.create(
+				&mut *connection,
+				room.uuid,
+				"live:Rocket Launch",
+				Duration::zero(),
+				"",
+				Utc::now().into(),
+			)
+			.await;
+
+		match result {
+			Err(DatabaseError::OtherConstraintViolation(_)) => { /* ok */ }
+			Ok(_) => panic!("Expected constraint violation when creating bookmark without label"),
+			Err(err) => panic!("Unexpected error: {err:?}"),
+		}
+	}
+
+	#[tokio::test]
+	async fn gets_bookmarks_of_medium_ordered_by_position<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let room = create_room(repository.as_ref(), &mut *connection, "cinema").await;
+		for (medium_id, position, label) in [
+			("url:https://example.com/metropolis.webm", 60_000, "Tower of Babel"),
+			("url:https://example.com/metropolis.webm", 1_000, "Intro ends"),
+			("live:Rocket Launch", 2_000, "Liftoff"),
+		] {
+			repository
+				.bookmark()
+				.create(
+					&mut *connection,
+					room.uuid,
+					medium_id,
+					Duration::milliseconds(position),
+					label,
+					Utc::now().into(),
+				)
+				.await
+				.expect("Failed to create bookmark");
+		}
+
+		let labels = repository
+			.bookmark()
+			.get_all_for_medium(&mut *connection, room.uuid, "url:https://example.com/metropolis.webm")
+			.await
+			.expect("Failed to get bookmarks")
+			.into_iter()
+			.map(|bookmark| bookmark.label)
+			.collect::<Vec<_>>();
+
+		assert_eq!(vec!["Intro ends", "Tower of Babel"], labels);
+	}
+
+	#[tokio::test]
+	async fn removes_bookmark_only_in_its_room<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let room = create_room(repository.as_ref(), &mut *connection, "cinema").await;
+		let other_room = create_room(repository.as_ref(), &mut *connection, "lobby").await;
+		let bookmark = repository
+			.bookmark()
+			.create(
+				&mut *connection,
+				room.uuid,
+				"live:Rocket Launch",
+				Duration::zero(),
+				"Countdown",
+				Utc::now().into(),
+			)
+			.await
+			.expect("Failed to create bookmark");
+
+		let removed_from_other_room = repository
+			.bookmark()
+			.remove(&mut *connection, other_room.uuid, bookmark.uuid)
+			.await
+			.expect("Failed to remove bookmark");
+		let removed = repository
+			.bookmark()
+			.remove(&mut *connection, room.uuid, bookmark.uuid)
+			.await
+			.expect("Failed to remove bookmark");
+
+		assert!(!removed_from_other_room);
+		assert!(removed);
+		let bookmarks = repository
+			.bookmark()
+			.get_all_for_medium(&mut *connection, room.uuid, "live:Rocket Launch")
+			.await
+			.expect("Failed to get bookmarks");
+		assert!(bookmarks.is_empty());
+	}
+
+	async fn create_room(repository: &dyn Repository, connection: &mut dyn Connection, name: &str) -> Room {
+		repository
+			.room()
+			.create(connection, name)
+			.await
+			.expect("Failed to create room")
+	}
+
+	#[instantiate_tests(<LibSqlTestFactory>)]
+	mod libsql {}
+}
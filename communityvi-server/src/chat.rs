@@ -1,2 +1,5 @@
+pub mod command;
+pub mod filter;
+pub mod mention;
 pub mod model;
 pub mod repository;
@@ -0,0 +1,394 @@
+use crate::message::outgoing::broadcast_message::{
+	BroadcastMessage, EmoteBroadcast, MediumStateChangedBroadcast, VersionedMediumBroadcast,
+};
+use crate::room::Room;
+use crate::room::client::Client;
+use crate::room::error::RoomError;
+use async_trait::async_trait;
+use chrono::Duration;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use tracing::error;
+
+/// Chat messages starting with this are commands, unless it is doubled to send a literal `/`.
+const COMMAND_PREFIX: char = '/';
+const MAXIMUM_DICE: u32 = 100;
+const MAXIMUM_SIDES: u32 = 1000;
+
+/// A chat message as typed by a client, either a command like `/roll 2d6` or a message for everyone.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChatInput<'message> {
+	Command {
+		name: &'message str,
+		arguments: &'message str,
+	},
+	Message(&'message str),
+}
+
+impl<'message> ChatInput<'message> {
+	pub fn parse(message: &'message str) -> Self {
+		let Some(command) = message.strip_prefix(COMMAND_PREFIX) else {
+			return Self::Message(message);
+		};
+		if command.starts_with(COMMAND_PREFIX) {
+			return Self::Message(command);
+		}
+
+		let (name, arguments) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+		Self::Command {
+			name,
+			arguments: arguments.trim(),
+		}
+	}
+}
+
+pub struct CommandContext<'a> {
+	pub room: &'a Room,
+	pub client: &'a Client,
+	pub commands: &'a CommandRegistry,
+}
+
+/// What happens after a command ran successfully.
+#[derive(Debug)]
+pub enum CommandOutput {
+	/// Only sent back to the client that used the command.
+	Reply(String),
+	/// Sent to everyone in the room.
+	Broadcast(BroadcastMessage),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommandError {
+	#[error("Unknown command '/{0}', see /help for a list of commands.")]
+	Unknown(String),
+	#[error("Usage: {0}")]
+	Usage(&'static str),
+	#[error("Only moderators may use /{0}.")]
+	NotAllowed(&'static str),
+	#[error("{0}")]
+	Failed(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Permission {
+	Everyone,
+	Moderator,
+}
+
+impl Permission {
+	fn allows(self, context: &CommandContext<'_>) -> bool {
+		match self {
+			Permission::Everyone => true,
+			Permission::Moderator => context.client.is_moderator(),
+		}
+	}
+}
+
+#[async_trait]
+pub trait CommandHandler: Send + Sync {
+	async fn handle(&self, context: &CommandContext<'_>, arguments: &str) -> Result<CommandOutput, CommandError>;
+}
+
+struct Command {
+	name: &'static str,
+	usage: &'static str,
+	description: &'static str,
+	permission: Permission,
+	handler: Box<dyn CommandHandler>,
+}
+
+/// Commands by their name, dispatched after checking the permission of the client that used them.
+#[derive(Default)]
+pub struct CommandRegistry {
+	commands: BTreeMap<&'static str, Command>,
+}
+
+impl CommandRegistry {
+	pub fn with_built_in_commands() -> Self {
+		let mut registry = Self::default();
+		registry.register(
+			"help",
+			"/help",
+			"List the commands you can use.",
+			Permission::Everyone,
+			Help,
+		);
+		registry.register(
+			"me",
+			"/me <action>",
+			"Describe what you are doing.",
+			Permission::Everyone,
+			Me,
+		);
+		registry.register("nick", "/nick <name>", "Change your name.", Permission::Everyone, Nick);
+		registry.register(
+			"roll",
+			"/roll [<count>d<sides>]",
+			"Roll some dice, 1d6 by default.",
+			Permission::Everyone,
+			Roll,
+		);
+		registry.register(
+			"seek",
+			"/seek <[[hh:]mm:]ss>",
+			"Jump to a position in the medium.",
+			Permission::Everyone,
+			Seek,
+		);
+		registry.register(
+			"kick",
+			"/kick <name>",
+			"Remove a client from the room.",
+			Permission::Moderator,
+			Kick,
+		);
+		registry
+	}
+
+	/// Registers `handler` for `/name`, replacing any command that was registered with the same name.
+	pub fn register(
+		&mut self,
+		name: &'static str,
+		usage: &'static str,
+		description: &'static str,
+		permission: Permission,
+		handler: impl CommandHandler + 'static,
+	) {
+		self.commands.insert(
+			name,
+			Command {
+				name,
+				usage,
+				description,
+				permission,
+				handler: Box::new(handler),
+			},
+		);
+	}
+
+	pub async fn execute(
+		&self,
+		context: &CommandContext<'_>,
+		name: &str,
+		arguments: &str,
+	) -> Result<CommandOutput, CommandError> {
+		let command = self
+			.commands
+			.get(name)
+			.ok_or_else(|| CommandError::Unknown(name.to_string()))?;
+		if !command.permission.allows(context) {
+			return Err(CommandError::NotAllowed(command.name));
+		}
+
+		command.handler.handle(context, arguments).await
+	}
+
+	fn usage(&self, name: &str) -> &'static str {
+		self.commands.get(name).map_or("", |command| command.usage)
+	}
+}
+
+struct Help;
+
+#[async_trait]
+impl CommandHandler for Help {
+	async fn handle(&self, context: &CommandContext<'_>, _arguments: &str) -> Result<CommandOutput, CommandError> {
+		let mut help = String::from("Commands:");
+		for command in context.commands.commands.values() {
+			if command.permission.allows(context) {
+				write!(help, "\n{} - {}", command.usage, command.description).expect("Writing to String can't fail");
+			}
+		}
+		Ok(CommandOutput::Reply(help))
+	}
+}
+
+struct Me;
+
+#[async_trait]
+impl CommandHandler for Me {
+	async fn handle(&self, context: &CommandContext<'_>, arguments: &str) -> Result<CommandOutput, CommandError> {
+		if arguments.is_empty() {
+			return Err(CommandError::Usage(context.commands.usage("me")));
+		}
+		Ok(emote(context.client, arguments.to_string()))
+	}
+}
+
+struct Nick;
+
+#[async_trait]
+impl CommandHandler for Nick {
+	async fn handle(&self, context: &CommandContext<'_>, arguments: &str) -> Result<CommandOutput, CommandError> {
+		if arguments.is_empty() {
+			return Err(CommandError::Usage(context.commands.usage("nick")));
+		}
+		if arguments == context.client.name() {
+			return Ok(CommandOutput::Reply(format!("You are already called {arguments}.")));
+		}
+
+		let broadcast = context
+			.room
+			.rename_client(context.client, arguments)
+			.await
+			.map_err(|error| match error {
+				RoomError::EmptyClientName
+				| RoomError::ClientNameTooLong
+				| RoomError::ClientNameAlreadyInUse
+				| RoomError::ClientNameReserved => CommandError::Failed(error.to_string()),
+				_ => {
+					error!("Failed to rename client: {error}");
+					CommandError::Failed("Failed to change your name.".to_string())
+				}
+			})?;
+		Ok(CommandOutput::Broadcast(broadcast.into()))
+	}
+}
+
+struct Roll;
+
+#[async_trait]
+impl CommandHandler for Roll {
+	async fn handle(&self, context: &CommandContext<'_>, arguments: &str) -> Result<CommandOutput, CommandError> {
+		let (count, sides) = parse_dice(arguments).ok_or(CommandError::Usage(context.commands.usage("roll")))?;
+		let rolls = (0..count).map(|_| rand::random_range(1..=sides)).collect::<Vec<u32>>();
+		let total = rolls.iter().sum::<u32>();
+		let rolls = rolls.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+		Ok(emote(
+			context.client,
+			format!("rolls {count}d{sides} and gets {total} ({rolls})"),
+		))
+	}
+}
+
+struct Seek;
+
+#[async_trait]
+impl CommandHandler for Seek {
+	async fn handle(&self, context: &CommandContext<'_>, arguments: &str) -> Result<CommandOutput, CommandError> {
+		let position = parse_position(arguments).ok_or(CommandError::Usage(context.commands.usage("seek")))?;
+		let CommandContext { room, client, .. } = context;
+
+		let versioned_medium = room
+			.seek_medium(position)
+			.map_err(|error| CommandError::Failed(error.to_string()))?;
+		Ok(CommandOutput::Broadcast(
+			MediumStateChangedBroadcast {
+				changed_by_name: client.name(),
+				changed_by_id: Some(client.id()),
+				medium: VersionedMediumBroadcast::new(versioned_medium, true),
+			}
+			.into(),
+		))
+	}
+}
+
+struct Kick;
+
+#[async_trait]
+impl CommandHandler for Kick {
+	async fn handle(&self, context: &CommandContext<'_>, arguments: &str) -> Result<CommandOutput, CommandError> {
+		if arguments.is_empty() {
+			return Err(CommandError::Usage(context.commands.usage("kick")));
+		}
+		let Some(client) = context.room.client_by_name(arguments).await else {
+			return Err(CommandError::Failed(format!("There is no client named '{arguments}'.")));
+		};
+		if client.id() == context.client.id() {
+			return Err(CommandError::Failed("You can't kick yourself.".to_string()));
+		}
+
+		client.kick();
+		Ok(CommandOutput::Reply(format!("Kicked {}.", client.name())))
+	}
+}
+
+fn emote(client: &Client, action: String) -> CommandOutput {
+	CommandOutput::Broadcast(
+		EmoteBroadcast {
+			sender_id: client.id(),
+			sender_name: client.name(),
+			action,
+		}
+		.into(),
+	)
+}
+
+/// Parses dice notation like `2d6`, defaulting to a single six-sided die.
+fn parse_dice(dice: &str) -> Option<(u32, u32)> {
+	if dice.is_empty() {
+		return Some((1, 6));
+	}
+
+	let (count, sides) = dice.split_once(['d', 'D'])?;
+	let count = if count.is_empty() { 1 } else { count.parse().ok()? };
+	let sides = sides.parse().ok()?;
+	((1..=MAXIMUM_DICE).contains(&count) && (2..=MAXIMUM_SIDES).contains(&sides)).then_some((count, sides))
+}
+
+/// Parses positions like `90`, `1:30` or `1:01:30`.
+fn parse_position(position: &str) -> Option<Duration> {
+	let mut components = position.rsplit(':');
+	let seconds = components.next()?.parse::<u32>().ok()?;
+	let minutes = components
+		.next()
+		.map_or(Some(0), |minutes| minutes.parse::<u32>().ok())?;
+	let hours = components.next().map_or(Some(0), |hours| hours.parse::<u32>().ok())?;
+	if components.next().is_some() || (position.contains(':') && seconds >= 60) || (hours > 0 && minutes >= 60) {
+		return None;
+	}
+
+	Some(Duration::hours(hours.into()) + Duration::minutes(minutes.into()) + Duration::seconds(seconds.into()))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn should_parse_commands_and_messages() {
+		assert_eq!(ChatInput::Message("Hello"), ChatInput::parse("Hello"));
+		assert_eq!(
+			ChatInput::Command {
+				name: "me",
+				arguments: "waves at everyone"
+			},
+			ChatInput::parse("/me  waves at everyone ")
+		);
+		assert_eq!(
+			ChatInput::Command {
+				name: "help",
+				arguments: ""
+			},
+			ChatInput::parse("/help")
+		);
+		assert_eq!(
+			ChatInput::Message("/me is not a command"),
+			ChatInput::parse("//me is not a command")
+		);
+	}
+
+	#[test]
+	fn should_parse_dice() {
+		assert_eq!(Some((1, 6)), parse_dice(""));
+		assert_eq!(Some((2, 20)), parse_dice("2d20"));
+		assert_eq!(Some((1, 4)), parse_dice("d4"));
+		assert_eq!(None, parse_dice("0d6"));
+		assert_eq!(None, parse_dice("1d1"));
+		assert_eq!(None, parse_dice("1000d6"));
+		assert_eq!(None, parse_dice("many"));
+	}
+
+	#[test]
+	fn should_parse_positions() {
+		assert_eq!(Some(Duration::seconds(90)), parse_position("90"));
+		assert_eq!(Some(Duration::seconds(12 * 60 + 30)), parse_position("12:30"));
+		assert_eq!(Some(Duration::seconds(3600 + 60 + 30)), parse_position("1:01:30"));
+		assert_eq!(Some(Duration::seconds(90 * 60)), parse_position("90:00"));
+		assert_eq!(None, parse_position("12:60"));
+		assert_eq!(None, parse_position("1:60:00"));
+		assert_eq!(None, parse_position("1:2:3:4"));
+		assert_eq!(None, parse_position("-5"));
+		assert_eq!(None, parse_position(""));
+	}
+}
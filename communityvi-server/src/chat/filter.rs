@@ -0,0 +1,454 @@
+use crate::room::client::Client;
+use crate::user::normalize_name;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::num::{NonZeroU32, NonZeroUsize};
+
+const LINK_PREFIXES: [&str; 3] = ["http://", "https://", "www."];
+const LINK_REPLACEMENT: &str = "[link removed]";
+
+/// One stage of the chat filter pipeline, see `chat_filters` in the configuration.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct ChatFilterConfiguration {
+	#[serde(flatten)]
+	pub filter: ChatFilterKind,
+	pub action: FilterAction,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum ChatFilterKind {
+	WordList {
+		words: WordList,
+	},
+	Links,
+	/// Too many messages within a minute or too many identical messages in a row.
+	Flood {
+		messages_per_minute: NonZeroU32,
+		repeated_message_limit: NonZeroU32,
+	},
+	LineLimit {
+		maximum_lines: NonZeroUsize,
+	},
+}
+
+/// What happens to messages that violate a filter.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterAction {
+	Reject,
+	/// Masks the offending parts of the message. Violations that can't be masked, like floods, are rejected.
+	Mask,
+	/// Delivers the message, but notifies moderators about it.
+	Flag,
+}
+
+/// Words that are compared after normalizing them with [`normalize_word`].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(from = "Vec<String>")]
+pub struct WordList(BTreeSet<String>);
+
+impl From<Vec<String>> for WordList {
+	fn from(words: Vec<String>) -> Self {
+		Self(words.iter().map(|word| normalize_word(word)).collect())
+	}
+}
+
+/// Like [`normalize_name`], but ignoring case so word lists don't have to contain every spelling.
+fn normalize_word(word: &str) -> String {
+	normalize_name(&word.to_lowercase()).to_lowercase()
+}
+
+pub trait ChatFilter {
+	/// Used to tell clients and moderators which filter a message violated.
+	fn name(&self) -> &'static str;
+
+	/// Returns `None` if `message` doesn't violate the filter, otherwise the masked message, if masking is possible.
+	fn check(&self, sender: &Client, message: &str) -> Option<Violation>;
+}
+
+pub struct Violation {
+	pub masked_message: Option<String>,
+}
+
+impl ChatFilterKind {
+	pub fn chat_filter(&self) -> Box<dyn ChatFilter + '_> {
+		match self {
+			ChatFilterKind::WordList { words } => Box::new(WordListFilter { words }),
+			ChatFilterKind::Links => Box::new(LinkFilter),
+			ChatFilterKind::Flood {
+				repeated_message_limit, ..
+			} => Box::new(FloodFilter {
+				repeated_message_limit: *repeated_message_limit,
+			}),
+			ChatFilterKind::LineLimit { maximum_lines } => Box::new(LineLimitFilter {
+				maximum_lines: *maximum_lines,
+			}),
+		}
+	}
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FilteredChatMessage {
+	pub message: String,
+	/// Names of the filters that flagged the message to moderators.
+	pub flagged_by: Vec<&'static str>,
+}
+
+#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq)]
+#[error("Chat message was rejected by the {0} filter.")]
+pub struct ChatFilterRejection(pub &'static str);
+
+/// Runs `message` through all `filters` in order, so later filters see the masked message of earlier ones.
+pub fn filter_chat_message<'filters>(
+	filters: impl IntoIterator<Item = &'filters ChatFilterConfiguration>,
+	sender: &Client,
+	message: String,
+) -> Result<FilteredChatMessage, ChatFilterRejection> {
+	let mut filtered_message = FilteredChatMessage {
+		message,
+		flagged_by: Vec::new(),
+	};
+	for ChatFilterConfiguration { filter, action } in filters {
+		let filter = filter.chat_filter();
+		let Some(violation) = filter.check(sender, &filtered_message.message) else {
+			continue;
+		};
+
+		match (action, violation.masked_message) {
+			(FilterAction::Reject, _) | (FilterAction::Mask, None) => return Err(ChatFilterRejection(filter.name())),
+			(FilterAction::Mask, Some(masked_message)) => filtered_message.message = masked_message,
+			(FilterAction::Flag, _) => filtered_message.flagged_by.push(filter.name()),
+		}
+	}
+	Ok(filtered_message)
+}
+
+/// Like [`filter_chat_message`], but for edits of existing messages. Edits don't send a new message,
+/// so the flood filter neither counts them nor limits them.
+pub fn filter_edited_chat_message(
+	filters: &[ChatFilterConfiguration],
+	sender: &Client,
+	message: String,
+) -> Result<FilteredChatMessage, ChatFilterRejection> {
+	let filters = filters
+		.iter()
+		.filter(|configuration| !matches!(configuration.filter, ChatFilterKind::Flood { .. }));
+	filter_chat_message(filters, sender, message)
+}
+
+struct WordListFilter<'words> {
+	words: &'words WordList,
+}
+
+impl ChatFilter for WordListFilter<'_> {
+	fn name(&self) -> &'static str {
+		"word list"
+	}
+
+	fn check(&self, _sender: &Client, message: &str) -> Option<Violation> {
+		let mut violated = false;
+		let masked_message = replace_tokens(
+			message,
+			|character| !character.is_alphanumeric(),
+			|word| {
+				let is_listed = self.words.0.contains(&normalize_word(word));
+				violated |= is_listed;
+				is_listed.then(|| "*".repeat(word.chars().count()))
+			},
+		);
+		violated.then_some(Violation {
+			masked_message: Some(masked_message),
+		})
+	}
+}
+
+struct LinkFilter;
+
+impl ChatFilter for LinkFilter {
+	fn name(&self) -> &'static str {
+		"link"
+	}
+
+	fn check(&self, _sender: &Client, message: &str) -> Option<Violation> {
+		let mut violated = false;
+		let masked_message = replace_tokens(message, char::is_whitespace, |token| {
+			let is_link = LINK_PREFIXES.iter().any(|prefix| {
+				token
+					.get(..prefix.len())
+					.is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+			});
+			violated |= is_link;
+			is_link.then(|| LINK_REPLACEMENT.to_string())
+		});
+		violated.then_some(Violation {
+			masked_message: Some(masked_message),
+		})
+	}
+}
+
+/// The messages per minute are limited by the rate limiter of each client, see [`MessageQuotas`].
+///
+/// [`MessageQuotas`]: crate::room::client::MessageQuotas
+struct FloodFilter {
+	repeated_message_limit: NonZeroU32,
+}
+
+impl ChatFilter for FloodFilter {
+	fn name(&self) -> &'static str {
+		"flood"
+	}
+
+	fn check(&self, sender: &Client, message: &str) -> Option<Violation> {
+		let repetitions = sender.count_chat_message_repetitions(&normalize_word(message));
+		let within_quota = sender.check_chat_message_quota();
+		(!within_quota || repetitions > self.repeated_message_limit.get()).then_some(Violation { masked_message: None })
+	}
+}
+
+struct LineLimitFilter {
+	maximum_lines: NonZeroUsize,
+}
+
+impl ChatFilter for LineLimitFilter {
+	fn name(&self) -> &'static str {
+		"line limit"
+	}
+
+	fn check(&self, _sender: &Client, message: &str) -> Option<Violation> {
+		(message.lines().count() > self.maximum_lines.get()).then(|| Violation {
+			masked_message: Some(
+				message
+					.lines()
+					.take(self.maximum_lines.get())
+					.collect::<Vec<_>>()
+					.join("\n"),
+			),
+		})
+	}
+}
+
+/// Replaces the tokens between `is_separator` characters for which `replace` returns a replacement.
+fn replace_tokens(
+	message: &str,
+	is_separator: impl Fn(char) -> bool,
+	mut replace: impl FnMut(&str) -> Option<String>,
+) -> String {
+	let mut replaced = String::with_capacity(message.len());
+	for token_with_separator in message.split_inclusive(&is_separator) {
+		let separator_length = token_with_separator
+			.chars()
+			.next_back()
+			.filter(|&character| is_separator(character))
+			.map_or(0, char::len_utf8);
+		let (token, separator) = token_with_separator.split_at(token_with_separator.len() - separator_length);
+		match replace(token) {
+			Some(replacement) if !token.is_empty() => replaced.push_str(&replacement),
+			_ => replaced.push_str(token),
+		}
+		replaced.push_str(separator);
+	}
+	replaced
+}
+
+#[cfg(test)]
+#[allow(clippy::non_ascii_literal)]
+mod test {
+	use super::*;
+	use crate::configuration::Configuration;
+	use crate::connection::broadcast_buffer::BroadcastBuffer;
+	use crate::room::client::MessageQuotas;
+	use crate::room::session_id::SessionId;
+	use crate::types::uuid::Uuid;
+	use crate::user::model::{Profile, User};
+	use crate::utils::fake_message_sender::FakeMessageSender;
+
+	#[test]
+	fn word_lists_should_match_normalized_words() {
+		let filters = [filter(
+			ChatFilterKind::WordList {
+				words: vec!["darn".to_string()].into(),
+			},
+			FilterAction::Mask,
+		)];
+
+		let filtered = filter_chat_message(&filters, &client(), "Darn it, dаrn! darned".to_string())
+			.expect("Masking must not reject");
+
+		assert_eq!("**** it, ****! darned", filtered.message);
+	}
+
+	#[test]
+	fn links_should_be_stripped_or_blocked() {
+		let message = "Look at https://example.com/video and WWW.example.org";
+		let mask = [filter(ChatFilterKind::Links, FilterAction::Mask)];
+		let reject = [filter(ChatFilterKind::Links, FilterAction::Reject)];
+
+		let filtered = filter_chat_message(&mask, &client(), message.to_string()).expect("Masking must not reject");
+		assert_eq!("Look at [link removed] and [link removed]", filtered.message);
+		assert_eq!(
+			Err(ChatFilterRejection("link")),
+			filter_chat_message(&reject, &client(), message.to_string())
+		);
+		assert!(filter_chat_message(&reject, &client(), "No links here".to_string()).is_ok());
+	}
+
+	#[test]
+	fn flood_filter_should_reject_repeated_messages() {
+		let filters = [filter(
+			ChatFilterKind::Flood {
+				messages_per_minute: NonZeroU32::new(10).unwrap(),
+				repeated_message_limit: NonZeroU32::new(2).unwrap(),
+			},
+			FilterAction::Mask,
+		)];
+		let client = client_with_filters(&filters);
+
+		assert!(filter_chat_message(&filters, &client, "Hello".to_string()).is_ok());
+		assert!(filter_chat_message(&filters, &client, "hello".to_string()).is_ok());
+		assert_eq!(
+			Err(ChatFilterRejection("flood")),
+			filter_chat_message(&filters, &client, "HELLO".to_string())
+		);
+		assert!(filter_chat_message(&filters, &client, "Bye".to_string()).is_ok());
+	}
+
+	#[test]
+	fn flood_filter_should_reject_too_many_messages() {
+		let filters = [filter(
+			ChatFilterKind::Flood {
+				messages_per_minute: NonZeroU32::new(2).unwrap(),
+				repeated_message_limit: NonZeroU32::new(10).unwrap(),
+			},
+			FilterAction::Reject,
+		)];
+		let client = client_with_filters(&filters);
+
+		assert!(filter_chat_message(&filters, &client, "One".to_string()).is_ok());
+		assert!(filter_chat_message(&filters, &client, "Two".to_string()).is_ok());
+		assert_eq!(
+			Err(ChatFilterRejection("flood")),
+			filter_chat_message(&filters, &client, "Three".to_string())
+		);
+	}
+
+	#[test]
+	fn flood_filter_should_neither_count_nor_limit_edits() {
+		let filters = [filter(
+			ChatFilterKind::Flood {
+				messages_per_minute: NonZeroU32::new(1).unwrap(),
+				repeated_message_limit: NonZeroU32::new(1).unwrap(),
+			},
+			FilterAction::Reject,
+		)];
+		let client = client_with_filters(&filters);
+
+		assert!(filter_chat_message(&filters, &client, "Hello".to_string()).is_ok());
+		assert!(filter_edited_chat_message(&filters, &client, "Hello".to_string()).is_ok());
+		assert!(filter_edited_chat_message(&filters, &client, "Hello".to_string()).is_ok());
+		assert_eq!(
+			Err(ChatFilterRejection("flood")),
+			filter_chat_message(&filters, &client, "Bye".to_string())
+		);
+	}
+
+	#[test]
+	fn line_limit_should_truncate_or_flag_messages() {
+		let message = "One\nTwo\nThree";
+		let mask = [filter(
+			ChatFilterKind::LineLimit {
+				maximum_lines: NonZeroUsize::new(2).unwrap(),
+			},
+			FilterAction::Mask,
+		)];
+		let flag = [filter(
+			ChatFilterKind::LineLimit {
+				maximum_lines: NonZeroUsize::new(2).unwrap(),
+			},
+			FilterAction::Flag,
+		)];
+
+		let masked = filter_chat_message(&mask, &client(), message.to_string()).expect("Masking must not reject");
+		assert_eq!("One\nTwo", masked.message);
+		assert_eq!(
+			FilteredChatMessage {
+				message: message.to_string(),
+				flagged_by: vec!["line limit"],
+			},
+			filter_chat_message(&flag, &client(), message.to_string()).expect("Flagging must not reject")
+		);
+	}
+
+	#[test]
+	fn should_deserialize_filter_configuration() {
+		#[derive(Deserialize)]
+		struct Filters {
+			chat_filters: Vec<ChatFilterConfiguration>,
+		}
+
+		let Filters { chat_filters } = toml::from_str(
+			r#"
+			[[chat_filters]]
+			type = "word_list"
+			words = ["Darn"]
+			action = "mask"
+
+			[[chat_filters]]
+			type = "flood"
+			messages_per_minute = 20
+			repeated_message_limit = 3
+			action = "reject"
+			"#,
+		)
+		.expect("Failed to deserialize chat filters");
+
+		assert_eq!(
+			vec![
+				filter(
+					ChatFilterKind::WordList {
+						words: vec!["darn".to_string()].into(),
+					},
+					FilterAction::Mask
+				),
+				filter(
+					ChatFilterKind::Flood {
+						messages_per_minute: NonZeroU32::new(20).unwrap(),
+						repeated_message_limit: NonZeroU32::new(3).unwrap(),
+					},
+					FilterAction::Reject
+				),
+			],
+			chat_filters
+		);
+	}
+
+	fn filter(filter: ChatFilterKind, action: FilterAction) -> ChatFilterConfiguration {
+		ChatFilterConfiguration { filter, action }
+	}
+
+	fn client() -> Client {
+		client_with_filters(&[])
+	}
+
+	fn client_with_filters(chat_filters: &[ChatFilterConfiguration]) -> Client {
+		let configuration = Configuration {
+			chat_filters: chat_filters.to_vec(),
+			..Configuration::test()
+		};
+		let user = User {
+			uuid: Uuid::new_v4(),
+			name: "Alice".to_string(),
+			normalized_name: normalize_name("Alice"),
+			profile: Profile::default(),
+		};
+		Client::new(
+			SessionId::from(0),
+			user,
+			false,
+			MessageQuotas::from(&configuration),
+			BroadcastBuffer::new(1),
+			FakeMessageSender::default().into(),
+		)
+	}
+}
@@ -0,0 +1,122 @@
+use crate::user::normalize_name;
+use std::collections::BTreeSet;
+use std::ops::Bound;
+
+/// Names are limited to 256 bytes, see [`crate::user::UserCreationError::NameTooLong`].
+const NAME_LENGTH_LIMIT: usize = 256;
+
+/// Finds `@name` mentions of `normalized_names` in `message`, comparing names the same way as
+/// [`normalize_name`]. If several names match after the same `@`, the longest one wins,
+/// so `@Alice Cooper` mentions "Alice Cooper" and not "Alice".
+pub fn find_mentions<'name>(message: &str, normalized_names: &'name BTreeSet<String>) -> BTreeSet<&'name str> {
+	let mut mentions = BTreeSet::new();
+	let mut previous_character = None;
+	for (index, character) in message.char_indices() {
+		let is_mention_start = character == '@' && previous_character.is_none_or(char::is_whitespace);
+		previous_character = Some(character);
+		if !is_mention_start {
+			continue;
+		}
+
+		let rest = &message[index + character.len_utf8()..];
+		mentions.extend(longest_mention(rest, normalized_names).map(String::as_str));
+	}
+	mentions
+}
+
+/// Normalizes `text` segment by segment, where segments end before characters that can't continue a name,
+/// and stops as soon as no name starts with what was normalized so far.
+fn longest_mention<'name>(text: &str, normalized_names: &'name BTreeSet<String>) -> Option<&'name String> {
+	let mut longest_mention = None;
+	let mut normalized_candidate = String::new();
+	let mut segment_start = 0;
+	let mut characters = text.char_indices().peekable();
+	while let Some((index, character)) = characters.next() {
+		let length = index + character.len_utf8();
+		if length > NAME_LENGTH_LIMIT {
+			break;
+		}
+		if characters
+			.peek()
+			.is_some_and(|(_, next_character)| next_character.is_alphanumeric())
+		{
+			continue;
+		}
+
+		normalized_candidate.push_str(&normalize_name(&text[segment_start..length]));
+		segment_start = length;
+		let Some(next_name) = normalized_names
+			.range::<str, _>((Bound::Included(normalized_candidate.as_str()), Bound::Unbounded))
+			.next()
+		else {
+			break;
+		};
+		if !next_name.starts_with(&normalized_candidate) {
+			break;
+		}
+		if *next_name == normalized_candidate {
+			longest_mention = Some(next_name);
+		}
+	}
+	longest_mention
+}
+
+#[cfg(test)]
+#[allow(clippy::non_ascii_literal)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn should_find_mentions_of_known_names() {
+		let names = normalized_names(&["Alice", "Bob", "Carol"]);
+
+		let mentions = find_mentions("@Alice and @Bob, have you seen Carol?", &names);
+
+		assert_eq!(
+			BTreeSet::from([normalize_name("Alice").as_str(), normalize_name("Bob").as_str()]),
+			mentions
+		);
+	}
+
+	#[test]
+	fn should_prefer_the_longest_matching_name() {
+		let names = normalized_names(&["Alice", "Alice Cooper"]);
+
+		let mentions = find_mentions("Welcome @Alice Cooper!", &names);
+
+		assert_eq!(BTreeSet::from([normalize_name("Alice Cooper").as_str()]), mentions);
+	}
+
+	#[test]
+	fn should_not_find_mentions_inside_words_or_names() {
+		let names = normalized_names(&["Alice", "example"]);
+
+		let mentions = find_mentions("Write to mail@example or @Alicia", &names);
+
+		assert!(mentions.is_empty());
+	}
+
+	#[test]
+	fn should_find_mentions_of_names_with_confusable_characters() {
+		let names = normalized_names(&["Ferris"]);
+
+		// Cyrillic 'е'
+		let mentions = find_mentions("@Fеrris 🦀", &names);
+
+		assert_eq!(BTreeSet::from([normalize_name("Ferris").as_str()]), mentions);
+	}
+
+	#[test]
+	fn should_find_mentions_in_long_messages() {
+		let names = normalized_names(&["Alice", "Bob"]);
+		let message = format!("{}@Bob", "@Alic ".repeat(10_000));
+
+		let mentions = find_mentions(&message, &names);
+
+		assert_eq!(BTreeSet::from([normalize_name("Bob").as_str()]), mentions);
+	}
+
+	fn normalized_names(names: &[&str]) -> BTreeSet<String> {
+		names.iter().map(|name| normalize_name(name)).collect()
+	}
+}
@@ -1,5 +1,7 @@
 use crate::types::date_time::DateTime;
 use crate::types::uuid::Uuid;
+use anyhow::anyhow;
+use chrono::Duration;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ChatMessage {
@@ -7,6 +9,77 @@ pub struct ChatMessage {
 	pub room_uuid: Uuid,
 	pub user_uuid: Option<Uuid>,
 	pub user_name: String,
+	pub content: ChatContent,
+	pub created_at: DateTime,
+	/// `None` if the message was never edited.
+	pub edited_at: Option<DateTime>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatContent {
+	pub kind: ChatMessageKind,
+	pub message: String,
+	/// Where the medium was when the message was sent, `None` if there was no medium.
+	pub medium_anchor: Option<MediumAnchor>,
+	/// The chat message this one replies to, `None` if it isn't a reply or that message was deleted.
+	pub reply_to: Option<Uuid>,
+}
+
+impl ChatContent {
+	pub fn message(message: String) -> Self {
+		Self {
+			kind: ChatMessageKind::Message,
+			message,
+			medium_anchor: None,
+			reply_to: None,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChatMessageKind {
+	Message,
+	/// A single emoji.
+	Reaction,
+}
+
+impl ChatMessageKind {
+	pub fn as_str(self) -> &'static str {
+		match self {
+			ChatMessageKind::Message => "message",
+			ChatMessageKind::Reaction => "reaction",
+		}
+	}
+}
+
+impl TryFrom<&str> for ChatMessageKind {
+	type Error = anyhow::Error;
+
+	fn try_from(kind: &str) -> Result<Self, Self::Error> {
+		match kind {
+			"message" => Ok(ChatMessageKind::Message),
+			"reaction" => Ok(ChatMessageKind::Reaction),
+			_ => Err(anyhow!("Unknown chat message kind '{kind}'")),
+		}
+	}
+}
+
+/// Position in a medium, see [`crate::room::medium::Medium::id`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MediumAnchor {
+	pub medium_id: String,
+	pub position: Duration,
+}
+
+/// A message that only its sender and recipient can see.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrivateChatMessage {
+	pub uuid: Uuid,
+	pub room_uuid: Uuid,
+	pub sender_user_uuid: Option<Uuid>,
+	pub sender_name: String,
+	pub recipient_user_uuid: Option<Uuid>,
+	pub recipient_name: String,
 	pub message: String,
 	pub created_at: DateTime,
 }
@@ -21,6 +94,11 @@ impl TryFrom<libsql::Row> for ChatMessage {
 		let user_name = row.get(3)?;
 		let message = row.get(4)?;
 		let created_at = row.get_value(5)?;
+		let kind = row.get::<String>(6)?;
+		let medium_id = row.get::<Option<String>>(7)?;
+		let medium_position_ms = row.get::<Option<i64>>(8)?;
+		let edited_at = row.get_value(9)?;
+		let reply_to = row.get_value(10)?;
 
 		Ok(Self {
 			uuid: uuid.try_into()?,
@@ -31,6 +109,59 @@ impl TryFrom<libsql::Row> for ChatMessage {
 				Some(user_uuid.try_into()?)
 			},
 			user_name,
+			content: ChatContent {
+				kind: kind.as_str().try_into()?,
+				message,
+				medium_anchor: medium_id
+					.zip(medium_position_ms)
+					.map(|(medium_id, position_ms)| MediumAnchor {
+						medium_id,
+						position: Duration::milliseconds(position_ms),
+					}),
+				reply_to: if reply_to.is_null() {
+					None
+				} else {
+					Some(reply_to.try_into()?)
+				},
+			},
+			created_at: created_at.try_into()?,
+			edited_at: if edited_at.is_null() {
+				None
+			} else {
+				Some(edited_at.try_into()?)
+			},
+		})
+	}
+}
+
+impl TryFrom<libsql::Row> for PrivateChatMessage {
+	type Error = anyhow::Error;
+
+	fn try_from(row: libsql::Row) -> Result<Self, Self::Error> {
+		let uuid = row.get_value(0)?;
+		let room_uuid = row.get_value(1)?;
+		let sender_user_uuid = row.get_value(2)?;
+		let sender_name = row.get(3)?;
+		let recipient_user_uuid = row.get_value(4)?;
+		let recipient_name = row.get(5)?;
+		let message = row.get(6)?;
+		let created_at = row.get_value(7)?;
+
+		Ok(Self {
+			uuid: uuid.try_into()?,
+			room_uuid: room_uuid.try_into()?,
+			sender_user_uuid: if sender_user_uuid.is_null() {
+				None
+			} else {
+				Some(sender_user_uuid.try_into()?)
+			},
+			sender_name,
+			recipient_user_uuid: if recipient_user_uuid.is_null() {
+				None
+			} else {
+				Some(recipient_user_uuid.try_into()?)
+			},
+			recipient_name,
 			message,
 			created_at: created_at.try_into()?,
 		})
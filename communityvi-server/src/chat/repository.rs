@@ -3,6 +3,7 @@ use crate::database::Connection;
 use crate::database::error::DatabaseError;
 use crate::types::date_time::DateTime;
 use crate::types::uuid::Uuid;
+use crate::user::model::User;
 use async_trait::async_trait;
 
 #[cfg(test)]
@@ -16,7 +17,45 @@ pub trait ChatRepository: Send + Sync + 'static {
 		room_uuid: Uuid,
 		user_uuid: Uuid,
 		user_name: String,
-		message: String,
+		content: model::ChatContent,
 		created_at: DateTime,
 	) -> Result<model::ChatMessage, DatabaseError>;
+	/// `None` if there is no such message in the room.
+	async fn get(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		chat_message_uuid: Uuid,
+	) -> Result<Option<model::ChatMessage>, DatabaseError>;
+	async fn update_message(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		chat_message_uuid: Uuid,
+		message: String,
+		edited_at: DateTime,
+	) -> Result<model::ChatMessage, DatabaseError>;
+	/// Returns `false` if there was no such message in the room.
+	async fn remove(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		chat_message_uuid: Uuid,
+	) -> Result<bool, DatabaseError>;
+	/// Messages and reactions that were sent while the medium was in the room, ordered by their position in it.
+	async fn get_all_anchored_to_medium(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		medium_id: &str,
+	) -> Result<Vec<model::ChatMessage>, DatabaseError>;
+	async fn create_private_message(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		sender: &User,
+		recipient: &User,
+		message: String,
+		created_at: DateTime,
+	) -> Result<model::PrivateChatMessage, DatabaseError>;
 }
@@ -1,6 +1,7 @@
 #[generic_tests::define(attrs(tokio::test))]
+#[allow(clippy::non_ascii_literal)]
 mod chat_tests {
-	use crate::chat::model::ChatMessage;
+	use crate::chat::model::{ChatContent, ChatMessage, ChatMessageKind, MediumAnchor, PrivateChatMessage};
 	use crate::database::error::DatabaseError;
 	use crate::database::libsql::test_utils::LibSqlTestFactory;
 	use crate::database::test::TestFactory;
@@ -8,7 +9,7 @@ mod chat_tests {
 	use crate::room::model::Room;
 	use crate::user::model::User;
 	use crate::user::normalize_name;
-	use chrono::Utc;
+	use chrono::{Duration, Utc};
 
 	#[tokio::test]
 	async fn creates_chat_message<Factory: TestFactory>() {
@@ -25,8 +26,9 @@ mod chat_tests {
 			room_uuid,
 			user_uuid,
 			user_name,
-			message: chat_message,
+			content: chat_content,
 			created_at: chat_created_at,
+			edited_at,
 		} = repository
 			.chat()
 			.create(
@@ -34,7 +36,7 @@ mod chat_tests {
 				room.uuid,
 				user.uuid,
 				user.name.clone(),
-				message.clone(),
+				ChatContent::message(message.clone()),
 				created_at,
 			)
 			.await
@@ -44,9 +46,10 @@ mod chat_tests {
 		assert_eq!(room.uuid, room_uuid);
 		assert_eq!(Some(user.uuid), user_uuid);
 		assert_eq!(user.name, user_name);
-		assert_eq!(message, chat_message);
+		assert_eq!(ChatContent::message(message), chat_content);
 		// Compare timestamps at second precision to avoid driver precision differences
 		assert_eq!(created_at.timestamp(), chat_created_at.timestamp());
+		assert_eq!(None, edited_at);
 	}
 
 	#[tokio::test]
@@ -64,7 +67,7 @@ mod chat_tests {
 				room.uuid,
 				user.uuid,
 				user.name.clone(),
-				String::new(), // empty message should violate check constraint
+				ChatContent::message(String::new()), // empty message should violate check constraint
 				Utc::now().into(),
 			)
 			.await;
@@ -76,6 +79,215 @@ mod chat_tests {
 		}
 	}
 
+	#[tokio::test]
+	async fn gets_messages_anchored_to_medium_ordered_by_position<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let user = user(repository.as_ref(), &mut *connection, "carol").await;
+		let room = room(repository.as_ref(), &mut *connection, "cinema").await;
+		let contents = [
+			(ChatMessageKind::Message, "Popcorn, anyone?", None),
+			(
+				ChatMessageKind::Message,
+				"Didn't see that coming",
+				Some(("live:Rocket Launch", 61_500)),
+			),
+			(ChatMessageKind::Reaction, "🚀", Some(("live:Rocket Launch", 60_000))),
+			(ChatMessageKind::Reaction, "🎬", Some(("live:Premiere", 0))),
+		];
+		for (kind, message, medium_anchor) in contents {
+			let content = ChatContent {
+				kind,
+				message: message.to_string(),
+				medium_anchor: medium_anchor.map(|(medium_id, position_ms)| MediumAnchor {
+					medium_id: medium_id.to_string(),
+					position: Duration::milliseconds(position_ms),
+				}),
+				reply_to: None,
+			};
+			repository
+				.chat()
+				.create(
+					&mut *connection,
+					room.uuid,
+					user.uuid,
+					user.name.clone(),
+					content,
+					Utc::now().into(),
+				)
+				.await
+				.expect("Failed to create chat message");
+		}
+
+		let contents = repository
+			.chat()
+			.get_all_anchored_to_medium(&mut *connection, room.uuid, "live:Rocket Launch")
+			.await
+			.expect("Failed to get chat messages")
+			.into_iter()
+			.map(|chat_message| chat_message.content)
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			vec![
+				ChatContent {
+					kind: ChatMessageKind::Reaction,
+					message: "🚀".to_string(),
+					medium_anchor: Some(MediumAnchor {
+						medium_id: "live:Rocket Launch".to_string(),
+						position: Duration::milliseconds(60_000),
+					}),
+					reply_to: None,
+				},
+				ChatContent {
+					kind: ChatMessageKind::Message,
+					message: "Didn't see that coming".to_string(),
+					medium_anchor: Some(MediumAnchor {
+						medium_id: "live:Rocket Launch".to_string(),
+						position: Duration::milliseconds(61_500),
+					}),
+					reply_to: None,
+				},
+			],
+			contents
+		);
+	}
+
+	#[tokio::test]
+	async fn updates_message<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let user = user(repository.as_ref(), &mut *connection, "carol").await;
+		let room = room(repository.as_ref(), &mut *connection, "editing").await;
+		let chat_message = repository
+			.chat()
+			.create(
+				&mut *connection,
+				room.uuid,
+				user.uuid,
+				user.name.clone(),
+				ChatContent::message("Helo".to_string()),
+				Utc::now().into(),
+			)
+			.await
+			.expect("Failed to create chat message");
+		let edited_at = Utc::now().into();
+
+		let updated_message = repository
+			.chat()
+			.update_message(
+				&mut *connection,
+				room.uuid,
+				chat_message.uuid,
+				"Hello".to_string(),
+				edited_at,
+			)
+			.await
+			.expect("Failed to update chat message");
+
+		assert_eq!(chat_message.uuid, updated_message.uuid);
+		assert_eq!("Hello", updated_message.content.message);
+		assert_eq!(
+			Some(edited_at.timestamp()),
+			updated_message.edited_at.map(|edited_at| edited_at.timestamp())
+		);
+		assert_eq!(
+			Some(updated_message),
+			repository
+				.chat()
+				.get(&mut *connection, room.uuid, chat_message.uuid)
+				.await
+				.expect("Failed to get chat message")
+		);
+	}
+
+	#[tokio::test]
+	async fn removes_message<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let user = user(repository.as_ref(), &mut *connection, "dave").await;
+		let room = room(repository.as_ref(), &mut *connection, "deleting").await;
+		let chat_message = repository
+			.chat()
+			.create(
+				&mut *connection,
+				room.uuid,
+				user.uuid,
+				user.name.clone(),
+				ChatContent::message("Oops".to_string()),
+				Utc::now().into(),
+			)
+			.await
+			.expect("Failed to create chat message");
+
+		let removed = repository
+			.chat()
+			.remove(&mut *connection, room.uuid, chat_message.uuid)
+			.await
+			.expect("Failed to remove chat message");
+		let removed_again = repository
+			.chat()
+			.remove(&mut *connection, room.uuid, chat_message.uuid)
+			.await
+			.expect("Failed to remove chat message");
+
+		assert!(removed);
+		assert!(!removed_again);
+		assert_eq!(
+			None,
+			repository
+				.chat()
+				.get(&mut *connection, room.uuid, chat_message.uuid)
+				.await
+				.expect("Failed to get chat message")
+		);
+	}
+
+	#[tokio::test]
+	async fn creates_private_message<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let sender = user(repository.as_ref(), &mut *connection, "erin").await;
+		let recipient = user(repository.as_ref(), &mut *connection, "frank").await;
+		let room = room(repository.as_ref(), &mut *connection, "whispers").await;
+		let created_at = Utc::now().into();
+
+		let PrivateChatMessage {
+			uuid,
+			room_uuid,
+			sender_user_uuid,
+			sender_name,
+			recipient_user_uuid,
+			recipient_name,
+			message,
+			created_at: message_created_at,
+		} = repository
+			.chat()
+			.create_private_message(
+				&mut *connection,
+				room.uuid,
+				&sender,
+				&recipient,
+				"Psst!".to_string(),
+				created_at,
+			)
+			.await
+			.expect("Failed to create private message");
+
+		assert_eq!(4, uuid.get_version_num());
+		assert_eq!(room.uuid, room_uuid);
+		assert_eq!(Some(sender.uuid), sender_user_uuid);
+		assert_eq!(sender.name, sender_name);
+		assert_eq!(Some(recipient.uuid), recipient_user_uuid);
+		assert_eq!(recipient.name, recipient_name);
+		assert_eq!("Psst!", message);
+		assert_eq!(created_at.timestamp(), message_created_at.timestamp());
+	}
+
 	async fn user(repository: &dyn Repository, connection: &mut dyn Connection, name: &str) -> User {
 		repository
 			.user()
@@ -1,7 +1,13 @@
+use crate::chat::filter::ChatFilterConfiguration;
+use crate::message::compression::MessageCompression;
+use crate::user::normalize_name;
+use flate2::Compression;
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use std::fs::read_to_string;
 use std::net::SocketAddr;
-use std::path::Path;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
@@ -13,6 +19,38 @@ pub struct Configuration {
 	#[serde(with = "humantime_serde")]
 	pub heartbeat_interval: std::time::Duration,
 	pub missed_heartbeat_limit: u8,
+	pub websocket_message_size_limit: usize,
+	pub websocket_frame_size_limit: usize,
+	pub chat_message_length_limit: usize,
+	pub compression_threshold: usize,
+	#[serde(with = "compression_level_deserializer")]
+	pub compression_level: Compression,
+	#[serde(with = "humantime_serde")]
+	pub resync_threshold: std::time::Duration,
+	/// Automatically pause the medium while any client is buffering and resume once everyone is ready.
+	pub pause_while_buffering: bool,
+	/// Hosts that media can be loaded from by URL, including their subdomains.
+	pub allowed_medium_hosts: Vec<String>,
+	/// Directory with media files the server hosts itself, indexed on startup.
+	pub media_directory: Option<PathBuf>,
+	/// Clients that may edit and delete everyone's chat messages, kick clients and close polls.
+	/// Their names are reserved for clients that register with the matching token.
+	pub moderators: Vec<ModeratorConfiguration>,
+	/// How many private messages a client may send within a minute.
+	pub private_messages_per_minute: NonZeroU32,
+	/// Store private messages in the database in addition to delivering them.
+	pub persist_private_messages: bool,
+	/// Filters that every chat message runs through in order before it is sent.
+	/// At most one of them may be a flood filter, because its rate limit is tracked per client.
+	#[serde(deserialize_with = "chat_filters_deserializer::deserialize")]
+	pub chat_filters: Vec<ChatFilterConfiguration>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct ModeratorConfiguration {
+	pub name: String,
+	/// Secret that the moderator sends when registering.
+	pub token: String,
 }
 
 impl Configuration {
@@ -21,6 +59,72 @@ impl Configuration {
 
 		Ok(Configuration::try_from(text.as_str())?)
 	}
+
+	#[cfg(test)]
+	pub fn test() -> Self {
+		Self {
+			address: "127.0.0.1:8000".parse().unwrap(),
+			log_filters: String::new(),
+			room_size_limit: 10,
+			heartbeat_interval: std::time::Duration::from_secs(2),
+			missed_heartbeat_limit: 3,
+			websocket_message_size_limit: 10 * 1024,
+			websocket_frame_size_limit: 10 * 1024,
+			chat_message_length_limit: 2 * 1024,
+			compression_threshold: 1024,
+			compression_level: Compression::default(),
+			resync_threshold: std::time::Duration::from_millis(500),
+			pause_while_buffering: false,
+			allowed_medium_hosts: vec!["example.com".to_string()],
+			media_directory: Some(PathBuf::from("test/media")),
+			moderators: vec![ModeratorConfiguration {
+				name: "Moderator".to_string(),
+				token: "moderator-token".to_string(),
+			}],
+			private_messages_per_minute: NonZeroU32::new(3).unwrap(),
+			persist_private_messages: true,
+			chat_filters: Vec::new(),
+		}
+	}
+
+	pub fn is_allowed_medium_host(&self, host: &str) -> bool {
+		self.allowed_medium_hosts.iter().any(|allowed_host| {
+			host.eq_ignore_ascii_case(allowed_host)
+				|| host
+					.len()
+					.checked_sub(allowed_host.len() + 1)
+					.and_then(|subdomain_length| host.get(subdomain_length..))
+					.and_then(|suffix| suffix.strip_prefix('.'))
+					.is_some_and(|suffix| suffix.eq_ignore_ascii_case(allowed_host))
+		})
+	}
+
+	/// Names reserved for moderators, normalized with [`normalize_name`].
+	pub fn moderator_names(&self) -> impl Iterator<Item = String> {
+		self.moderators.iter().map(|moderator| normalize_name(&moderator.name))
+	}
+
+	/// Whether a client registering with `name` and `token` is a moderator.
+	pub fn is_moderator(&self, name: &str, token: &str) -> bool {
+		// Comparing digests instead of the tokens means the time the comparison takes doesn't reveal how many
+		// leading bytes of a guessed token are correct.
+		self.moderator(name)
+			.is_some_and(|moderator| Sha256::digest(moderator.token.as_bytes()) == Sha256::digest(token.as_bytes()))
+	}
+
+	fn moderator(&self, name: &str) -> Option<&ModeratorConfiguration> {
+		let normalized_name = normalize_name(name);
+		self.moderators
+			.iter()
+			.find(|moderator| normalize_name(&moderator.name) == normalized_name)
+	}
+
+	pub fn message_compression(&self) -> MessageCompression {
+		MessageCompression {
+			threshold: self.compression_threshold,
+			level: self.compression_level,
+		}
+	}
 }
 
 impl TryFrom<&str> for Configuration {
@@ -54,9 +158,52 @@ mod socket_addr_deserializer {
 	}
 }
 
+mod compression_level_deserializer {
+	use flate2::Compression;
+	use serde::{self, Deserialize, Deserializer};
+
+	pub fn deserialize<'deserializer, D>(deserializer: D) -> Result<Compression, D::Error>
+	where
+		D: Deserializer<'deserializer>,
+	{
+		let level = u32::deserialize(deserializer)?;
+		if level > Compression::best().level() {
+			return Err(serde::de::Error::custom(format!(
+				"Compression level must be between 0 and 9, got {level}"
+			)));
+		}
+
+		Ok(Compression::new(level))
+	}
+}
+
+mod chat_filters_deserializer {
+	use crate::chat::filter::{ChatFilterConfiguration, ChatFilterKind};
+	use serde::{self, Deserialize, Deserializer};
+
+	pub fn deserialize<'deserializer, D>(deserializer: D) -> Result<Vec<ChatFilterConfiguration>, D::Error>
+	where
+		D: Deserializer<'deserializer>,
+	{
+		let filters = Vec::<ChatFilterConfiguration>::deserialize(deserializer)?;
+		let flood_filter_count = filters
+			.iter()
+			.filter(|configuration| matches!(configuration.filter, ChatFilterKind::Flood { .. }))
+			.count();
+		if flood_filter_count > 1 {
+			return Err(serde::de::Error::custom(format!(
+				"At most one flood filter can be configured, got {flood_filter_count}"
+			)));
+		}
+
+		Ok(filters)
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
+	use crate::chat::filter::{ChatFilterKind, FilterAction};
 	use std::str::FromStr;
 
 	#[test]
@@ -69,6 +216,19 @@ mod test {
 			room_size_limit,
 			heartbeat_interval,
 			missed_heartbeat_limit,
+			websocket_message_size_limit,
+			websocket_frame_size_limit,
+			chat_message_length_limit,
+			compression_threshold,
+			compression_level,
+			resync_threshold,
+			pause_while_buffering,
+			allowed_medium_hosts,
+			media_directory,
+			moderators,
+			private_messages_per_minute,
+			persist_private_messages,
+			chat_filters,
 		} = Configuration::from_file(TEST_FILE_PATH).unwrap();
 
 		assert_eq!(SocketAddr::from_str("127.0.0.1:8000").unwrap(), address);
@@ -76,5 +236,130 @@ mod test {
 		assert_eq!(42, room_size_limit);
 		assert_eq!(std::time::Duration::from_secs(2), heartbeat_interval);
 		assert_eq!(3, missed_heartbeat_limit);
+		assert_eq!(10240, websocket_message_size_limit);
+		assert_eq!(10240, websocket_frame_size_limit);
+		assert_eq!(2048, chat_message_length_limit);
+		assert_eq!(1024, compression_threshold);
+		assert_eq!(Compression::new(6), compression_level);
+		assert_eq!(std::time::Duration::from_millis(500), resync_threshold);
+		assert!(pause_while_buffering);
+		assert_eq!(vec!["example.com".to_string()], allowed_medium_hosts);
+		assert_eq!(Some(PathBuf::from("test/media")), media_directory);
+		assert_eq!(
+			vec![ModeratorConfiguration {
+				name: "Moderator".to_string(),
+				token: "moderator-token".to_string(),
+			}],
+			moderators
+		);
+		assert_eq!(nonzero_ext::nonzero!(30u32), private_messages_per_minute);
+		assert!(persist_private_messages);
+		assert_eq!(
+			vec![ChatFilterConfiguration {
+				filter: ChatFilterKind::Links,
+				action: FilterAction::Reject,
+			}],
+			chat_filters
+		);
+	}
+
+	#[test]
+	fn should_not_deserialize_invalid_compression_level() {
+		let text = r#"
+			address = "127.0.0.1:8000"
+			log_filters = "info"
+			room_size_limit = 42
+			heartbeat_interval = "2s"
+			missed_heartbeat_limit = 3
+			websocket_message_size_limit = 10240
+			websocket_frame_size_limit = 10240
+			chat_message_length_limit = 2048
+			compression_threshold = 1024
+			compression_level = 10
+			resync_threshold = "500ms"
+			pause_while_buffering = true
+			allowed_medium_hosts = []
+			moderators = []
+			private_messages_per_minute = 30
+			persist_private_messages = false
+			chat_filters = []
+		"#;
+
+		let error = Configuration::try_from(text).expect_err("Compression level 10 must be rejected");
+
+		assert!(error.message().contains("Compression level must be between 0 and 9"));
+	}
+
+	#[test]
+	fn should_not_deserialize_more_than_one_flood_filter() {
+		let text = r#"
+			address = "127.0.0.1:8000"
+			log_filters = "info"
+			room_size_limit = 42
+			heartbeat_interval = "2s"
+			missed_heartbeat_limit = 3
+			websocket_message_size_limit = 10240
+			websocket_frame_size_limit = 10240
+			chat_message_length_limit = 2048
+			compression_threshold = 1024
+			compression_level = 6
+			resync_threshold = "500ms"
+			pause_while_buffering = true
+			allowed_medium_hosts = []
+			moderators = []
+			private_messages_per_minute = 30
+			persist_private_messages = false
+
+			[[chat_filters]]
+			type = "flood"
+			action = "reject"
+			messages_per_minute = 10
+			repeated_message_limit = 3
+
+			[[chat_filters]]
+			type = "flood"
+			action = "flag"
+			messages_per_minute = 5
+			repeated_message_limit = 2
+		"#;
+
+		let error = Configuration::try_from(text).expect_err("Two flood filters must be rejected");
+
+		assert!(
+			error
+				.message()
+				.contains("At most one flood filter can be configured, got 2")
+		);
+	}
+
+	#[test]
+	fn should_allow_medium_hosts_and_their_subdomains() {
+		let configuration = Configuration::test();
+
+		assert!(configuration.is_allowed_medium_host("example.com"));
+		assert!(configuration.is_allowed_medium_host("videos.EXAMPLE.com"));
+		assert!(!configuration.is_allowed_medium_host("notexample.com"));
+		assert!(!configuration.is_allowed_medium_host("example.com.evil.org"));
+	}
+
+	#[test]
+	fn should_recognize_moderators_by_normalized_name_and_token() {
+		let configuration = Configuration::test();
+
+		assert!(configuration.is_moderator("Moderator", "moderator-token"));
+		assert!(configuration.is_moderator("Mo derator", "moderator-token"));
+		assert!(!configuration.is_moderator("Moderator", "guessed-token"));
+		assert!(!configuration.is_moderator("Alice", "moderator-token"));
+	}
+
+	#[test]
+	fn should_reserve_moderator_names() {
+		let configuration = Configuration::test();
+
+		let moderator_names = configuration.moderator_names().collect::<Vec<_>>();
+
+		assert!(moderator_names.contains(&normalize_name("Moderator")));
+		assert!(moderator_names.contains(&normalize_name("Mo derator")));
+		assert!(!moderator_names.contains(&normalize_name("Alice")));
 	}
 }
@@ -1,13 +1,16 @@
 use crate::connection::broadcast_buffer::BroadcastBuffer;
 use crate::connection::sender::MessageSender;
-use crate::message::outgoing::broadcast_message::BroadcastMessage;
+use crate::connection::shared_broadcast::SharedBroadcast;
 use crate::message::outgoing::error_message::ErrorMessage;
+use crate::message::outgoing::moderator_notice::ModeratorNotice;
+use crate::message::outgoing::private_message::PrivateMessage;
 use crate::message::outgoing::success_message::SuccessMessage;
 use js_int::UInt;
 
 pub mod broadcast_buffer;
 pub mod receiver;
 pub mod sender;
+pub mod shared_broadcast;
 
 pub struct Connection {
 	sender: MessageSender,
@@ -30,15 +33,23 @@ impl Connection {
 		self.sender.send_error_message(message, request_id).await.is_ok()
 	}
 
-	pub async fn send_broadcast_message(&self, message: BroadcastMessage) -> bool {
+	pub async fn send_private_message(&self, message: PrivateMessage) -> bool {
+		self.sender.send_private_message(message).await.is_ok()
+	}
+
+	pub async fn send_moderator_notice(&self, message: ModeratorNotice) -> bool {
+		self.sender.send_moderator_notice(message).await.is_ok()
+	}
+
+	pub async fn send_broadcast_message(&self, message: &SharedBroadcast) -> bool {
 		self.sender.send_broadcast_message(message).await.is_ok()
 	}
 
-	pub fn enqueue_broadcast(&self, message: BroadcastMessage, count: usize) {
+	pub fn enqueue_broadcast(&self, message: SharedBroadcast, count: usize) {
 		self.broadcast_buffer.enqueue(message, count);
 	}
 
-	pub async fn wait_for_broadcast(&self) -> BroadcastMessage {
+	pub async fn wait_for_broadcast(&self) -> SharedBroadcast {
 		self.broadcast_buffer.wait_for_broadcast().await
 	}
 
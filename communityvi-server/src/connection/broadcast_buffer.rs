@@ -1,10 +1,14 @@
 #![allow(clippy::should_panic_without_expect)]
+use crate::connection::shared_broadcast::SharedBroadcast;
 use crate::message::outgoing::broadcast_message::{
-	BroadcastMessage, ChatBroadcast, ClientJoinedBroadcast, ClientLeftBroadcast, MediumStateChangedBroadcast,
-	VersionedMediumBroadcast,
+	BookmarksChangedBroadcast, BroadcastMessage, ChatBroadcast, ChatDeletedBroadcast, ChatEditedBroadcast,
+	ClientJoinedBroadcast, ClientLeftBroadcast, ClientProfileChangedBroadcast, ClientRenamedBroadcast, EmoteBroadcast,
+	MediumStateChangedBroadcast, PlaybackScheduledBroadcast, PollBroadcast, ReactionBroadcast,
+	SubtitlesChangedBroadcast, TypingBroadcast, VersionedMediumBroadcast,
 };
+use crate::room::session_id::SessionId;
 use js_int::{UInt, uint};
-use std::collections::{BTreeSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use tokio::sync::Notify;
 
 pub struct BroadcastBuffer {
@@ -14,12 +18,13 @@ pub struct BroadcastBuffer {
 }
 
 const CHAT_MESSAGE_BUFFER_LIMIT: usize = 10;
+const POLL_BUFFER_LIMIT: usize = 5;
 
 #[derive(Default)]
 pub struct Inner {
 	next_medium_version: UInt,
 	next_chat_message_counter: UInt,
-	messages: VecDeque<BroadcastMessage>,
+	messages: VecDeque<SharedBroadcast>,
 	next_broadcast_number: Option<usize>,
 }
 
@@ -32,14 +37,14 @@ impl BroadcastBuffer {
 		}
 	}
 
-	pub fn enqueue(&self, message: BroadcastMessage, broadcast_number: usize) {
+	pub fn enqueue(&self, message: SharedBroadcast, broadcast_number: usize) {
 		let mut inner = self.inner.lock();
 		if let Some(next_broadcast_number) = inner.next_broadcast_number {
 			assert_eq!(next_broadcast_number, broadcast_number);
 		}
 		inner.next_broadcast_number = Some(broadcast_number + 1);
 
-		match &message {
+		match message.message() {
 			BroadcastMessage::MediumStateChanged(MediumStateChangedBroadcast {
 				medium: VersionedMediumBroadcast { version, .. },
 				..
@@ -68,7 +73,6 @@ impl BroadcastBuffer {
 		}
 
 		if !inner.is_empty() {
-			// FIXME: Check if this use of tokio::sync::Notify is correct!
 			self.new_broadcast_available_notification_channel.notify_one();
 		}
 	}
@@ -77,22 +81,34 @@ impl BroadcastBuffer {
 		(self.maximum_client_count - 1) // join/leave messages for all clients except the one we're currently sending to
 			+ (CHAT_MESSAGE_BUFFER_LIMIT * 3) // Join + Chat + Leave if a client Joins, sends a message and leaves again
 			+ 3 // Join + medium state + Leave if a client joins, changes the state and leaves again
+			+ 3 // Join + scheduled playback + Leave if a client joins, schedules playback and leaves again
+			+ 1 // fingerprint mismatch
+			+ 3 // Join + bookmarks + Leave if a client joins, changes a bookmark and leaves again
+			+ 3 // Join + subtitles + Leave if a client joins, changes the subtitles and leaves again
+			+ 3 // Join + reaction + Leave if a client joins, reacts and leaves again
+			+ (CHAT_MESSAGE_BUFFER_LIMIT * 3) // Join + edit/delete + Leave if a client joins, changes a message and leaves again
+			+ (self.maximum_client_count - 1) // typing state of all clients except the one we're currently sending to
+			+ POLL_BUFFER_LIMIT // last state of the most recent polls
+			+ (self.maximum_client_count - 1) // latest name of all clients except the one we're currently sending to
+			+ (self.maximum_client_count - 1) // latest profile of all clients except the one we're currently sending to
 	}
 
 	pub fn is_empty(&self) -> bool {
 		self.inner.lock().is_empty()
 	}
 
-	pub fn dequeue(&self) -> Option<BroadcastMessage> {
+	pub fn dequeue(&self) -> Option<SharedBroadcast> {
 		self.inner.lock().messages.pop_front()
 	}
 
-	pub async fn wait_for_broadcast(&self) -> BroadcastMessage {
+	pub async fn wait_for_broadcast(&self) -> SharedBroadcast {
 		loop {
-			self.new_broadcast_available_notification_channel.notified().await;
+			// Several broadcasts can be enqueued while nobody is waiting but they only leave a single permit behind,
+			// so the buffer needs to be drained before waiting for the next notification.
 			if let Some(broadcast) = self.dequeue() {
 				return broadcast;
 			}
+			self.new_broadcast_available_notification_channel.notified().await;
 		}
 	}
 }
@@ -101,63 +117,100 @@ impl Inner {
 	/// Ensures that there is a bounded count of messages in the buffer by enforcing some simple rules:
 	/// * Only ever keep the medium state with highest version alive
 	///   (which is the last in the buffer since the order of versions is already enforced when enqueueing)
-	/// * Only ever keep at most the last `CHAT_MESSAGE_BUFFER_LIMIT` chat messages.
+	/// * Only ever keep the last scheduled start of playback alive
+	/// * Only ever keep the last fingerprint mismatch alive
+	/// * Only ever keep the last bookmark list alive
+	/// * Only ever keep the last subtitles alive
+	/// * Only ever keep the last reaction alive, reactions are only interesting while they are fresh
+	/// * Only ever keep the last typing state of each client that is still in the room, they are ephemeral
+	/// * Only ever keep the last state of each of the last `POLL_BUFFER_LIMIT` polls
+	/// * Only ever keep the last rename of each client that is still in the room
+	/// * Only ever keep the last profile change of each client that is still in the room
+	/// * Only ever keep at most the last `CHAT_MESSAGE_BUFFER_LIMIT` chat messages and emotes.
+	/// * Remove deleted chat messages together with their deletion, the client never needs to know about them.
+	/// * Only ever keep the last edit of a chat message and none for chat messages that are removed.
+	/// * Only ever keep at most the last `CHAT_MESSAGE_BUFFER_LIMIT` edits and deletions.
 	/// * Remove Join and Left messages for the same client as long as we don't still have any chat messages from them.
 	///
 	/// This means we can calculate the maximum count of messages by taking the worst case scenario
 	/// (see `worst_count_of_messages_to_keep_alive`)
 	fn collect_garbage(&mut self) {
-		let mut seen_chat_messages = 0;
-		let mut last_seen_medium_index = None;
-		let mut clients_to_keep_alive = BTreeSet::new();
-		let mut joined_clients = BTreeSet::new();
-		let mut left_clients = BTreeSet::new();
-
-		// Mark phase
-		for (index, message) in self.messages.iter().enumerate() {
-			use BroadcastMessage::*;
-			match message {
-				ClientJoined(ClientJoinedBroadcast { id, .. }) => {
-					joined_clients.insert(*id);
-				}
-				ClientLeft(ClientLeftBroadcast { id, .. }) => {
-					left_clients.insert(*id);
-				}
-				Chat(ChatBroadcast { sender_id, .. }) => {
-					seen_chat_messages += 1;
-					clients_to_keep_alive.insert(*sender_id);
-				}
-				MediumStateChanged(MediumStateChangedBroadcast { changed_by_id, .. }) => {
-					last_seen_medium_index = Some(index);
-					clients_to_keep_alive.insert(*changed_by_id);
-				}
-			}
-		}
+		let Marks {
+			mut seen_chat_messages,
+			mut seen_chat_modifications,
+			last_edit_indices,
+			deleted_chat_messages,
+			last_seen_medium_index,
+			last_seen_schedule_index,
+			last_seen_fingerprint_mismatch_index,
+			last_seen_bookmarks_index,
+			last_seen_subtitles_index,
+			last_seen_reaction_index,
+			last_typing_indices,
+			last_rename_indices,
+			last_profile_indices,
+			kept_poll_indices,
+			clients_to_keep_alive,
+			joined_clients,
+			left_clients,
+		} = Marks::of(&self.messages);
+		let mut removed_chat_messages = BTreeSet::new();
 
 		// Sweep Phase
-		self.messages = self
-			.messages
-			.drain(..)
-			.enumerate()
-			.filter(|(index, message)| {
-				use BroadcastMessage::*;
-				match message {
-					ClientJoined(ClientJoinedBroadcast { id, .. }) => {
-						!left_clients.contains(id) || clients_to_keep_alive.contains(id)
+		self.messages =
+			self.messages
+				.drain(..)
+				.enumerate()
+				.filter(|(index, message)| {
+					use BroadcastMessage::*;
+					match message.message() {
+						ClientJoined(ClientJoinedBroadcast { id, .. }) => {
+							!left_clients.contains(id) || clients_to_keep_alive.contains(id)
+						}
+						ClientLeft(ClientLeftBroadcast { id, .. }) => {
+							!joined_clients.contains(id) || clients_to_keep_alive.contains(id)
+						}
+						Chat(ChatBroadcast { id, .. }) => {
+							let keep =
+								seen_chat_messages <= CHAT_MESSAGE_BUFFER_LIMIT && !deleted_chat_messages.contains(id);
+							seen_chat_messages -= 1;
+							if !keep {
+								removed_chat_messages.insert(id.clone());
+							}
+							keep
+						}
+						Emote(_) => {
+							seen_chat_messages -= 1;
+							seen_chat_messages < CHAT_MESSAGE_BUFFER_LIMIT
+						}
+						ChatEdited(ChatEditedBroadcast { id, .. }) | ChatDeleted(ChatDeletedBroadcast { id, .. }) => {
+							let is_superseded_edit = matches!(message.message(), ChatEdited(_))
+								&& (last_edit_indices.get(id) != Some(index) || deleted_chat_messages.contains(id));
+							let keep = seen_chat_modifications <= CHAT_MESSAGE_BUFFER_LIMIT
+								&& !is_superseded_edit && !removed_chat_messages.contains(id);
+							seen_chat_modifications -= 1;
+							keep
+						}
+						Reaction(_) => Some(*index) == last_seen_reaction_index,
+						MediumStateChanged(_) => Some(*index) == last_seen_medium_index,
+						PlaybackScheduled(_) => Some(*index) == last_seen_schedule_index,
+						FingerprintMismatch(_) => Some(*index) == last_seen_fingerprint_mismatch_index,
+						BookmarksChanged(_) => Some(*index) == last_seen_bookmarks_index,
+						SubtitlesChanged(_) => Some(*index) == last_seen_subtitles_index,
+						Typing(TypingBroadcast { id, .. }) => {
+							Some(*index) == last_typing_indices.get(id).copied() && !left_clients.contains(id)
+						}
+						Poll(_) => kept_poll_indices.contains(index),
+						ClientRenamed(ClientRenamedBroadcast { id, .. }) => {
+							Some(*index) == last_rename_indices.get(id).copied() && !left_clients.contains(id)
+						}
+						ClientProfileChanged(ClientProfileChangedBroadcast { id, .. }) => {
+							Some(*index) == last_profile_indices.get(id).copied() && !left_clients.contains(id)
+						}
 					}
-					ClientLeft(ClientLeftBroadcast { id, .. }) => {
-						!joined_clients.contains(id) || clients_to_keep_alive.contains(id)
-					}
-					Chat(_) => {
-						let keep = seen_chat_messages <= CHAT_MESSAGE_BUFFER_LIMIT;
-						seen_chat_messages -= 1;
-						keep
-					}
-					MediumStateChanged(_) => Some(*index) == last_seen_medium_index,
-				}
-			})
-			.map(|(_index, message)| message)
-			.collect();
+				})
+				.map(|(_index, message)| message)
+				.collect();
 	}
 
 	fn is_empty(&self) -> bool {
@@ -169,11 +222,107 @@ impl Inner {
 	}
 }
 
+/// What the mark phase of [`Inner::collect_garbage`] found out about the buffered messages.
+#[derive(Default)]
+struct Marks {
+	seen_chat_messages: usize,
+	seen_chat_modifications: usize,
+	last_edit_indices: BTreeMap<String, usize>,
+	deleted_chat_messages: BTreeSet<String>,
+	last_seen_medium_index: Option<usize>,
+	last_seen_schedule_index: Option<usize>,
+	last_seen_fingerprint_mismatch_index: Option<usize>,
+	last_seen_bookmarks_index: Option<usize>,
+	last_seen_subtitles_index: Option<usize>,
+	last_seen_reaction_index: Option<usize>,
+	last_typing_indices: BTreeMap<SessionId, usize>,
+	last_rename_indices: BTreeMap<SessionId, usize>,
+	last_profile_indices: BTreeMap<SessionId, usize>,
+	kept_poll_indices: BTreeSet<usize>,
+	clients_to_keep_alive: BTreeSet<SessionId>,
+	joined_clients: BTreeSet<SessionId>,
+	left_clients: BTreeSet<SessionId>,
+}
+
+impl Marks {
+	fn of(messages: &VecDeque<SharedBroadcast>) -> Self {
+		let mut marks = Self::default();
+		let mut last_poll_indices = BTreeMap::new();
+		for (index, message) in messages.iter().enumerate() {
+			use BroadcastMessage::*;
+			match message.message() {
+				ClientJoined(ClientJoinedBroadcast { id, .. }) => {
+					marks.joined_clients.insert(*id);
+				}
+				ClientLeft(ClientLeftBroadcast { id, .. }) => {
+					marks.left_clients.insert(*id);
+				}
+				Chat(ChatBroadcast { sender_id, .. }) | Emote(EmoteBroadcast { sender_id, .. }) => {
+					marks.seen_chat_messages += 1;
+					marks.clients_to_keep_alive.insert(*sender_id);
+				}
+				ChatEdited(ChatEditedBroadcast { id, edited_by_id, .. }) => {
+					marks.seen_chat_modifications += 1;
+					marks.last_edit_indices.insert(id.clone(), index);
+					marks.clients_to_keep_alive.insert(*edited_by_id);
+				}
+				ChatDeleted(ChatDeletedBroadcast { id, deleted_by_id, .. }) => {
+					marks.seen_chat_modifications += 1;
+					marks.deleted_chat_messages.insert(id.clone());
+					marks.clients_to_keep_alive.insert(*deleted_by_id);
+				}
+				Reaction(ReactionBroadcast { sender_id, .. }) => {
+					marks.last_seen_reaction_index = Some(index);
+					marks.clients_to_keep_alive.insert(*sender_id);
+				}
+				MediumStateChanged(MediumStateChangedBroadcast { changed_by_id, .. }) => {
+					marks.last_seen_medium_index = Some(index);
+					marks.clients_to_keep_alive.extend(*changed_by_id);
+				}
+				PlaybackScheduled(PlaybackScheduledBroadcast { changed_by_id, .. }) => {
+					marks.last_seen_schedule_index = Some(index);
+					marks.clients_to_keep_alive.extend(*changed_by_id);
+				}
+				Typing(TypingBroadcast { id, .. }) => {
+					marks.last_typing_indices.insert(*id, index);
+				}
+				FingerprintMismatch(_) => {
+					marks.last_seen_fingerprint_mismatch_index = Some(index);
+				}
+				BookmarksChanged(BookmarksChangedBroadcast { changed_by_id, .. }) => {
+					marks.last_seen_bookmarks_index = Some(index);
+					marks.clients_to_keep_alive.insert(*changed_by_id);
+				}
+				SubtitlesChanged(SubtitlesChangedBroadcast { changed_by_id, .. }) => {
+					marks.last_seen_subtitles_index = Some(index);
+					marks.clients_to_keep_alive.insert(*changed_by_id);
+				}
+				Poll(PollBroadcast { id, .. }) => {
+					last_poll_indices.insert(id.clone(), index);
+				}
+				ClientRenamed(ClientRenamedBroadcast { id, .. }) => {
+					marks.last_rename_indices.insert(*id, index);
+				}
+				ClientProfileChanged(ClientProfileChangedBroadcast { id, .. }) => {
+					marks.last_profile_indices.insert(*id, index);
+				}
+			}
+		}
+
+		let mut poll_indices = last_poll_indices.into_values().collect::<Vec<_>>();
+		poll_indices.sort_unstable();
+		marks.kept_poll_indices = poll_indices.into_iter().rev().take(POLL_BUFFER_LIMIT).collect();
+		marks
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
-	use crate::message::outgoing::broadcast_message::{LeftReason, MediumBroadcast};
+	use crate::message::outgoing::broadcast_message::{LeftReason, MediumBroadcast, PollKindBroadcast};
+	use crate::message::outgoing::success_message::ProfileResponse;
 	use crate::room::session_id::SessionId;
+	use crate::user::model::Presence;
 	use std::ops::Deref;
 
 	#[allow(clippy::cast_possible_truncation)]
@@ -205,13 +354,15 @@ mod test {
 		fn enqueue_next(&mut self, broadcast: BroadcastMessage) {
 			let broadcast_number = self.broadcast_number;
 			self.broadcast_number += 1;
-			self.enqueue(broadcast, broadcast_number);
+			self.enqueue(broadcast.into(), broadcast_number);
 		}
 
 		fn enqueue_client_joined(&mut self, id: UInt) {
 			let message = ClientJoinedBroadcast {
 				id: id.into(),
 				name: format!("{id}"),
+				accepts_private_messages: true,
+				profile: ProfileResponse::default(),
 			};
 			self.enqueue_next(message.into());
 		}
@@ -225,10 +376,45 @@ mod test {
 			self.enqueue_next(message.into());
 		}
 
+		fn enqueue_typing(&mut self, id: UInt, typing: bool) {
+			let message = TypingBroadcast {
+				id: id.into(),
+				name: format!("{id}"),
+				typing,
+			};
+			self.enqueue_next(message.into());
+		}
+
+		fn enqueue_profile_changed(&mut self, id: UInt, presence: Presence) {
+			let message = ClientProfileChangedBroadcast {
+				id: id.into(),
+				name: format!("{id}"),
+				profile: ProfileResponse {
+					presence,
+					..ProfileResponse::default()
+				},
+			};
+			self.enqueue_next(message.into());
+		}
+
+		fn enqueue_poll(&mut self, id: usize, closed: bool) {
+			let message = PollBroadcast {
+				id: id.to_string(),
+				kind: PollKindBroadcast::Custom,
+				question: format!("{id}?"),
+				options: Vec::new(),
+				created_by_name: "Alice".to_string(),
+				medium_version: None,
+				deadline_in_milliseconds: uint!(0),
+				closed,
+			};
+			self.enqueue_next(message.into());
+		}
+
 		fn enqueue_medium_state(&mut self, id: SessionId, version: UInt) {
 			let medium_state = MediumStateChangedBroadcast {
 				changed_by_name: format!("{id}"),
-				changed_by_id: id,
+				changed_by_id: Some(id),
 				medium: VersionedMediumBroadcast {
 					version,
 					medium: MediumBroadcast::Empty,
@@ -239,42 +425,74 @@ mod test {
 
 		fn enqueue_chat_message(&mut self, id: SessionId, number: UInt) {
 			let chat_message = ChatBroadcast {
+				id: number.to_string(),
 				sender_id: id,
 				sender_name: format!("{id}"),
 				message: format!("{number}"),
 				counter: number,
+				medium_position_in_milliseconds: None,
+				reply_to: None,
+				mentions: Vec::new(),
+				mentions_you: false,
 			};
 			self.enqueue_next(chat_message.into());
 		}
 
+		fn enqueue_chat_edited(&mut self, id: &str, message: &str) {
+			let chat_edited = ChatEditedBroadcast {
+				id: id.to_string(),
+				message: message.to_string(),
+				edited_by_name: "Editor".to_string(),
+				edited_by_id: SessionId::from(100),
+			};
+			self.enqueue_next(chat_edited.into());
+		}
+
+		fn enqueue_chat_deleted(&mut self, id: &str) {
+			let chat_deleted = ChatDeletedBroadcast {
+				id: id.to_string(),
+				deleted_by_name: "Deleter".to_string(),
+				deleted_by_id: SessionId::from(101),
+			};
+			self.enqueue_next(chat_deleted.into());
+		}
+
+		fn dequeue_message(&mut self) -> BroadcastMessage {
+			self.broadcast_buffer
+				.dequeue()
+				.expect("No message queued")
+				.message()
+				.clone()
+		}
+
 		fn dequeue_client_joined(&mut self) -> SessionId {
-			match self.broadcast_buffer.dequeue().expect("No message queued") {
+			match self.broadcast_buffer.dequeue().expect("No message queued").message() {
 				BroadcastMessage::ClientJoined(joined) => joined.id,
 				_ => panic!("Head of buffer was not ClientJoined"),
 			}
 		}
 
 		fn dequeue_client_left(&mut self) -> SessionId {
-			match self.broadcast_buffer.dequeue().expect("No message queued") {
+			match self.broadcast_buffer.dequeue().expect("No message queued").message() {
 				BroadcastMessage::ClientLeft(left) => left.id,
 				_ => panic!("Head of buffer was not ClientLeft"),
 			}
 		}
 
 		fn dequeue_medium_state(&mut self) -> (SessionId, UInt) {
-			match self.broadcast_buffer.dequeue().expect("No message queued") {
+			match self.broadcast_buffer.dequeue().expect("No message queued").message() {
 				BroadcastMessage::MediumStateChanged(MediumStateChangedBroadcast {
 					changed_by_id,
 					medium: VersionedMediumBroadcast { version, .. },
 					..
-				}) => (changed_by_id, version),
+				}) => (changed_by_id.expect("Medium state was changed by the server"), *version),
 				_ => panic!("Head of buffer was not MediumStateChanged"),
 			}
 		}
 
 		fn dequeue_chat_message(&mut self) -> (SessionId, UInt) {
-			match self.broadcast_buffer.dequeue().expect("No message queued") {
-				BroadcastMessage::Chat(ChatBroadcast { sender_id, counter, .. }) => (sender_id, counter),
+			match self.broadcast_buffer.dequeue().expect("No message queued").message() {
+				BroadcastMessage::Chat(ChatBroadcast { sender_id, counter, .. }) => (*sender_id, *counter),
 				_ => panic!("Head of buffer was not Chat"),
 			}
 		}
@@ -297,6 +515,88 @@ mod test {
 		assert!(broadcast_buffer.is_empty());
 	}
 
+	#[test]
+	fn collect_garbage_should_only_keep_the_latest_typing_state_of_clients_in_the_room() {
+		let mut broadcast_buffer = BroadcastBufferWithTestHelpers::default();
+		broadcast_buffer.enqueue_typing(uint!(0), true);
+		broadcast_buffer.enqueue_typing(uint!(1), true);
+		broadcast_buffer.enqueue_typing(uint!(0), false);
+		broadcast_buffer.enqueue_typing(uint!(2), true);
+		broadcast_buffer.enqueue_client_left(uint!(2));
+
+		broadcast_buffer.inner.lock().collect_garbage();
+
+		let expected_typing = |id: u32, typing| {
+			BroadcastMessage::from(TypingBroadcast {
+				id: SessionId::from(id),
+				name: id.to_string(),
+				typing,
+			})
+		};
+		assert_eq!(expected_typing(1, true), broadcast_buffer.dequeue_message());
+		assert_eq!(expected_typing(0, false), broadcast_buffer.dequeue_message());
+		assert_eq!(broadcast_buffer.dequeue_client_left(), SessionId::from(2));
+		assert!(broadcast_buffer.is_empty());
+	}
+
+	#[test]
+	fn collect_garbage_should_only_keep_the_latest_profile_of_clients_in_the_room() {
+		let mut broadcast_buffer = BroadcastBufferWithTestHelpers::default();
+		broadcast_buffer.enqueue_profile_changed(uint!(0), Presence::Away);
+		broadcast_buffer.enqueue_profile_changed(uint!(1), Presence::DoNotDisturb);
+		broadcast_buffer.enqueue_profile_changed(uint!(0), Presence::Active);
+		broadcast_buffer.enqueue_profile_changed(uint!(2), Presence::Away);
+		broadcast_buffer.enqueue_client_left(uint!(2));
+
+		broadcast_buffer.inner.lock().collect_garbage();
+
+		let expected_profile_changed = |id: u32, presence| {
+			BroadcastMessage::from(ClientProfileChangedBroadcast {
+				id: SessionId::from(id),
+				name: id.to_string(),
+				profile: ProfileResponse {
+					presence,
+					..ProfileResponse::default()
+				},
+			})
+		};
+		assert_eq!(
+			expected_profile_changed(1, Presence::DoNotDisturb),
+			broadcast_buffer.dequeue_message()
+		);
+		assert_eq!(
+			expected_profile_changed(0, Presence::Active),
+			broadcast_buffer.dequeue_message()
+		);
+		assert_eq!(broadcast_buffer.dequeue_client_left(), SessionId::from(2));
+		assert!(broadcast_buffer.is_empty());
+	}
+
+	#[test]
+	fn collect_garbage_should_only_keep_the_latest_state_of_the_most_recent_polls() {
+		let mut broadcast_buffer = BroadcastBufferWithTestHelpers::default();
+		for id in 0..POLL_BUFFER_LIMIT {
+			broadcast_buffer.enqueue_poll(id, false);
+		}
+		broadcast_buffer.enqueue_poll(POLL_BUFFER_LIMIT, false);
+		broadcast_buffer.enqueue_poll(1, true);
+
+		broadcast_buffer.inner.lock().collect_garbage();
+
+		let poll_states = std::iter::from_fn(|| broadcast_buffer.dequeue())
+			.map(|broadcast| match broadcast.message() {
+				BroadcastMessage::Poll(PollBroadcast { id, closed, .. }) => (id.clone(), *closed),
+				message => panic!("Expected poll broadcast, got {message:?}"),
+			})
+			.collect::<Vec<_>>();
+		let expected_poll_states = [2, 3, 4, POLL_BUFFER_LIMIT]
+			.into_iter()
+			.map(|id| (id.to_string(), false))
+			.chain([("1".to_string(), true)])
+			.collect::<Vec<_>>();
+		assert_eq!(expected_poll_states, poll_states);
+	}
+
 	#[test]
 	fn collect_garbage_should_only_produce_latest_medium_state() {
 		let mut broadcast_buffer = BroadcastBufferWithTestHelpers::default();
@@ -327,6 +627,59 @@ mod test {
 		}
 	}
 
+	#[test]
+	fn emotes_should_count_towards_the_chat_message_limit() {
+		let mut broadcast_buffer = BroadcastBufferWithTestHelpers::default();
+		broadcast_buffer.enqueue_next(
+			EmoteBroadcast {
+				sender_id: SessionId::from(42),
+				sender_name: "Emoter".to_string(),
+				action: "waves".to_string(),
+			}
+			.into(),
+		);
+		for number in 0..CHAT_MESSAGE_BUFFER_LIMIT {
+			broadcast_buffer.enqueue_chat_message(SessionId::from(number), number.into());
+		}
+
+		broadcast_buffer.inner.lock().collect_garbage();
+
+		for number in 0..CHAT_MESSAGE_BUFFER_LIMIT {
+			let (id, _count) = broadcast_buffer.dequeue_chat_message();
+			assert_eq!(id, SessionId::from(number));
+		}
+		assert!(broadcast_buffer.is_empty());
+	}
+
+	#[test]
+	fn collect_garbage_should_drop_deleted_chat_messages_and_outdated_edits() {
+		let mut broadcast_buffer = BroadcastBufferWithTestHelpers::default();
+		broadcast_buffer.enqueue_chat_message(SessionId::from(7), uint!(1));
+		broadcast_buffer.enqueue_chat_message(SessionId::from(7), uint!(2));
+		broadcast_buffer.enqueue_chat_edited("2", "first edit");
+		broadcast_buffer.enqueue_chat_edited("2", "second edit");
+		broadcast_buffer.enqueue_chat_deleted("1");
+		broadcast_buffer.enqueue_chat_deleted("0");
+
+		broadcast_buffer.inner.lock().collect_garbage();
+
+		let (id, count) = broadcast_buffer.dequeue_chat_message();
+		assert_eq!(id, SessionId::from(7));
+		assert_eq!(count, uint!(2));
+		match broadcast_buffer.dequeue_message() {
+			BroadcastMessage::ChatEdited(ChatEditedBroadcast { id, message, .. }) => {
+				assert_eq!(id, "2");
+				assert_eq!(message, "second edit");
+			}
+			message => panic!("Head of buffer was not ChatEdited, got {message:?}"),
+		}
+		match broadcast_buffer.dequeue_message() {
+			BroadcastMessage::ChatDeleted(ChatDeletedBroadcast { id, .. }) => assert_eq!(id, "0"),
+			message => panic!("Head of buffer was not ChatDeleted, got {message:?}"),
+		}
+		assert!(broadcast_buffer.is_empty());
+	}
+
 	#[test]
 	fn chat_messages_should_keep_clients_alive() {
 		let mut broadcast_buffer = BroadcastBufferWithTestHelpers::default();
@@ -364,9 +717,11 @@ mod test {
 	fn broadcast_number_must_not_stay_the_same() {
 		let broadcast_buffer = BroadcastBufferWithTestHelpers::default();
 
-		let message = BroadcastMessage::ClientJoined(ClientJoinedBroadcast {
+		let message = SharedBroadcast::from(ClientJoinedBroadcast {
 			id: 0.into(),
 			name: String::default(),
+			accepts_private_messages: true,
+			profile: ProfileResponse::default(),
 		});
 		broadcast_buffer.enqueue(message.clone(), 42);
 		broadcast_buffer.enqueue(message, 42);
@@ -377,9 +732,11 @@ mod test {
 	fn broadcast_number_must_not_skip() {
 		let broadcast_buffer = BroadcastBufferWithTestHelpers::default();
 
-		let message = BroadcastMessage::ClientJoined(ClientJoinedBroadcast {
+		let message = SharedBroadcast::from(ClientJoinedBroadcast {
 			id: 0.into(),
 			name: String::default(),
+			accepts_private_messages: true,
+			profile: ProfileResponse::default(),
 		});
 		broadcast_buffer.enqueue(message.clone(), 42);
 		broadcast_buffer.enqueue(message, 44);
@@ -390,9 +747,11 @@ mod test {
 	fn broadcast_number_must_not_decrease() {
 		let broadcast_buffer = BroadcastBufferWithTestHelpers::default();
 
-		let message = BroadcastMessage::ClientJoined(ClientJoinedBroadcast {
+		let message = SharedBroadcast::from(ClientJoinedBroadcast {
 			id: 0.into(),
 			name: String::default(),
+			accepts_private_messages: true,
+			profile: ProfileResponse::default(),
 		});
 		broadcast_buffer.enqueue(message.clone(), 42);
 		broadcast_buffer.enqueue(message, 41);
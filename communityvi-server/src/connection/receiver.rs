@@ -1,5 +1,6 @@
 use crate::connection::sender::MessageSender;
 use crate::message::client_request::{ClientRequestWithId, RequestIdOnly};
+use crate::message::format::MessageFormat;
 use crate::message::outgoing::error_message::{ErrorMessage, ErrorMessageType};
 use crate::message::{MessageError, WebSocketMessage};
 use futures_util::{Stream, StreamExt};
@@ -9,15 +10,18 @@ use tracing::error;
 pub struct MessageReceiver {
 	stream: Pin<Box<dyn Stream<Item = anyhow::Result<WebSocketMessage>> + Unpin + Send>>,
 	sender: MessageSender,
+	format: MessageFormat,
 }
 
 impl MessageReceiver {
+	/// Create a receiver that decodes messages in the same format as `sender` encodes them.
 	pub fn new<WebSocketStream>(websocket_stream: WebSocketStream, sender: MessageSender) -> Self
 	where
 		WebSocketStream: Stream<Item = anyhow::Result<WebSocketMessage>> + Unpin + Send + 'static,
 	{
 		Self {
 			stream: Box::pin(websocket_stream),
+			format: sender.format(),
 			sender,
 		}
 	}
@@ -61,15 +65,17 @@ impl MessageReceiver {
 				websocket_message => websocket_message,
 			};
 
-			let client_request = match ClientRequestWithId::try_from(&websocket_message) {
+			let client_request = match self.format.decode::<ClientRequestWithId>(&websocket_message) {
 				Ok(client_request) => client_request,
 				Err(message_error) => {
-					let request_id = RequestIdOnly::try_from(&websocket_message)
+					let request_id = self
+						.format
+						.decode::<RequestIdOnly>(&websocket_message)
 						.map(|request| request.request_id)
 						.ok();
 					let message = match message_error {
-						MessageError::DeserializationFailed { error, json } => {
-							format!("Failed to deserialize client message with error: {error}, message was: {json}")
+						MessageError::DeserializationFailed { error, message } => {
+							format!("Failed to deserialize client message with error: {error}, message was: {message}")
 						}
 						MessageError::WrongMessageType(message) => {
 							format!("Client request has incorrect message type. Message was: {message:?}")
@@ -1,7 +1,11 @@
+use crate::connection::shared_broadcast::SharedBroadcast;
 use crate::message::WebSocketMessage;
+use crate::message::compression::MessageCompression;
+use crate::message::format::MessageFormat;
 use crate::message::outgoing::OutgoingMessage;
-use crate::message::outgoing::broadcast_message::BroadcastMessage;
 use crate::message::outgoing::error_message::ErrorMessage;
+use crate::message::outgoing::moderator_notice::ModeratorNotice;
+use crate::message::outgoing::private_message::PrivateMessage;
 use crate::message::outgoing::success_message::SuccessMessage;
 use futures_util::{Sink, SinkExt};
 use js_int::UInt;
@@ -12,6 +16,8 @@ use tracing::error;
 #[derive(Clone)]
 pub struct MessageSender {
 	sink: Pin<Arc<tokio::sync::Mutex<dyn Sink<WebSocketMessage, Error = anyhow::Error> + Unpin + Send>>>,
+	format: MessageFormat,
+	compression: Option<MessageCompression>,
 }
 
 impl<WebSocketSink> From<WebSocketSink> for MessageSender
@@ -21,11 +27,29 @@ where
 	fn from(websocket_sink: WebSocketSink) -> Self {
 		Self {
 			sink: Arc::pin(tokio::sync::Mutex::new(websocket_sink)),
+			format: MessageFormat::default(),
+			compression: None,
 		}
 	}
 }
 
 impl MessageSender {
+	#[must_use]
+	pub fn with_format(mut self, format: MessageFormat) -> Self {
+		self.format = format;
+		self
+	}
+
+	pub fn format(&self) -> MessageFormat {
+		self.format
+	}
+
+	#[must_use]
+	pub fn with_compression(mut self, compression: Option<MessageCompression>) -> Self {
+		self.compression = compression;
+		self
+	}
+
 	pub async fn send_success_message(&self, message: SuccessMessage, request_id: UInt) -> Result<(), ()> {
 		let outgoing_message = OutgoingMessage::Success { request_id, message };
 		self.send_message(outgoing_message).await
@@ -36,16 +60,33 @@ impl MessageSender {
 		self.send_message(outgoing_message).await
 	}
 
-	pub async fn send_broadcast_message(&self, message: BroadcastMessage) -> Result<(), ()> {
-		let outgoing_message = OutgoingMessage::Broadcast { message };
+	pub async fn send_private_message(&self, message: PrivateMessage) -> Result<(), ()> {
+		let outgoing_message = OutgoingMessage::Private { message };
 		self.send_message(outgoing_message).await
 	}
 
-	async fn send_message(&self, message: OutgoingMessage) -> Result<(), ()> {
-		let mut sink = self.sink.lock().await;
+	pub async fn send_moderator_notice(&self, message: ModeratorNotice) -> Result<(), ()> {
+		let outgoing_message = OutgoingMessage::ModeratorNotice { message };
+		self.send_message(outgoing_message).await
+	}
+
+	/// Broadcasts are already shared between all clients, so they are only encoded once per format.
+	pub async fn send_broadcast_message(&self, message: &SharedBroadcast) -> Result<(), ()> {
+		self.send_websocket_message(message.websocket_message(self.format, self.compression))
+			.await
+	}
 
-		let websocket_message = WebSocketMessage::from(&message);
+	async fn send_message(&self, message: OutgoingMessage) -> Result<(), ()> {
+		let websocket_message = self.format.encode(&message);
+		let websocket_message = match self.compression {
+			Some(compression) => compression.compress(websocket_message),
+			None => websocket_message,
+		};
+		self.send_websocket_message(websocket_message).await
+	}
 
+	async fn send_websocket_message(&self, websocket_message: WebSocketMessage) -> Result<(), ()> {
+		let mut sink = self.sink.lock().await;
 		sink.send(websocket_message)
 			.await
 			.map_err(|error| error!("Error while sending message: {error:?}"))
@@ -0,0 +1,182 @@
+use crate::message::WebSocketMessage;
+use crate::message::compression::MessageCompression;
+use crate::message::format::MessageFormat;
+use crate::message::outgoing::OutgoingMessage;
+use crate::message::outgoing::broadcast_message::BroadcastMessage;
+use std::sync::{Arc, OnceLock};
+
+/// A broadcast that is shared between the broadcast buffers of all clients in a room.
+///
+/// It is serialized (and compressed) at most once per [`MessageFormat`], no matter how many clients it is sent to.
+#[derive(Clone, Debug)]
+pub struct SharedBroadcast {
+	inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+	message: BroadcastMessage,
+	json: EncodedMessages,
+	message_pack: EncodedMessages,
+}
+
+#[derive(Debug, Default)]
+struct EncodedMessages {
+	uncompressed: OnceLock<WebSocketMessage>,
+	compressed: OnceLock<WebSocketMessage>,
+}
+
+impl SharedBroadcast {
+	pub fn new(message: BroadcastMessage) -> Self {
+		Self {
+			inner: Arc::new(Inner {
+				message,
+				json: EncodedMessages::default(),
+				message_pack: EncodedMessages::default(),
+			}),
+		}
+	}
+
+	pub fn message(&self) -> &BroadcastMessage {
+		&self.inner.message
+	}
+
+	/// Get the encoded websocket message for `format`, encoding it on first use.
+	///
+	/// The compressed message is cached as well, so `compression` must be the same for all clients.
+	pub fn websocket_message(
+		&self,
+		format: MessageFormat,
+		compression: Option<MessageCompression>,
+	) -> WebSocketMessage {
+		let encoded_messages = match format {
+			MessageFormat::Json => &self.inner.json,
+			MessageFormat::MessagePack => &self.inner.message_pack,
+		};
+
+		let uncompressed = encoded_messages.uncompressed.get_or_init(|| {
+			format.encode(&OutgoingMessage::Broadcast {
+				message: self.inner.message.clone(),
+			})
+		});
+		match compression {
+			Some(compression) => encoded_messages
+				.compressed
+				.get_or_init(|| compression.compress(uncompressed.clone()))
+				.clone(),
+			None => uncompressed.clone(),
+		}
+	}
+}
+
+impl<Message: Into<BroadcastMessage>> From<Message> for SharedBroadcast {
+	fn from(message: Message) -> Self {
+		Self::new(message.into())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::connection::sender::MessageSender;
+	use crate::message::outgoing::broadcast_message::{ChatBroadcast, ClientJoinedBroadcast};
+	use crate::message::outgoing::success_message::ProfileResponse;
+	use crate::room::session_id::SessionId;
+	use crate::utils::fake_message_sender::FakeMessageSender;
+	use js_int::UInt;
+	use std::time::Instant;
+
+	fn client_joined() -> SharedBroadcast {
+		ClientJoinedBroadcast {
+			id: SessionId::from(42),
+			name: "Ferris".to_string(),
+			accepts_private_messages: true,
+			profile: ProfileResponse::default(),
+		}
+		.into()
+	}
+
+	#[test]
+	fn should_encode_the_same_as_an_outgoing_broadcast() {
+		let broadcast = client_joined();
+		let outgoing_message = OutgoingMessage::Broadcast {
+			message: broadcast.message().clone(),
+		};
+
+		for format in MessageFormat::ALL {
+			assert_eq!(
+				format.encode(&outgoing_message),
+				broadcast.websocket_message(format, None)
+			);
+		}
+	}
+
+	#[test]
+	fn should_share_the_encoded_message_between_clones() {
+		let broadcast = client_joined();
+		let clone = broadcast.clone();
+
+		let WebSocketMessage::Text(json) = broadcast.websocket_message(MessageFormat::Json, None) else {
+			panic!("Expected a text message.");
+		};
+		let WebSocketMessage::Text(cloned_json) = clone.websocket_message(MessageFormat::Json, None) else {
+			panic!("Expected a text message.");
+		};
+
+		assert_eq!(json.as_str().as_ptr(), cloned_json.as_str().as_ptr());
+	}
+
+	/// Checks that sharing the encoded broadcast between all clients is faster than encoding
+	/// every broadcast once per client (as if each client had its own copy).
+	///
+	/// Run with `cargo test --release shared_broadcast_benchmark -- --ignored`
+	#[tokio::test]
+	#[ignore = "benchmark"]
+	async fn shared_broadcast_benchmark() {
+		const CLIENT_COUNT: usize = 500;
+		const BROADCAST_COUNT: u32 = 100;
+
+		let senders = (0..CLIENT_COUNT)
+			.map(|index| {
+				let format = MessageFormat::ALL[index % MessageFormat::ALL.len()];
+				MessageSender::from(FakeMessageSender::default()).with_format(format)
+			})
+			.collect::<Vec<_>>();
+		let broadcasts = (0..BROADCAST_COUNT)
+			.map(|counter| ChatBroadcast {
+				id: counter.to_string(),
+				sender_id: SessionId::from(42),
+				sender_name: "Ferris".to_string(),
+				message: "The quick brown fox jumps over the lazy dog. ".repeat(10),
+				counter: UInt::from(counter),
+				medium_position_in_milliseconds: None,
+				reply_to: None,
+				mentions: Vec::new(),
+				mentions_you: false,
+			})
+			.collect::<Vec<_>>();
+
+		let start = Instant::now();
+		for broadcast in &broadcasts {
+			for sender in &senders {
+				let broadcast = SharedBroadcast::from(broadcast.clone());
+				sender.send_broadcast_message(&broadcast).await.unwrap();
+			}
+		}
+		let per_client = start.elapsed();
+
+		let start = Instant::now();
+		for broadcast in &broadcasts {
+			let broadcast = SharedBroadcast::from(broadcast.clone());
+			for sender in &senders {
+				sender.send_broadcast_message(&broadcast).await.unwrap();
+			}
+		}
+		let shared = start.elapsed();
+
+		assert!(
+			shared < per_client,
+			"Encoding {BROADCAST_COUNT} broadcasts once took {shared:?}, encoding them for each of {CLIENT_COUNT} clients took {per_client:?}"
+		);
+	}
+}
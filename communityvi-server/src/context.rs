@@ -1,6 +1,7 @@
 use crate::configuration::Configuration;
 use crate::database::libsql::{LibSqlRepository, create_pool};
 use crate::database::{Database, Repository};
+use crate::media_directory::MediaDirectory;
 use crate::reference_time::ReferenceTimer;
 use crate::user::UserService;
 use crate::utils::time_source::TimeSource;
@@ -15,6 +16,7 @@ pub struct ApplicationContext {
 	pub user_service: UserService,
 	pub database: Arc<dyn Database>,
 	pub repository: Arc<dyn Repository>,
+	pub media_directory: MediaDirectory,
 }
 
 impl ApplicationContext {
@@ -29,6 +31,11 @@ impl ApplicationContext {
 
 		let user_service = UserService::new(repository.clone());
 
+		let media_directory = match &configuration.media_directory {
+			Some(directory) => MediaDirectory::index(directory)?,
+			None => MediaDirectory::default(),
+		};
+
 		Ok(Self {
 			configuration,
 			time_source,
@@ -36,6 +43,7 @@ impl ApplicationContext {
 			user_service,
 			database,
 			repository,
+			media_directory,
 		})
 	}
 }
@@ -1,5 +1,7 @@
+use crate::bookmark::repository::BookmarkRepository;
 use crate::chat::repository::ChatRepository;
 use crate::database::error::DatabaseError;
+use crate::poll::repository::PollRepository;
 use crate::room::repository::RoomRepository;
 use crate::user::repository::UserRepository;
 use async_trait::async_trait;
@@ -27,10 +29,14 @@ pub trait Connection: Any + Send {
 
 assert_obj_safe!(Connection);
 
-pub trait Repository: UserRepository + RoomRepository + ChatRepository + Send + Sync + 'static {
+pub trait Repository:
+	UserRepository + RoomRepository + ChatRepository + BookmarkRepository + PollRepository + Send + Sync + 'static
+{
 	fn user(&self) -> &dyn UserRepository;
 	fn room(&self) -> &dyn RoomRepository;
 	fn chat(&self) -> &dyn ChatRepository;
+	fn bookmark(&self) -> &dyn BookmarkRepository;
+	fn poll(&self) -> &dyn PollRepository;
 }
 
 assert_obj_safe!(Repository);
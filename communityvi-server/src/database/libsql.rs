@@ -5,17 +5,21 @@ use deadpool::managed::{Object, PoolError};
 use std::any::Any;
 use std::ops::DerefMut;
 
+mod bookmark;
 mod chat;
 mod migration;
+mod poll;
 mod pool;
 mod room;
 #[cfg(test)]
 pub mod test_utils;
 mod user;
 
+use crate::bookmark::repository::BookmarkRepository;
 use crate::chat::repository::ChatRepository;
 use crate::database::error::DatabaseError;
 use crate::database::libsql::pool::LibSqlManager;
+use crate::poll::repository::PollRepository;
 use crate::room::repository::RoomRepository;
 use crate::user::repository::UserRepository;
 pub use pool::LibSqlPool;
@@ -110,4 +114,12 @@ impl Repository for LibSqlRepository {
 	fn chat(&self) -> &dyn ChatRepository {
 		self
 	}
+
+	fn bookmark(&self) -> &dyn BookmarkRepository {
+		self
+	}
+
+	fn poll(&self) -> &dyn PollRepository {
+		self
+	}
 }
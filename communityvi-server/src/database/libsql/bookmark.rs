@@ -0,0 +1,99 @@
+use crate::bookmark::model::Bookmark;
+use crate::bookmark::repository::BookmarkRepository;
+use crate::database::Connection;
+use crate::database::error::DatabaseError;
+use crate::database::libsql::{LibSqlRepository, libsql_connection};
+use crate::types::date_time::DateTime;
+use crate::types::uuid::Uuid;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::Duration;
+
+#[async_trait]
+impl BookmarkRepository for LibSqlRepository {
+	async fn create(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		medium_id: &str,
+		position: Duration,
+		label: &str,
+		created_at: DateTime,
+	) -> Result<Bookmark, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let uuid = Uuid::new_v4();
+		let mut rows = connection
+			.query(
+				r"INSERT INTO bookmark(
+				uuid, room_uuid, medium_id, position_ms, label, created_at
+			) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+			RETURNING
+				uuid,
+				room_uuid,
+				medium_id,
+				position_ms,
+				label,
+				created_at
+			",
+				(
+					uuid,
+					room_uuid,
+					medium_id,
+					position.num_milliseconds(),
+					label,
+					created_at,
+				),
+			)
+			.await?;
+
+		rows.next()
+			.await?
+			.ok_or_else(|| DatabaseError::NotFound(anyhow!("not found")))?
+			.try_into()
+			.map_err(DatabaseError::Decode)
+	}
+
+	async fn remove(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		bookmark_uuid: Uuid,
+	) -> Result<bool, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let removed_rows = connection
+			.execute(
+				r"DELETE FROM bookmark WHERE room_uuid = ?1 AND uuid = ?2",
+				(room_uuid, bookmark_uuid),
+			)
+			.await?;
+
+		Ok(removed_rows > 0)
+	}
+
+	async fn get_all_for_medium(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		medium_id: &str,
+	) -> Result<Vec<Bookmark>, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let mut rows = connection
+			.query(
+				r"SELECT uuid, room_uuid, medium_id, position_ms, label, created_at
+				FROM bookmark
+				WHERE room_uuid = ?1 AND medium_id = ?2
+				ORDER BY position_ms ASC",
+				(room_uuid, medium_id),
+			)
+			.await?;
+
+		let mut bookmarks = Vec::new();
+		while let Some(row) = rows.next().await? {
+			bookmarks.push(row.try_into().map_err(DatabaseError::Decode)?);
+		}
+		Ok(bookmarks)
+	}
+}
@@ -1,10 +1,11 @@
-use crate::chat::model::ChatMessage;
+use crate::chat::model::{ChatContent, ChatMessage, PrivateChatMessage};
 use crate::chat::repository::ChatRepository;
 use crate::database::Connection;
 use crate::database::error::DatabaseError;
 use crate::database::libsql::{LibSqlRepository, libsql_connection};
 use crate::types::date_time::DateTime;
 use crate::types::uuid::Uuid;
+use crate::user::model::User;
 use anyhow::anyhow;
 use async_trait::async_trait;
 
@@ -16,26 +17,191 @@ impl ChatRepository for LibSqlRepository {
 		room_uuid: Uuid,
 		user_uuid: Uuid,
 		user_name: String,
-		message: String,
+		ChatContent {
+			kind,
+			message,
+			medium_anchor,
+			reply_to,
+		}: ChatContent,
 		created_at: DateTime,
 	) -> Result<ChatMessage, DatabaseError> {
 		let connection = libsql_connection(connection)?;
 
 		let uuid = Uuid::new_v4();
+		let (medium_id, medium_position_ms) = medium_anchor
+			.map(|anchor| (anchor.medium_id, anchor.position.num_milliseconds()))
+			.unzip();
 		let mut rows = connection
 			.query(
 				"INSERT INTO chat_message(
-				uuid, room_uuid, user_uuid, user_name, message, created_at
-			) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+				uuid, room_uuid, user_uuid, user_name, message, created_at, kind, medium_id, medium_position_ms,
+				reply_to_uuid
+			) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
 			RETURNING
 				uuid,
 				room_uuid,
 				user_uuid,
 				user_name,
 				message,
-				created_at
+				created_at,
+				kind,
+				medium_id,
+				medium_position_ms,
+				edited_at,
+				reply_to_uuid
 			",
-				(uuid, room_uuid, user_uuid, user_name, message, created_at),
+				libsql::params![
+					uuid,
+					room_uuid,
+					user_uuid,
+					user_name,
+					message,
+					created_at,
+					kind.as_str(),
+					medium_id,
+					medium_position_ms,
+					reply_to
+				],
+			)
+			.await?;
+
+		rows.next()
+			.await?
+			.ok_or_else(|| DatabaseError::NotFound(anyhow!("not found")))?
+			.try_into()
+			.map_err(DatabaseError::Decode)
+	}
+
+	async fn get(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		chat_message_uuid: Uuid,
+	) -> Result<Option<ChatMessage>, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let mut rows = connection
+			.query(
+				r"SELECT
+					uuid, room_uuid, user_uuid, user_name, message, created_at, kind, medium_id, medium_position_ms,
+					edited_at, reply_to_uuid
+				FROM chat_message
+				WHERE room_uuid = ?1 AND uuid = ?2",
+				(room_uuid, chat_message_uuid),
+			)
+			.await?;
+
+		rows.next()
+			.await?
+			.map(TryInto::try_into)
+			.transpose()
+			.map_err(DatabaseError::Decode)
+	}
+
+	async fn update_message(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		chat_message_uuid: Uuid,
+		message: String,
+		edited_at: DateTime,
+	) -> Result<ChatMessage, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let mut rows = connection
+			.query(
+				r"UPDATE chat_message
+				SET message = ?3, edited_at = ?4
+				WHERE room_uuid = ?1 AND uuid = ?2
+				RETURNING
+					uuid, room_uuid, user_uuid, user_name, message, created_at, kind, medium_id, medium_position_ms,
+					edited_at, reply_to_uuid",
+				(room_uuid, chat_message_uuid, message, edited_at),
+			)
+			.await?;
+
+		rows.next()
+			.await?
+			.ok_or_else(|| DatabaseError::NotFound(anyhow!("not found")))?
+			.try_into()
+			.map_err(DatabaseError::Decode)
+	}
+
+	async fn remove(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		chat_message_uuid: Uuid,
+	) -> Result<bool, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let removed_rows = connection
+			.execute(
+				r"DELETE FROM chat_message WHERE room_uuid = ?1 AND uuid = ?2",
+				(room_uuid, chat_message_uuid),
+			)
+			.await?;
+
+		Ok(removed_rows > 0)
+	}
+
+	async fn get_all_anchored_to_medium(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		medium_id: &str,
+	) -> Result<Vec<ChatMessage>, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let mut rows = connection
+			.query(
+				r"SELECT
+					uuid, room_uuid, user_uuid, user_name, message, created_at, kind, medium_id, medium_position_ms,
+					edited_at, reply_to_uuid
+				FROM chat_message
+				WHERE room_uuid = ?1 AND medium_id = ?2
+				ORDER BY medium_position_ms ASC, created_at ASC",
+				(room_uuid, medium_id),
+			)
+			.await?;
+
+		let mut messages = Vec::new();
+		while let Some(row) = rows.next().await? {
+			messages.push(row.try_into().map_err(DatabaseError::Decode)?);
+		}
+		Ok(messages)
+	}
+
+	async fn create_private_message(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		sender: &User,
+		recipient: &User,
+		message: String,
+		created_at: DateTime,
+	) -> Result<PrivateChatMessage, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let uuid = Uuid::new_v4();
+		let mut rows = connection
+			.query(
+				r"INSERT INTO private_message(
+					uuid, room_uuid, sender_user_uuid, sender_name, recipient_user_uuid, recipient_name, message, created_at
+				) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+				RETURNING
+					uuid, room_uuid, sender_user_uuid, sender_name, recipient_user_uuid, recipient_name, message,
+					created_at",
+				libsql::params![
+					uuid,
+					room_uuid,
+					sender.uuid,
+					sender.name.as_str(),
+					recipient.uuid,
+					recipient.name.as_str(),
+					message,
+					created_at
+				],
 			)
 			.await?;
 
@@ -0,0 +1,208 @@
+use crate::database::Connection;
+use crate::database::error::DatabaseError;
+use crate::database::libsql::{LibSqlRepository, libsql_connection};
+use crate::poll::model::{NewPoll, Poll, PollOption, PollResults};
+use crate::poll::repository::PollRepository;
+use crate::types::date_time::DateTime;
+use crate::types::uuid::Uuid;
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+#[async_trait]
+impl PollRepository for LibSqlRepository {
+	async fn create(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		user_uuid: Uuid,
+		user_name: String,
+		NewPoll {
+			kind,
+			question,
+			options,
+			medium_version,
+			deadline,
+		}: NewPoll,
+		created_at: DateTime,
+	) -> Result<PollResults, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let uuid = Uuid::new_v4();
+		let mut rows = connection
+			.query(
+				r"INSERT INTO poll(
+				uuid, room_uuid, kind, question, created_by_user_uuid, created_by_name, medium_version, deadline,
+				created_at
+			) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+			RETURNING
+				uuid,
+				room_uuid,
+				kind,
+				question,
+				created_by_user_uuid,
+				created_by_name,
+				medium_version,
+				deadline,
+				closed_at,
+				created_at
+			",
+				libsql::params![
+					uuid,
+					room_uuid,
+					kind.as_str(),
+					question,
+					user_uuid,
+					user_name,
+					medium_version.map(i64::from),
+					deadline,
+					created_at,
+				],
+			)
+			.await?;
+		let poll: Poll = rows
+			.next()
+			.await?
+			.ok_or_else(|| DatabaseError::NotFound(anyhow!("not found")))?
+			.try_into()
+			.map_err(DatabaseError::Decode)?;
+
+		for (option_index, label) in options.iter().enumerate() {
+			let option_index = i64::try_from(option_index).map_err(|error| DatabaseError::Encode(error.into()))?;
+			connection
+				.execute(
+					r"INSERT INTO poll_option(poll_uuid, option_index, label) VALUES (?1, ?2, ?3)",
+					(poll.uuid, option_index, label.as_str()),
+				)
+				.await?;
+		}
+
+		Ok(PollResults {
+			poll,
+			options: options
+				.into_iter()
+				.map(|label| PollOption { label, votes: 0 })
+				.collect(),
+		})
+	}
+
+	async fn get(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		poll_uuid: Uuid,
+	) -> Result<Option<PollResults>, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let mut rows = connection
+			.query(
+				r"SELECT
+				uuid,
+				room_uuid,
+				kind,
+				question,
+				created_by_user_uuid,
+				created_by_name,
+				medium_version,
+				deadline,
+				closed_at,
+				created_at
+			FROM poll
+			WHERE room_uuid = ?1 AND uuid = ?2",
+				(room_uuid, poll_uuid),
+			)
+			.await?;
+		let Some(row) = rows.next().await? else {
+			return Ok(None);
+		};
+		let poll: Poll = row.try_into().map_err(DatabaseError::Decode)?;
+
+		let mut rows = connection
+			.query(
+				r"SELECT poll_option.label, count(poll_vote.voter_user_uuid)
+				FROM poll_option
+				LEFT JOIN poll_vote
+					ON poll_vote.poll_uuid = poll_option.poll_uuid AND poll_vote.option_index = poll_option.option_index
+				WHERE poll_option.poll_uuid = ?1
+				GROUP BY poll_option.option_index
+				ORDER BY poll_option.option_index ASC",
+				[poll.uuid],
+			)
+			.await?;
+		let mut options = Vec::new();
+		while let Some(row) = rows.next().await? {
+			options.push(row.try_into().map_err(DatabaseError::Decode)?);
+		}
+
+		Ok(Some(PollResults { poll, options }))
+	}
+
+	async fn get_open(&self, connection: &mut dyn Connection, room_uuid: Uuid) -> Result<Vec<Poll>, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let mut rows = connection
+			.query(
+				r"SELECT
+				uuid,
+				room_uuid,
+				kind,
+				question,
+				created_by_user_uuid,
+				created_by_name,
+				medium_version,
+				deadline,
+				closed_at,
+				created_at
+			FROM poll
+			WHERE room_uuid = ?1 AND closed_at IS NULL
+			ORDER BY created_at ASC",
+				[room_uuid],
+			)
+			.await?;
+		let mut polls = Vec::new();
+		while let Some(row) = rows.next().await? {
+			polls.push(row.try_into().map_err(DatabaseError::Decode)?);
+		}
+		Ok(polls)
+	}
+
+	async fn vote(
+		&self,
+		connection: &mut dyn Connection,
+		poll_uuid: Uuid,
+		voter_user_uuid: Uuid,
+		option_index: u32,
+		voted_at: DateTime,
+	) -> Result<(), DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		connection
+			.execute(
+				r"INSERT INTO poll_vote(poll_uuid, voter_user_uuid, option_index, voted_at) VALUES (?1, ?2, ?3, ?4)
+				ON CONFLICT (poll_uuid, voter_user_uuid)
+					DO UPDATE SET option_index = excluded.option_index, voted_at = excluded.voted_at",
+				(poll_uuid, voter_user_uuid, option_index, voted_at),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn close(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		poll_uuid: Uuid,
+		closed_at: DateTime,
+	) -> Result<bool, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let closed_rows = connection
+			.execute(
+				r"UPDATE poll SET closed_at = ?3 WHERE room_uuid = ?1 AND uuid = ?2 AND closed_at IS NULL",
+				(room_uuid, poll_uuid, closed_at),
+			)
+			.await?;
+
+		Ok(closed_rows > 0)
+	}
+}
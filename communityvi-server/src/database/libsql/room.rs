@@ -5,10 +5,9 @@ use crate::room::model::Room;
 use crate::room::repository::RoomRepository;
 use crate::types::uuid::Uuid;
 use crate::user::model::User;
-use anyhow::{anyhow, bail};
+use anyhow::anyhow;
 use async_trait::async_trait;
 use futures_util::{StreamExt, TryStreamExt, stream};
-use libsql::{Row, Value};
 
 #[async_trait]
 impl RoomRepository for LibSqlRepository {
@@ -68,41 +67,11 @@ impl RoomRepository for LibSqlRepository {
 		connection: &mut dyn Connection,
 		room_uuid: Uuid,
 	) -> Result<Vec<User>, DatabaseError> {
-		struct OptionalUser {
-			uuid: Option<Uuid>,
-			name: Option<String>,
-			normalized_name: Option<String>,
-		}
-
-		impl TryFrom<Row> for OptionalUser {
-			type Error = anyhow::Error;
-
-			fn try_from(row: Row) -> Result<Self, Self::Error> {
-				let uuid = row.get_value(0)?;
-				let name = row.get_value(1)?;
-				let normalized_name = row.get_value(2)?;
-
-				Ok(Self {
-					uuid: if uuid.is_null() { None } else { Some(uuid.try_into()?) },
-					name: match name {
-						Value::Null => None,
-						Value::Text(name) => Some(name),
-						_ => bail!("Invalid type for uuid"),
-					},
-					normalized_name: match normalized_name {
-						Value::Null => None,
-						Value::Text(normalized_name) => Some(normalized_name),
-						_ => bail!("Invalid type for normalized_name"),
-					},
-				})
-			}
-		}
-
 		let connection = libsql_connection(connection)?;
 
 		let mut rows = connection
 			.query(
-				r"SELECT u.uuid, u.name, u.normalized_name
+				r"SELECT u.uuid, u.name, u.normalized_name, u.presence, u.color, u.avatar, u.pronouns
 				FROM room r
 				LEFT JOIN room_user ru ON ru.room_uuid = r.uuid
 				LEFT JOIN user u ON ru.user_uuid = u.uuid
@@ -123,22 +92,12 @@ impl RoomRepository for LibSqlRepository {
 				};
 				Ok(Some((row, rows)))
 			}))
-			.and_then(async |row| row.try_into().map_err(DatabaseError::Decode))
-			.try_filter_map(async |optional_user| {
-				let OptionalUser {
-					uuid: Some(uuid),
-					name: Some(name),
-					normalized_name: Some(normalized_name),
-				} = optional_user
-				else {
+			// The room is joined with its users, so a room without users results in a single row of nulls.
+			.try_filter_map(async |row| {
+				if row.get_value(0)?.is_null() {
 					return Ok(None);
-				};
-
-				Ok(Some(User {
-					uuid,
-					name,
-					normalized_name,
-				}))
+				}
+				User::try_from(row).map(Some).map_err(DatabaseError::Decode)
 			})
 			.try_collect()
 			.await
@@ -2,7 +2,7 @@ use super::{LibSqlRepository, libsql_connection};
 use crate::database::Connection;
 use crate::database::error::DatabaseError;
 use crate::types::uuid::Uuid;
-use crate::user::model::User;
+use crate::user::model::{Avatar, Profile, User};
 use crate::user::repository::UserRepository;
 use anyhow::anyhow;
 use async_trait::async_trait;
@@ -14,7 +14,7 @@ impl UserRepository for LibSqlRepository {
 
 		let mut rows = connection
 			.query(
-				r"SELECT uuid, name, normalized_name
+				r"SELECT uuid, name, normalized_name, presence, color, avatar, pronouns
 			FROM user
 			WHERE uuid = ?1",
 				[user_uuid],
@@ -43,7 +43,11 @@ impl UserRepository for LibSqlRepository {
 			RETURNING
 				uuid,
 				name,
-				normalized_name",
+				normalized_name,
+				presence,
+				color,
+				avatar,
+				pronouns",
 				(uuid, name, normalized_name),
 			)
 			.await?;
@@ -55,6 +59,73 @@ impl UserRepository for LibSqlRepository {
 			.map_err(DatabaseError::Decode)
 	}
 
+	async fn update_name(
+		&self,
+		connection: &mut dyn Connection,
+		user_uuid: Uuid,
+		name: &str,
+		normalized_name: &str,
+	) -> Result<User, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let mut rows = connection
+			.query(
+				r"UPDATE user SET name = ?2, normalized_name = ?3 WHERE uuid = ?1
+			RETURNING
+				uuid,
+				name,
+				normalized_name,
+				presence,
+				color,
+				avatar,
+				pronouns",
+				(user_uuid, name, normalized_name),
+			)
+			.await?;
+
+		rows.next()
+			.await?
+			.ok_or_else(|| DatabaseError::NotFound(anyhow!("not found")))?
+			.try_into()
+			.map_err(DatabaseError::Decode)
+	}
+
+	async fn update_profile(
+		&self,
+		connection: &mut dyn Connection,
+		user_uuid: Uuid,
+		profile: &Profile,
+	) -> Result<User, DatabaseError> {
+		let connection = libsql_connection(connection)?;
+
+		let mut rows = connection
+			.query(
+				r"UPDATE user SET presence = ?2, color = ?3, avatar = ?4, pronouns = ?5 WHERE uuid = ?1
+			RETURNING
+				uuid,
+				name,
+				normalized_name,
+				presence,
+				color,
+				avatar,
+				pronouns",
+				(
+					user_uuid,
+					profile.presence.as_str(),
+					profile.color.as_deref(),
+					profile.avatar.map(Avatar::as_str),
+					profile.pronouns.as_deref(),
+				),
+			)
+			.await?;
+
+		rows.next()
+			.await?
+			.ok_or_else(|| DatabaseError::NotFound(anyhow!("not found")))?
+			.try_into()
+			.map_err(DatabaseError::Decode)
+	}
+
 	async fn remove(&self, connection: &mut dyn Connection, user_uuid: Uuid) -> Result<(), DatabaseError> {
 		let connection = libsql_connection(connection)?;
 
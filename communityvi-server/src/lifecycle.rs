@@ -1,54 +1,96 @@
+use crate::bookmark::model::Bookmark;
+use crate::chat::command::{ChatInput, CommandContext, CommandError, CommandOutput, CommandRegistry};
+use crate::chat::filter::{ChatFilterRejection, FilteredChatMessage, filter_chat_message, filter_edited_chat_message};
+use crate::configuration::Configuration;
 use crate::connection::receiver::{MessageReceiver, ReceivedMessage};
 use crate::connection::sender::MessageSender;
 use crate::context::ApplicationContext;
+use crate::database::error::DatabaseError;
+use crate::media_directory::MediaDirectory;
 use crate::message::client_request::{
-	ChatRequest, ClientRequest, InsertMediumRequest, PauseRequest, PlayRequest, RegisterRequest,
+	AddBookmarkRequest, AddSubtitleTrackRequest, ChangeNameRequest, ChatRequest, ClientRequest, ClosePollRequest,
+	CreatePollRequest, DeleteChatRequest, EditChatRequest, FingerprintReportRequest, InsertMediumRequest,
+	JumpToBookmarkRequest, PauseRequest, PlayRequest, PollRequest, PositionReportRequest, PrivateMessageRequest,
+	ReactionRequest, ReadinessRequest, RegisterRequest, RemoveBookmarkRequest, SchedulePlayRequest,
+	SubtitleOffsetRequest, TimeSyncRequest, TypingRequest, UpdateProfileRequest, VoteRequest,
 };
 use crate::message::outgoing::broadcast_message::{
-	ClientJoinedBroadcast, ClientLeftBroadcast, LeftReason, MediumStateChangedBroadcast, VersionedMediumBroadcast,
+	BookmarksChangedBroadcast, BroadcastMessage, ChatDeletedBroadcast, ChatEditedBroadcast, ClientJoinedBroadcast,
+	ClientLeftBroadcast, FingerprintMismatchBroadcast, LeftReason, MediumStateChangedBroadcast, MismatchingClient,
+	PlaybackScheduledBroadcast, PollBroadcast, SubtitlesChangedBroadcast, TypingBroadcast, VersionedMediumBroadcast,
 };
 use crate::message::outgoing::error_message::{ErrorMessage, ErrorMessageType};
-use crate::message::outgoing::success_message::{ClientResponse, SuccessMessage};
+use crate::message::outgoing::moderator_notice::ChatFlaggedNotice;
+use crate::message::outgoing::success_message::{
+	ClientResponse, MediumReactionResponse, SuccessMessage, TimeSyncResponse, VersionedMediumResponse,
+};
+use crate::poll::model::{NewPoll, Poll, PollKind, PollResults, YES_OPTION};
 use crate::room::Room;
 use crate::room::client::Client;
-use crate::room::error::RoomError;
-use crate::room::medium::Medium;
+use crate::room::error::{ChatError, ChatModificationError, PollError, PrivateMessageError, RoomError};
+use crate::room::medium::fingerprint::Fingerprint;
+use crate::room::medium::fixed_length::FixedLengthMedium;
+use crate::room::medium::playback_state::PlaybackState;
+use crate::room::medium::subtitles::Subtitles;
+use crate::room::medium::url::UrlMedium;
+use crate::room::medium::{Medium, SubtitleUpdateError, VersionedMedium};
+use crate::room::scheduled_play::ScheduledPlay;
+use crate::types::date_time::DateTime;
+use crate::types::uuid::Uuid;
+use crate::user::ProfileError;
+use crate::user::model::{Profile, User};
 use crate::utils::time_source::TimeSource;
 use chrono::Duration;
 use futures_channel::mpsc;
 use futures_util::{SinkExt, StreamExt};
 use governor::{Quota, RateLimiter};
-use js_int::UInt;
+use js_int::{Int, UInt};
 use nonzero_ext::nonzero;
+use std::sync::LazyLock;
 use tracing::{debug, error, info};
 
 /// Once this count of heartbeats are missed, the client is kicked.
 const MISSED_HEARTBEAT_LIMIT: u32 = 3;
 
+/// Clients stop typing if they don't report it again within this time.
+const TYPING_EXPIRY: std::time::Duration = std::time::Duration::from_secs(5);
+
+static CHAT_COMMANDS: LazyLock<CommandRegistry> = LazyLock::new(CommandRegistry::with_built_in_commands);
+
 pub async fn run_client(
 	application_context: ApplicationContext,
 	room: Room,
 	message_sender: MessageSender,
 	message_receiver: MessageReceiver,
 ) {
-	let Some((client, message_receiver)) = register_client(room.clone(), message_sender, message_receiver).await else {
+	let Some((client, message_receiver)) = register_client(
+		&application_context.configuration,
+		room.clone(),
+		message_sender,
+		message_receiver,
+	)
+	.await
+	else {
 		return;
 	};
 	let session_id = client.id();
-	let client_name = client.name().to_string();
 	let (pong_sender, pong_receiver) = mpsc::channel(MISSED_HEARTBEAT_LIMIT as usize);
 
 	let left_reason = tokio::select! {
-		() = handle_messages(&room, client.clone(), message_receiver, pong_sender) => LeftReason::Closed,
+		() = handle_messages(&application_context, &room, client.clone(), message_receiver, pong_sender) => LeftReason::Closed,
 		() = send_broadcasts(client.clone()) => LeftReason::Closed,
+		() = client.kicked() => LeftReason::Kicked,
+		() = expire_typing(&room, &client, &application_context.time_source) => LeftReason::Closed,
 		left_reason = heartbeat(
-			client,
+			client.clone(),
 			&application_context.time_source,
 			pong_receiver,
 			application_context.configuration.heartbeat_interval,
 			application_context.configuration.missed_heartbeat_limit
 		) => left_reason,
 	};
+	// Read after leaving because the client might have been renamed in the meantime.
+	let client_name = client.name();
 	if let Err(error) = room.remove_client(session_id).await {
 		error!("Failed to remove client '{client_name}' with id {session_id} from room: {error}");
 	}
@@ -61,9 +103,15 @@ pub async fn run_client(
 	})
 	.await
 	.ok();
+
+	// The client might have been the last one buffering
+	if let Err(error) = update_buffering_pause(&room).await {
+		error!("Failed sending broadcast: {error}");
+	}
 }
 
 async fn register_client(
+	configuration: &Configuration,
 	room: Room,
 	message_sender: MessageSender,
 	mut message_receiver: MessageReceiver,
@@ -81,7 +129,12 @@ async fn register_client(
 		Request(request) => request,
 	};
 
-	let ClientRequest::Register(RegisterRequest { name }) = request.request else {
+	let ClientRequest::Register(RegisterRequest {
+		name,
+		accepts_private_messages,
+		moderator_token,
+	}) = request.request
+	else {
 		error!("Client registration failed. Invalid request: {request:?}");
 
 		let _ = message_sender
@@ -96,37 +149,18 @@ async fn register_client(
 		return None;
 	};
 
-	let (client, existing_clients) = match room.add_client_and_return_existing(&name, message_sender.clone()).await {
+	let is_moderator = moderator_token.is_some_and(|token| configuration.is_moderator(&name, &token));
+
+	let (client, existing_clients) = match room
+		.add_client_and_return_existing(&name, is_moderator, message_sender.clone())
+		.await
+	{
 		Ok(success) => success,
 		Err(error) => {
-			use RoomError::*;
-			let error_response = match &error {
-				EmptyClientName | ClientNameTooLong => {
-					error!("Client registration failed. Tried to register with invalid name.");
-					ErrorMessageType::InvalidFormat
-				}
-				ClientNameAlreadyInUse => {
-					error!("Client registration failed. Tried to register with name that is already used.");
-					ErrorMessageType::InvalidOperation
-				}
-				RoomFull => {
-					error!("Client registration failed. Room is full.");
-					ErrorMessageType::InvalidOperation
-				}
-				Database(error) => {
-					error!("Internal error: {error}.");
-					ErrorMessageType::InternalServerError
-				}
-				Overflow(error) => {
-					error!("{error}");
-					ErrorMessageType::InternalServerError
-				}
-			};
-
 			let _ = message_sender
 				.send_error_message(
 					ErrorMessage::builder()
-						.error(error_response)
+						.error(registration_error_type(&error))
 						.message(error.to_string())
 						.build(),
 					Some(request.request_id),
@@ -136,33 +170,79 @@ async fn register_client(
 			return None;
 		}
 	};
+	client.set_accepts_private_messages(accepts_private_messages);
 
-	let clients = existing_clients.into_iter().map(ClientResponse::from).collect();
+	let current_medium = room.medium();
+	let bookmarks = room
+		.bookmarks(&current_medium.medium)
+		.await
+		.inspect_err(|error| error!("Failed to get bookmarks: {error}"))
+		.unwrap_or_default();
+	let clients = existing_clients
+		.iter()
+		.map(|existing_client| ClientResponse::new(existing_client, current_medium.version))
+		.collect();
 	let hello_response = SuccessMessage::Hello {
 		id: client.id(),
+		session_token: client.session_token().to_string(),
 		clients,
-		current_medium: room.medium().into(),
+		current_medium: VersionedMediumResponse::new(current_medium, bookmarks),
 	};
 	if client.send_success_message(hello_response, request.request_id).await {
 		let id = client.id();
-		let name = client.name().to_string();
+		let User { name, profile, .. } = client.user();
 
 		info!("Registered client: {id} {name}");
 
-		room.broadcast(ClientJoinedBroadcast { id, name })
-			.await
-			.inspect_err(|error| todo!("Log error: {error}"))
-			.ok()?;
+		room.broadcast(ClientJoinedBroadcast {
+			id,
+			name,
+			accepts_private_messages,
+			profile: profile.into(),
+		})
+		.await
+		.inspect_err(|error| todo!("Log error: {error}"))
+		.ok()?;
 		Some((client, message_receiver))
 	} else {
 		None
 	}
 }
 
+fn registration_error_type(error: &RoomError) -> ErrorMessageType {
+	use RoomError::*;
+	match error {
+		EmptyClientName | ClientNameTooLong => {
+			error!("Client registration failed. Tried to register with invalid name.");
+			ErrorMessageType::InvalidFormat
+		}
+		ClientNameAlreadyInUse => {
+			error!("Client registration failed. Tried to register with name that is already used.");
+			ErrorMessageType::InvalidOperation
+		}
+		ClientNameReserved => {
+			error!("Client registration failed. Tried to register with the name of a moderator.");
+			ErrorMessageType::NotAllowed
+		}
+		RoomFull => {
+			error!("Client registration failed. Room is full.");
+			ErrorMessageType::InvalidOperation
+		}
+		Database(error) => {
+			error!("Internal error: {error}.");
+			ErrorMessageType::InternalServerError
+		}
+		Overflow(error) => {
+			error!("{error}");
+			ErrorMessageType::InternalServerError
+		}
+	}
+}
+
 pub async fn send_broadcasts(client: Client) {
 	loop {
 		let broadcast = client.wait_for_broadcast().await;
-		if !client.send_broadcast_message(broadcast).await {
+		if !client.send_broadcast_message(&broadcast).await {
 			break;
 		}
 	}
@@ -211,9 +291,40 @@ pub async fn heartbeat(
 	LeftReason::Timeout
 }
 
+/// Broadcasts that a client stopped typing once it didn't report typing for `TYPING_EXPIRY`.
+async fn expire_typing(room: &Room, client: &Client, time_source: &TimeSource) {
+	loop {
+		client.typing_reported().await;
+		while time_source
+			.timeout(TYPING_EXPIRY, client.typing_reported())
+			.await
+			.is_ok()
+		{}
+
+		if let Err(error) = broadcast_typing(room, client, false).await {
+			error!("Failed sending broadcast: {error}");
+		}
+	}
+}
+
+/// Only broadcasts changes of the typing state, so clients can report typing as often as they like.
+async fn broadcast_typing(room: &Room, client: &Client, typing: bool) -> Result<(), RoomError> {
+	if client.update_typing(typing) == typing {
+		return Ok(());
+	}
+
+	room.broadcast(TypingBroadcast {
+		id: client.id(),
+		name: client.name(),
+		typing,
+	})
+	.await
+}
+
 const QUOTA: Quota = Quota::per_second(nonzero!(1u32)).allow_burst(nonzero!(10u32));
 
 async fn handle_messages(
+	application_context: &ApplicationContext,
 	room: &Room,
 	client: Client,
 	mut message_receiver: MessageReceiver,
@@ -232,6 +343,9 @@ async fn handle_messages(
 			ReceivedMessage::Finished => break,
 		};
 
+		// taken before rate limiting so that waiting for the rate limiter doesn't distort time synchronization
+		let receive_time = room.reference_time_milliseconds();
+
 		// rate limit after receiving a message so we don't apply it to receiving pong messages
 		rate_limiter.until_ready().await;
 
@@ -242,29 +356,225 @@ async fn handle_messages(
 			client.id(),
 		);
 
-		match handle_request(room, &client, message.request).await {
+		match handle_request(application_context, room, &client, message.request, receive_time).await {
 			Ok(success_message) => client.send_success_message(success_message, message.request_id).await,
 			Err(error_message) => client.send_error_message(error_message, Some(message.request_id)).await,
 		};
 	}
 }
 
-async fn handle_request(room: &Room, client: &Client, request: ClientRequest) -> Result<SuccessMessage, ErrorMessage> {
+async fn handle_request(
+	application_context: &ApplicationContext,
+	room: &Room,
+	client: &Client,
+	request: ClientRequest,
+	receive_time: UInt,
+) -> Result<SuccessMessage, ErrorMessage> {
 	use ClientRequest::*;
+	let ApplicationContext {
+		configuration,
+		time_source,
+		media_directory,
+		..
+	} = application_context;
 	match request {
-		Chat(chat_request) => handle_chat_request(room, client, chat_request).await,
+		Chat(chat_request) => handle_chat_request(configuration, room, client, chat_request).await,
+		EditChat(edit_chat_request) => handle_edit_chat_request(configuration, room, client, edit_chat_request).await,
+		DeleteChat(delete_chat_request) => handle_delete_chat_request(room, client, delete_chat_request).await,
+		PrivateMessage(private_message_request) => {
+			handle_private_message_request(configuration, room, client, private_message_request).await
+		}
 		Register { .. } => handle_register_request(client),
-		InsertMedium(insert_medium_request) => handle_insert_medium_request(room, client, insert_medium_request).await,
+		ChangeName(change_name_request) => handle_change_name_request(room, client, change_name_request).await,
+		UpdateProfile(update_profile_request) => {
+			handle_update_profile_request(room, client, update_profile_request).await
+		}
+		InsertMedium(insert_medium_request) => {
+			handle_insert_medium_request(configuration, media_directory, room, client, insert_medium_request).await
+		}
 		Play(play_request) => handle_play_request(room, client, play_request).await,
 		Pause(pause_request) => handle_pause_request(room, client, pause_request).await,
+		TimeSync(time_sync_request) => Ok(handle_time_sync_request(room, time_sync_request, receive_time)),
+		PositionReport(position_report_request) => {
+			handle_position_report_request(configuration, room, client, position_report_request, receive_time).await
+		}
+		Readiness(readiness_request) => handle_readiness_request(room, client, readiness_request).await,
+		SchedulePlay(schedule_play_request) => {
+			handle_schedule_play_request(time_source, room, client, schedule_play_request).await
+		}
+		CancelScheduledPlay(_) => handle_cancel_scheduled_play_request(room, client).await,
+		FingerprintReport(fingerprint_report_request) => {
+			handle_fingerprint_report_request(room, client, fingerprint_report_request).await
+		}
+		AddSubtitleTrack(add_subtitle_track_request) => {
+			handle_add_subtitle_track_request(room, client, add_subtitle_track_request).await
+		}
+		SubtitleOffset(subtitle_offset_request) => {
+			handle_subtitle_offset_request(room, client, subtitle_offset_request).await
+		}
+		AddBookmark(add_bookmark_request) => handle_add_bookmark_request(room, client, add_bookmark_request).await,
+		RemoveBookmark(remove_bookmark_request) => {
+			handle_remove_bookmark_request(room, client, remove_bookmark_request).await
+		}
+		ListBookmarks(_) => handle_list_bookmarks_request(room).await,
+		JumpToBookmark(jump_to_bookmark_request) => {
+			handle_jump_to_bookmark_request(room, client, jump_to_bookmark_request).await
+		}
+		Reaction(reaction_request) => handle_reaction_request(room, client, reaction_request).await,
+		ReactionsForMedium(_) => handle_reactions_for_medium_request(room).await,
+		Typing(typing_request) => handle_typing_request(room, client, typing_request).await,
+		CreatePoll(create_poll_request) => {
+			handle_create_poll_request(time_source, room, client, create_poll_request).await
+		}
+		Vote(vote_request) => handle_vote_request(room, client, vote_request).await,
+		ClosePoll(close_poll_request) => handle_close_poll_request(room, client, close_poll_request).await,
 	}
 }
 
 async fn handle_chat_request(
+	configuration: &Configuration,
+	room: &Room,
+	client: &Client,
+	ChatRequest { message, reply_to }: ChatRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	validate_chat_message(configuration, &message)?;
+	let filtered_message =
+		filter_chat_message(&configuration.chat_filters, client, message).map_err(chat_filter_rejection_message)?;
+	let (response, id) = match ChatInput::parse(&filtered_message.message) {
+		ChatInput::Command { name, arguments } => (handle_chat_command(room, client, name, arguments).await?, None),
+		ChatInput::Message(message) => {
+			let reply_to = reply_to.as_deref().map(|id| parse_id(id, "chat message")).transpose()?;
+			let uuid = room
+				.send_chat_message(client, message.to_string(), reply_to)
+				.await
+				.map_err(chat_error_message)?;
+			(SuccessMessage::Success, Some(uuid.to_string()))
+		}
+	};
+
+	flag_chat(room, client, id, filtered_message).await;
+	Ok(response)
+}
+
+fn chat_error_message(error: ChatError) -> ErrorMessage {
+	match error {
+		ChatError::UnknownReplyTarget => ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message(error.to_string())
+			.build(),
+		error => {
+			error!("Failed sending chat message: {error}");
+			ErrorMessage::builder()
+				.error(ErrorMessageType::InternalServerError)
+				.message("Failed sending chat message".to_string())
+				.build()
+		}
+	}
+}
+
+fn chat_filter_rejection_message(rejection: ChatFilterRejection) -> ErrorMessage {
+	ErrorMessage::builder()
+		.error(ErrorMessageType::ChatMessageFiltered)
+		.message(rejection.to_string())
+		.build()
+}
+
+/// Notifies moderators if any chat filter flagged the message.
+async fn flag_chat(
+	room: &Room,
+	client: &Client,
+	id: Option<String>,
+	FilteredChatMessage { message, flagged_by }: FilteredChatMessage,
+) {
+	if flagged_by.is_empty() {
+		return;
+	}
+
+	let notice = ChatFlaggedNotice {
+		id,
+		sender_id: client.id(),
+		sender_name: client.name(),
+		message,
+		flagged_by: flagged_by.into_iter().map(str::to_string).collect(),
+	};
+	room.send_moderator_notice(notice.into()).await;
+}
+
+async fn handle_chat_command(
+	room: &Room,
+	client: &Client,
+	name: &str,
+	arguments: &str,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let context = CommandContext {
+		room,
+		client,
+		commands: &CHAT_COMMANDS,
+	};
+	let output = CHAT_COMMANDS.execute(&context, name, arguments).await;
+	let output = output.map_err(|error| {
+		use CommandError::*;
+		let error_type = match &error {
+			Unknown(_) | Failed(_) => ErrorMessageType::InvalidOperation,
+			Usage(_) => ErrorMessageType::InvalidFormat,
+			NotAllowed(_) => ErrorMessageType::NotAllowed,
+		};
+		ErrorMessage::builder()
+			.error(error_type)
+			.message(error.to_string())
+			.build()
+	})?;
+
+	match output {
+		CommandOutput::Reply(message) => Ok(SuccessMessage::CommandReply { message }),
+		CommandOutput::Broadcast(broadcast) => {
+			if let Err(error) = room.broadcast(broadcast).await {
+				error!("Failed sending broadcast: {error}");
+				return Err(ErrorMessage::builder()
+					.error(ErrorMessageType::InternalServerError)
+					.message("Failed sending broadcast".to_string())
+					.build());
+			}
+			Ok(SuccessMessage::Success)
+		}
+	}
+}
+
+async fn handle_private_message_request(
+	configuration: &Configuration,
 	room: &Room,
 	client: &Client,
-	ChatRequest { message }: ChatRequest,
+	PrivateMessageRequest { recipient_id, message }: PrivateMessageRequest,
 ) -> Result<SuccessMessage, ErrorMessage> {
+	validate_chat_message(configuration, &message)?;
+
+	let result = room
+		.send_private_message(client, recipient_id, message, configuration.persist_private_messages)
+		.await;
+	if let Err(error) = result {
+		use PrivateMessageError::*;
+		let error_type = match &error {
+			UnknownRecipient | RecipientIsSender => ErrorMessageType::InvalidOperation,
+			NotAccepted => ErrorMessageType::NotAllowed,
+			RateLimited => ErrorMessageType::RateLimited,
+			Database(error) => {
+				error!("Failed sending private message: {error}");
+				return Err(ErrorMessage::builder()
+					.error(ErrorMessageType::InternalServerError)
+					.message("Failed sending private message".to_string())
+					.build());
+			}
+		};
+		return Err(ErrorMessage::builder()
+			.error(error_type)
+			.message(error.to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+fn validate_chat_message(configuration: &Configuration, message: &str) -> Result<(), ErrorMessage> {
 	if message.trim().is_empty() {
 		return Err(ErrorMessage::builder()
 			.error(ErrorMessageType::EmptyChatMessage)
@@ -272,17 +582,162 @@ async fn handle_chat_request(
 			.build());
 	}
 
-	if let Err(error) = room.send_chat_message(client, message).await {
-		error!("Failed sending chat message: {error}");
+	if message.len() > configuration.chat_message_length_limit {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::ChatMessageTooLong)
+			.message(format!(
+				"Chat messages must not be longer than {} bytes!",
+				configuration.chat_message_length_limit
+			))
+			.build());
+	}
+
+	Ok(())
+}
+
+async fn handle_edit_chat_request(
+	configuration: &Configuration,
+	room: &Room,
+	client: &Client,
+	EditChatRequest { id, message }: EditChatRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let chat_message_uuid = parse_id(&id, "chat message")?;
+	validate_chat_message(configuration, &message)?;
+	let filtered_message = filter_edited_chat_message(&configuration.chat_filters, client, message)
+		.map_err(chat_filter_rejection_message)?;
+
+	let is_moderator = client.is_moderator();
+	let chat_message = room
+		.edit_chat_message(
+			client,
+			is_moderator,
+			chat_message_uuid,
+			filtered_message.message.clone(),
+		)
+		.await
+		.map_err(|error| chat_modification_error_message(&id, error))?;
+
+	let response = broadcast_chat_modification(
+		room,
+		ChatEditedBroadcast {
+			id: id.clone(),
+			message: chat_message.content.message,
+			edited_by_name: client.name(),
+			edited_by_id: client.id(),
+		},
+	)
+	.await?;
+	flag_chat(room, client, Some(id), filtered_message).await;
+	Ok(response)
+}
+
+async fn handle_delete_chat_request(
+	room: &Room,
+	client: &Client,
+	DeleteChatRequest { id }: DeleteChatRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let chat_message_uuid = parse_id(&id, "chat message")?;
+
+	let is_moderator = client.is_moderator();
+	room.delete_chat_message(client, is_moderator, chat_message_uuid)
+		.await
+		.map_err(|error| chat_modification_error_message(&id, error))?;
+
+	broadcast_chat_modification(
+		room,
+		ChatDeletedBroadcast {
+			id,
+			deleted_by_name: client.name(),
+			deleted_by_id: client.id(),
+		},
+	)
+	.await
+}
+
+fn chat_modification_error_message(id: &str, error: ChatModificationError) -> ErrorMessage {
+	match error {
+		ChatModificationError::NotFound => ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message(format!("There is no chat message with id '{id}'."))
+			.build(),
+		ChatModificationError::NotAllowed => ErrorMessage::builder()
+			.error(ErrorMessageType::NotAllowed)
+			.message(error.to_string())
+			.build(),
+		ChatModificationError::Database(error) => {
+			error!("Failed to change chat message: {error}");
+			ErrorMessage::builder()
+				.error(ErrorMessageType::InternalServerError)
+				.message("Failed to change chat message".to_string())
+				.build()
+		}
+	}
+}
+
+async fn broadcast_chat_modification(
+	room: &Room,
+	broadcast: impl Into<BroadcastMessage> + Clone,
+) -> Result<SuccessMessage, ErrorMessage> {
+	if let Err(error) = room.broadcast(broadcast).await {
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+async fn handle_reaction_request(
+	room: &Room,
+	client: &Client,
+	ReactionRequest { emoji }: ReactionRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	// Enough for emoji that are joined from several code points, like flags or families.
+	const EMOJI_LENGTH_LIMIT: usize = 32;
+	let is_emoji = !emoji.is_empty()
+		&& emoji.len() <= EMOJI_LENGTH_LIMIT
+		&& !emoji
+			.chars()
+			.any(|character| character.is_ascii() || character.is_whitespace());
+	if !is_emoji {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidFormat)
+			.message("Reactions must be a single emoji.".to_string())
+			.build());
+	}
+
+	if let Err(error) = room.send_reaction(client, emoji).await {
+		error!("Failed sending reaction: {error}");
 		return Err(ErrorMessage::builder()
 			.error(ErrorMessageType::InternalServerError)
-			.message("Failed sending chat message".to_string())
+			.message("Failed sending reaction".to_string())
 			.build());
 	}
 
 	Ok(SuccessMessage::Success)
 }
 
+async fn handle_reactions_for_medium_request(room: &Room) -> Result<SuccessMessage, ErrorMessage> {
+	let versioned_medium = room.medium();
+	let chat_messages = room.chat_anchored_to(&versioned_medium.medium).await.map_err(|error| {
+		error!("Failed to get reactions: {error}");
+		ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed to get reactions".to_string())
+			.build()
+	})?;
+
+	Ok(SuccessMessage::ReactionsForMedium {
+		medium_version: versioned_medium.version,
+		reactions: chat_messages
+			.into_iter()
+			.filter_map(MediumReactionResponse::new)
+			.collect(),
+	})
+}
+
 fn handle_register_request(client: &Client) -> Result<SuccessMessage, ErrorMessage> {
 	error!(
 		"Client: {} tried to register even though it is already registered.",
@@ -294,7 +749,82 @@ fn handle_register_request(client: &Client) -> Result<SuccessMessage, ErrorMessa
 		.build())
 }
 
+async fn handle_change_name_request(
+	room: &Room,
+	client: &Client,
+	ChangeNameRequest { name }: ChangeNameRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	if name == client.name() {
+		return Ok(SuccessMessage::Success);
+	}
+
+	let broadcast = room.rename_client(client, &name).await.map_err(|error| {
+		let error_type = match error {
+			RoomError::EmptyClientName | RoomError::ClientNameTooLong => ErrorMessageType::InvalidFormat,
+			RoomError::ClientNameAlreadyInUse => ErrorMessageType::InvalidOperation,
+			RoomError::ClientNameReserved => ErrorMessageType::NotAllowed,
+			_ => {
+				error!("Failed to rename client: {error}");
+				ErrorMessageType::InternalServerError
+			}
+		};
+		ErrorMessage::builder()
+			.error(error_type)
+			.message(error.to_string())
+			.build()
+	})?;
+
+	if let Err(error) = room.broadcast(broadcast).await {
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+async fn handle_update_profile_request(
+	room: &Room,
+	client: &Client,
+	UpdateProfileRequest { profile }: UpdateProfileRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let profile = Profile::from(profile);
+	if profile == client.user().profile {
+		return Ok(SuccessMessage::Success);
+	}
+
+	let broadcast = room.update_profile(client, profile).await.map_err(|error| {
+		let error_type = match error {
+			ProfileError::InvalidColor | ProfileError::PronounsEmpty | ProfileError::PronounsTooLong => {
+				ErrorMessageType::InvalidFormat
+			}
+			ProfileError::Database(_) => {
+				error!("Failed to update profile: {error}");
+				ErrorMessageType::InternalServerError
+			}
+		};
+		ErrorMessage::builder()
+			.error(error_type)
+			.message(error.to_string())
+			.build()
+	})?;
+
+	if let Err(error) = room.broadcast(broadcast).await {
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
 async fn handle_insert_medium_request(
+	configuration: &Configuration,
+	media_directory: &MediaDirectory,
 	room: &Room,
 	client: &Client,
 	InsertMediumRequest {
@@ -302,7 +832,17 @@ async fn handle_insert_medium_request(
 		medium: medium_request,
 	}: InsertMediumRequest,
 ) -> Result<SuccessMessage, ErrorMessage> {
-	let medium = Medium::try_from(medium_request)?;
+	let medium = medium_request.into_medium(media_directory)?;
+	if let Medium::Url(UrlMedium { url, .. }) = &medium {
+		let host = url.host_str().unwrap_or_default();
+		if !configuration.is_allowed_medium_host(host) {
+			return Err(ErrorMessage::builder()
+				.error(ErrorMessageType::MediumHostNotAllowed)
+				.message(format!("Media from '{host}' are not allowed."))
+				.build());
+		}
+	}
+
 	let Some(versioned_medium) = room.insert_medium(medium, previous_version) else {
 		return Err(ErrorMessage {
 			error: ErrorMessageType::IncorrectMediumVersion,
@@ -315,8 +855,8 @@ async fn handle_insert_medium_request(
 
 	if let Err(error) = room
 		.broadcast(MediumStateChangedBroadcast {
-			changed_by_name: client.name().to_string(),
-			changed_by_id: client.id(),
+			changed_by_name: client.name(),
+			changed_by_id: Some(client.id()),
 			medium: VersionedMediumBroadcast::new(versioned_medium, false),
 		})
 		.await
@@ -354,8 +894,8 @@ async fn handle_play_request(
 	};
 	if let Err(error) = room
 		.broadcast(MediumStateChangedBroadcast {
-			changed_by_name: client.name().to_string(),
-			changed_by_id: client.id(),
+			changed_by_name: client.name(),
+			changed_by_id: Some(client.id()),
 			medium: VersionedMediumBroadcast::new(versioned_medium, skipped),
 		})
 		.await
@@ -394,8 +934,8 @@ async fn handle_pause_request(
 
 	if let Err(error) = room
 		.broadcast(MediumStateChangedBroadcast {
-			changed_by_name: client.name().to_string(),
-			changed_by_id: client.id(),
+			changed_by_name: client.name(),
+			changed_by_id: Some(client.id()),
 			medium: VersionedMediumBroadcast::new(versioned_medium, skipped),
 		})
 		.await
@@ -410,131 +950,2540 @@ async fn handle_pause_request(
 	Ok(SuccessMessage::Success)
 }
 
-#[cfg(test)]
-mod test {
-	use super::*;
-	use crate::database::test::DefaultTestFactory;
-	use crate::database::test::TestFactory;
-	use crate::lifecycle::{handle_messages, handle_request, register_client};
-	use crate::message::client_request::{MediumRequest, PauseRequest};
-	use crate::message::outgoing::broadcast_message::{BroadcastMessage, ChatBroadcast, MediumBroadcast};
-	use crate::message::outgoing::error_message::ErrorMessageType;
-	use crate::message::outgoing::success_message::{MediumResponse, PlaybackStateResponse, VersionedMediumResponse};
-	use crate::reference_time::ReferenceTimer;
-	use crate::room::medium::VersionedMedium;
-	use crate::room::medium::fixed_length::FixedLengthMedium;
-	use crate::room::session_id::SessionId;
-	use crate::user::UserService;
-	use crate::utils::fake_message_sender::FakeMessageSender;
-	use crate::utils::test_client::WebsocketTestClient;
-	use chrono::DateTime;
-	use js_int::{int, uint};
+fn handle_time_sync_request(
+	room: &Room,
+	TimeSyncRequest {
+		originate_timestamp_in_milliseconds,
+	}: TimeSyncRequest,
+	receive_time: UInt,
+) -> SuccessMessage {
+	SuccessMessage::TimeSync(TimeSyncResponse {
+		originate_timestamp_in_milliseconds: Some(originate_timestamp_in_milliseconds),
+		receive_timestamp_in_milliseconds: receive_time,
+		transmit_timestamp_in_milliseconds: room.reference_time_milliseconds(),
+	})
+}
 
-	#[tokio::test]
-	async fn the_client_should_get_an_error_for_empty_chat_messages() {
-		let room = room(ReferenceTimer::default(), 1).await;
-		let (client, mut test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+async fn handle_position_report_request(
+	configuration: &Configuration,
+	room: &Room,
+	client: &Client,
+	PositionReportRequest {
+		medium_version,
+		position_in_milliseconds,
+	}: PositionReportRequest,
+	receive_time: UInt,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let versioned_medium = room.medium();
+	if versioned_medium.version != medium_version {
+		return Err(ErrorMessage {
+			error: ErrorMessageType::IncorrectMediumVersion,
+			message: format!(
+				"Medium version is incorrect. Request had {medium_version} but current version is {current_version}.",
+				current_version = versioned_medium.version
+			),
+		});
+	}
 
-		let empty_chat_request = ChatRequest {
-			message: " \t".to_string(),
-		};
-		let non_empty_chat_request = ChatRequest {
-			message: "Hi!".to_string(),
-		};
-		let error = handle_request(&room, &client, empty_chat_request.into())
-			.await
-			.expect_err("Accepted empty chat message.");
-		handle_request(&room, &client, non_empty_chat_request.clone().into())
-			.await
-			.expect("Failed to send proper chat message");
+	// The expected position is derived from the server's reference time, so clients can't pick their own drift.
+	let reference_time = Duration::milliseconds(receive_time.into());
+	let Some(expected_position) = versioned_medium.medium.position_at(reference_time) else {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message("Can't report a position without a medium.".to_string())
+			.build());
+	};
 
-		assert_eq!(
-			error,
-			ErrorMessage::builder()
-				.error(ErrorMessageType::EmptyChatMessage)
-				.message("Chat messages must not be empty!".to_string())
-				.build()
-		);
+	let drift = Duration::milliseconds(position_in_milliseconds.into()) - expected_position;
+	client.update_drift(medium_version, drift);
 
-		// ensure we don't see the empty chat message
-		let received_message = test_client.receive_broadcast_message().await;
-		assert_eq!(
-			received_message,
-			BroadcastMessage::Chat(ChatBroadcast {
+	let threshold = Duration::from_std(configuration.resync_threshold).unwrap_or(Duration::MAX);
+	if drift.abs() <= threshold {
+		return Ok(SuccessMessage::Success);
+	}
+
+	debug!(
+		"Client '{}', {} drifted by {}ms, requesting resync.",
+		client.name(),
+		client.id(),
+		drift.num_milliseconds()
+	);
+	let bookmarks = room.bookmarks(&versioned_medium.medium).await.map_err(|error| {
+		error!("Failed to get bookmarks: {error}");
+		ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed to get bookmarks".to_string())
+			.build()
+	})?;
+	Ok(SuccessMessage::Resync {
+		drift_in_milliseconds: Int::new_saturating(drift.num_milliseconds()),
+		current_medium: VersionedMediumResponse::new(versioned_medium, bookmarks),
+	})
+}
+
+async fn handle_readiness_request(
+	room: &Room,
+	client: &Client,
+	ReadinessRequest { readiness }: ReadinessRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	if client.update_readiness(readiness) == readiness {
+		return Ok(SuccessMessage::Success);
+	}
+
+	if let Err(error) = update_buffering_pause(room).await {
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+async fn handle_schedule_play_request(
+	time_source: &TimeSource,
+	room: &Room,
+	client: &Client,
+	SchedulePlayRequest {
+		previous_version,
+		scheduled_time_in_milliseconds,
+	}: SchedulePlayRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	if scheduled_time_in_milliseconds <= room.reference_time_milliseconds() {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message("Playback can only be scheduled in the future.".to_string())
+			.build());
+	}
+
+	if matches!(room.medium().medium, Medium::Empty) {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message("Can't schedule playback without a medium.".to_string())
+			.build());
+	}
+
+	let Some(scheduled_play) = room.schedule_play(
+		Duration::milliseconds(scheduled_time_in_milliseconds.into()),
+		previous_version,
+	) else {
+		return Err(ErrorMessage {
+			error: ErrorMessageType::IncorrectMediumVersion,
+			message: format!(
+				"Medium version is incorrect. Request had {previous_version} but current version is {current_version}.",
+				current_version = room.medium().version
+			),
+		});
+	};
+
+	if let Err(error) = room
+		.broadcast(PlaybackScheduledBroadcast {
+			changed_by_name: client.name(),
+			changed_by_id: Some(client.id()),
+			medium_version: scheduled_play.medium_version,
+			scheduled_time_in_milliseconds: Some(scheduled_time_in_milliseconds),
+		})
+		.await
+	{
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	tokio::spawn(start_scheduled_play(time_source.clone(), room.clone(), scheduled_play));
+	Ok(SuccessMessage::Success)
+}
+
+async fn handle_cancel_scheduled_play_request(room: &Room, client: &Client) -> Result<SuccessMessage, ErrorMessage> {
+	let Some(scheduled_play) = room.cancel_scheduled_play() else {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message("There is no scheduled playback to cancel.".to_string())
+			.build());
+	};
+
+	if let Err(error) = room
+		.broadcast(PlaybackScheduledBroadcast {
+			changed_by_name: client.name(),
+			changed_by_id: Some(client.id()),
+			medium_version: scheduled_play.medium_version,
+			scheduled_time_in_milliseconds: None,
+		})
+		.await
+	{
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+async fn handle_fingerprint_report_request(
+	room: &Room,
+	client: &Client,
+	FingerprintReportRequest {
+		medium_version,
+		fingerprint,
+	}: FingerprintReportRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let fingerprint = Fingerprint::try_from(fingerprint)?;
+	let versioned_medium = room.medium();
+	if versioned_medium.version != medium_version {
+		return Err(ErrorMessage {
+			error: ErrorMessageType::IncorrectMediumVersion,
+			message: format!(
+				"Medium version is incorrect. Request had {medium_version} but current version is {current_version}.",
+				current_version = versioned_medium.version
+			),
+		});
+	}
+
+	if matches!(versioned_medium.medium, Medium::Empty) {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message("Can't report a fingerprint without a medium.".to_string())
+			.build());
+	}
+
+	let previously_mismatching_clients = room.clients_with_mismatching_fingerprint(&versioned_medium).await;
+	client.update_fingerprint(medium_version, fingerprint);
+	let mismatching_clients = room.clients_with_mismatching_fingerprint(&versioned_medium).await;
+
+	let client_ids = |clients: &[Client]| clients.iter().map(Client::id).collect::<Vec<_>>();
+	if client_ids(&previously_mismatching_clients) == client_ids(&mismatching_clients) {
+		return Ok(SuccessMessage::Success);
+	}
+
+	let mismatching_clients = mismatching_clients
+		.iter()
+		.map(|client| MismatchingClient {
+			id: client.id(),
+			name: client.name(),
+		})
+		.collect();
+	if let Err(error) = room
+		.broadcast(FingerprintMismatchBroadcast {
+			medium_version,
+			mismatching_clients,
+		})
+		.await
+	{
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+async fn handle_add_subtitle_track_request(
+	room: &Room,
+	client: &Client,
+	AddSubtitleTrackRequest {
+		medium_version,
+		track_id,
+		label,
+		language,
+	}: AddSubtitleTrackRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	const LABEL_LENGTH_LIMIT: usize = 256;
+	if label.trim().is_empty() || label.len() > LABEL_LENGTH_LIMIT {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidFormat)
+			.message(format!(
+				"Subtitle label must be between 1 and {LABEL_LENGTH_LIMIT} bytes long."
+			))
+			.build());
+	}
+
+	let Some(upload) = room.subtitle_upload(&track_id) else {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message(format!("There are no uploaded subtitles with id '{track_id}'."))
+			.build());
+	};
+	let track = upload.into_track(label, language);
+	let result = room.add_subtitle_track(track, medium_version);
+	broadcast_subtitle_update(room, client, medium_version, result).await
+}
+
+async fn handle_subtitle_offset_request(
+	room: &Room,
+	client: &Client,
+	SubtitleOffsetRequest {
+		medium_version,
+		offset_in_milliseconds,
+	}: SubtitleOffsetRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let offset = Duration::milliseconds(offset_in_milliseconds.into());
+	let result = room.set_subtitle_offset(offset, medium_version);
+	broadcast_subtitle_update(room, client, medium_version, result).await
+}
+
+async fn broadcast_subtitle_update(
+	room: &Room,
+	client: &Client,
+	medium_version: UInt,
+	result: Result<Subtitles, SubtitleUpdateError>,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let subtitles = result.map_err(|error| match error {
+		SubtitleUpdateError::IncorrectMediumVersion => ErrorMessage {
+			error: ErrorMessageType::IncorrectMediumVersion,
+			message: format!(
+				"Medium version is incorrect. Request had {medium_version} but current version is {current_version}.",
+				current_version = room.medium().version
+			),
+		},
+		SubtitleUpdateError::NoMedium => ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message("Can't change subtitles without a medium.".to_string())
+			.build(),
+		SubtitleUpdateError::TrackLimitReached => ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message(format!(
+				"A medium can't have more than {} subtitle tracks.",
+				Subtitles::TRACK_LIMIT
+			))
+			.build(),
+	})?;
+
+	if let Err(error) = room
+		.broadcast(SubtitlesChangedBroadcast {
+			changed_by_name: client.name(),
+			changed_by_id: client.id(),
+			medium_version,
+			subtitles: subtitles.into(),
+		})
+		.await
+	{
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+async fn handle_add_bookmark_request(
+	room: &Room,
+	client: &Client,
+	AddBookmarkRequest {
+		medium_version,
+		position_in_milliseconds,
+		label,
+	}: AddBookmarkRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	const LABEL_LENGTH_LIMIT: usize = 256;
+	if label.trim().is_empty() || label.len() > LABEL_LENGTH_LIMIT {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidFormat)
+			.message(format!(
+				"Bookmark label must be between 1 and {LABEL_LENGTH_LIMIT} bytes long."
+			))
+			.build());
+	}
+
+	let versioned_medium = room.medium();
+	if versioned_medium.version != medium_version {
+		return Err(ErrorMessage {
+			error: ErrorMessageType::IncorrectMediumVersion,
+			message: format!(
+				"Medium version is incorrect. Request had {medium_version} but current version is {current_version}.",
+				current_version = versioned_medium.version
+			),
+		});
+	}
+
+	let Some(medium_id) = versioned_medium.medium.id() else {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message("Can't add a bookmark without a medium.".to_string())
+			.build());
+	};
+
+	let position = Duration::milliseconds(position_in_milliseconds.into());
+	if let Medium::FixedLength(FixedLengthMedium { length, .. }) = &versioned_medium.medium
+		&& position > *length
+	{
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message("Bookmark position is beyond the end of the medium.".to_string())
+			.build());
+	}
+
+	if let Err(error) = room.add_bookmark(&medium_id, position, &label).await {
+		error!("Failed to add bookmark: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed to add bookmark".to_string())
+			.build());
+	}
+
+	broadcast_bookmarks(room, client, versioned_medium).await
+}
+
+async fn handle_remove_bookmark_request(
+	room: &Room,
+	client: &Client,
+	RemoveBookmarkRequest { id }: RemoveBookmarkRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let bookmark_uuid = parse_id(&id, "bookmark")?;
+	match room.remove_bookmark(bookmark_uuid).await {
+		Ok(true) => {}
+		Ok(false) => {
+			return Err(ErrorMessage::builder()
+				.error(ErrorMessageType::InvalidOperation)
+				.message(format!("There is no bookmark with id '{id}'."))
+				.build());
+		}
+		Err(error) => {
+			error!("Failed to remove bookmark: {error}");
+			return Err(ErrorMessage::builder()
+				.error(ErrorMessageType::InternalServerError)
+				.message("Failed to remove bookmark".to_string())
+				.build());
+		}
+	}
+
+	broadcast_bookmarks(room, client, room.medium()).await
+}
+
+async fn handle_list_bookmarks_request(room: &Room) -> Result<SuccessMessage, ErrorMessage> {
+	let versioned_medium = room.medium();
+	let bookmarks = current_bookmarks(room, &versioned_medium).await?;
+	Ok(SuccessMessage::Bookmarks {
+		medium_version: versioned_medium.version,
+		bookmarks: bookmarks.into_iter().map(Into::into).collect(),
+	})
+}
+
+async fn handle_jump_to_bookmark_request(
+	room: &Room,
+	client: &Client,
+	JumpToBookmarkRequest { previous_version, id }: JumpToBookmarkRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let bookmark_uuid = parse_id(&id, "bookmark")?;
+	let incorrect_medium_version = || ErrorMessage {
+		error: ErrorMessageType::IncorrectMediumVersion,
+		message: format!(
+			"Medium version is incorrect. Request had {previous_version} but current version is {current_version}.",
+			current_version = room.medium().version
+		),
+	};
+
+	let versioned_medium = room.medium();
+	if versioned_medium.version != previous_version {
+		return Err(incorrect_medium_version());
+	}
+
+	let Some(bookmark) = current_bookmarks(room, &versioned_medium)
+		.await?
+		.into_iter()
+		.find(|bookmark| bookmark.uuid == bookmark_uuid)
+	else {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message(format!("The current medium has no bookmark with id '{id}'."))
+			.build());
+	};
+
+	let versioned_medium = room
+		.jump_medium_to(bookmark.position, previous_version)
+		.ok_or_else(incorrect_medium_version)?;
+	if let Err(error) = room
+		.broadcast(MediumStateChangedBroadcast {
+			changed_by_name: client.name(),
+			changed_by_id: Some(client.id()),
+			medium: VersionedMediumBroadcast::new(versioned_medium, true),
+		})
+		.await
+	{
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+/// `kind` names what the id refers to in the error message.
+fn parse_id(id: &str, kind: &str) -> Result<Uuid, ErrorMessage> {
+	id.parse::<uuid::Uuid>().map(Uuid::from).map_err(|_| {
+		ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidFormat)
+			.message(format!("Invalid {kind} id '{id}'."))
+			.build()
+	})
+}
+
+async fn current_bookmarks(room: &Room, versioned_medium: &VersionedMedium) -> Result<Vec<Bookmark>, ErrorMessage> {
+	room.bookmarks(&versioned_medium.medium).await.map_err(|error| {
+		error!("Failed to get bookmarks: {error}");
+		ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed to get bookmarks".to_string())
+			.build()
+	})
+}
+
+async fn broadcast_bookmarks(
+	room: &Room,
+	client: &Client,
+	versioned_medium: VersionedMedium,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let bookmarks = current_bookmarks(room, &versioned_medium).await?;
+	if let Err(error) = room
+		.broadcast(BookmarksChangedBroadcast {
+			changed_by_name: client.name(),
+			changed_by_id: client.id(),
+			medium_version: versioned_medium.version,
+			bookmarks: bookmarks.into_iter().map(Into::into).collect(),
+		})
+		.await
+	{
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+/// Waits until the scheduled time and then starts playing, unless the schedule is obsolete by then.
+async fn start_scheduled_play(time_source: TimeSource, room: Room, scheduled_play: ScheduledPlay) {
+	let reference_now = Duration::milliseconds(room.reference_time_milliseconds().into());
+	let delay = (scheduled_play.start_time - reference_now).to_std().unwrap_or_default();
+	// The pending future never finishes, so this only returns once the delay has passed.
+	let _ = time_source.timeout(delay, std::future::pending::<()>()).await;
+
+	let Some(versioned_medium) = room.start_scheduled_play(scheduled_play) else {
+		return;
+	};
+
+	info!("Started scheduled playback.");
+	if let Err(error) = room
+		.broadcast(MediumStateChangedBroadcast::by_server(VersionedMediumBroadcast::new(
+			versioned_medium,
+			false,
+		)))
+		.await
+	{
+		error!("Failed sending broadcast: {error}");
+	}
+}
+
+/// Pauses or resumes the medium depending on whether anyone is buffering and broadcasts the change.
+async fn update_buffering_pause(room: &Room) -> Result<(), RoomError> {
+	let Some(versioned_medium) = room.update_buffering_pause().await else {
+		return Ok(());
+	};
+
+	info!("Medium was automatically changed because of buffering clients.");
+	room.broadcast(MediumStateChangedBroadcast::by_server(VersionedMediumBroadcast::new(
+		versioned_medium,
+		false,
+	)))
+	.await
+}
+
+async fn handle_typing_request(
+	room: &Room,
+	client: &Client,
+	TypingRequest { typing }: TypingRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	if let Err(error) = broadcast_typing(room, client, typing).await {
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+async fn handle_create_poll_request(
+	time_source: &TimeSource,
+	room: &Room,
+	client: &Client,
+	CreatePollRequest {
+		duration_in_milliseconds,
+		poll,
+	}: CreatePollRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let duration = Duration::milliseconds(duration_in_milliseconds.into());
+	if duration <= Duration::zero() || duration > Duration::days(1) {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidFormat)
+			.message("Poll duration must be between 1 millisecond and 1 day.".to_string())
+			.build());
+	}
+
+	let new_poll = new_poll(room, poll, (*room.reference_date_time() + duration).into())?;
+	let poll = room.create_poll(client, new_poll).await.map_err(|error| {
+		error!("Failed to create poll: {error}");
+		ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed to create poll".to_string())
+			.build()
+	})?;
+
+	let Poll { uuid, deadline, .. } = poll.poll;
+	broadcast_poll(room, poll).await?;
+	tokio::spawn(close_poll_at_deadline(
+		time_source.clone(),
+		room.clone(),
+		uuid,
+		deadline,
+	));
+	Ok(SuccessMessage::Success)
+}
+
+fn new_poll(room: &Room, poll: PollRequest, deadline: DateTime) -> Result<NewPoll, ErrorMessage> {
+	const TEXT_LENGTH_LIMIT: usize = 256;
+	const MAXIMUM_OPTION_COUNT: usize = 10;
+	let is_valid_text = |text: &str| !text.trim().is_empty() && text.len() <= TEXT_LENGTH_LIMIT;
+
+	let (kind, medium_version) = match poll {
+		PollRequest::Custom { question, options } => {
+			if !is_valid_text(&question) || !options.iter().all(|option| is_valid_text(option)) {
+				return Err(ErrorMessage::builder()
+					.error(ErrorMessageType::InvalidFormat)
+					.message(format!(
+						"Poll question and options must be between 1 and {TEXT_LENGTH_LIMIT} bytes long."
+					))
+					.build());
+			}
+			if !(2..=MAXIMUM_OPTION_COUNT).contains(&options.len()) {
+				return Err(ErrorMessage::builder()
+					.error(ErrorMessageType::InvalidFormat)
+					.message(format!("Polls need between 2 and {MAXIMUM_OPTION_COUNT} options."))
+					.build());
+			}
+
+			return Ok(NewPoll {
+				kind: PollKind::Custom,
+				question,
+				options,
+				medium_version: None,
+				deadline,
+			});
+		}
+		PollRequest::Skip { medium_version } => (PollKind::Skip, medium_version),
+		PollRequest::Pause { medium_version } => (PollKind::Pause, medium_version),
+	};
+
+	let versioned_medium = room.medium();
+	if versioned_medium.version != medium_version {
+		return Err(ErrorMessage {
+			error: ErrorMessageType::IncorrectMediumVersion,
+			message: format!(
+				"Medium version is incorrect. Request had {medium_version} but current version is {current_version}.",
+				current_version = versioned_medium.version
+			),
+		});
+	}
+
+	let question = match (kind, versioned_medium.medium.playback()) {
+		(PollKind::Skip, Some(_)) => "Skip the current medium?",
+		(PollKind::Pause, Some(PlaybackState::Playing { .. })) => "Pause the medium?",
+		_ => {
+			return Err(ErrorMessage::builder()
+				.error(ErrorMessageType::InvalidOperation)
+				.message(format!("Can't vote to {} the medium right now.", kind.as_str()))
+				.build());
+		}
+	};
+	Ok(NewPoll {
+		kind,
+		question: question.to_string(),
+		options: vec!["Yes".to_string(), "No".to_string()],
+		medium_version: Some(medium_version),
+		deadline,
+	})
+}
+
+async fn handle_vote_request(
+	room: &Room,
+	client: &Client,
+	VoteRequest { poll_id, option }: VoteRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let poll_uuid = parse_id(&poll_id, "poll")?;
+	let option_index = u32::try_from(u64::from(option)).unwrap_or(u32::MAX);
+	let poll = room
+		.vote(client, poll_uuid, option_index)
+		.await
+		.map_err(|error| poll_error_message(&poll_id, error))?;
+
+	let is_decided = poll.poll.kind != PollKind::Custom && room.has_majority(&poll, YES_OPTION).await;
+	broadcast_poll(room, poll).await?;
+	if is_decided {
+		let closed_poll = room
+			.close_poll_by_server(poll_uuid)
+			.await
+			.map_err(|error| poll_error_message(&poll_id, error))?;
+		if let Some(closed_poll) = closed_poll {
+			broadcast_closed_poll(room, closed_poll).await?;
+		}
+	}
+
+	Ok(SuccessMessage::Success)
+}
+
+async fn handle_close_poll_request(
+	room: &Room,
+	client: &Client,
+	ClosePollRequest { poll_id }: ClosePollRequest,
+) -> Result<SuccessMessage, ErrorMessage> {
+	let poll_uuid = parse_id(&poll_id, "poll")?;
+	let is_moderator = client.is_moderator();
+	let poll = room
+		.close_poll(client, is_moderator, poll_uuid)
+		.await
+		.map_err(|error| poll_error_message(&poll_id, error))?;
+
+	broadcast_closed_poll(room, poll).await?;
+	Ok(SuccessMessage::Success)
+}
+
+fn poll_error_message(id: &str, error: PollError) -> ErrorMessage {
+	match error {
+		PollError::NotFound => ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message(format!("There is no poll with id '{id}'."))
+			.build(),
+		PollError::Closed | PollError::UnknownOption => ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidOperation)
+			.message(error.to_string())
+			.build(),
+		PollError::NotAllowed => ErrorMessage::builder()
+			.error(ErrorMessageType::NotAllowed)
+			.message(error.to_string())
+			.build(),
+		PollError::Database(error) => {
+			error!("Failed to change poll: {error}");
+			ErrorMessage::builder()
+				.error(ErrorMessageType::InternalServerError)
+				.message("Failed to change poll".to_string())
+				.build()
+		}
+	}
+}
+
+/// Deadlines are in reference time, so clients can show a countdown like for scheduled playback.
+fn poll_broadcast(PollResults { poll, options }: PollResults) -> PollBroadcast {
+	let deadline_milliseconds = (*poll.deadline - chrono::DateTime::UNIX_EPOCH).num_milliseconds();
+	let closed = poll.is_closed();
+	PollBroadcast {
+		id: poll.uuid.to_string(),
+		kind: poll.kind.into(),
+		question: poll.question,
+		options: options.into_iter().map(Into::into).collect(),
+		created_by_name: poll.created_by_name,
+		medium_version: poll.medium_version,
+		deadline_in_milliseconds: UInt::try_from(deadline_milliseconds).unwrap_or_default(),
+		closed,
+	}
+}
+
+async fn broadcast_poll(room: &Room, poll: PollResults) -> Result<(), ErrorMessage> {
+	if let Err(error) = room.broadcast(poll_broadcast(poll)).await {
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(())
+}
+
+/// Skip and pause polls change the medium once they are closed with more than half of the room having voted "Yes".
+async fn broadcast_closed_poll(room: &Room, poll: PollResults) -> Result<(), ErrorMessage> {
+	let versioned_medium = if room.has_majority(&poll, YES_OPTION).await {
+		room.apply_medium_poll(&poll.poll)
+	} else {
+		None
+	};
+	broadcast_poll(room, poll).await?;
+
+	let Some(versioned_medium) = versioned_medium else {
+		return Ok(());
+	};
+	info!("Medium was changed by a poll.");
+	if let Err(error) = room
+		.broadcast(MediumStateChangedBroadcast::by_server(VersionedMediumBroadcast::new(
+			versioned_medium,
+			false,
+		)))
+		.await
+	{
+		error!("Failed sending broadcast: {error}");
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InternalServerError)
+			.message("Failed sending broadcast".to_string())
+			.build());
+	}
+
+	Ok(())
+}
+
+/// Closing polls at their deadline doesn't survive restarts, so this is needed for the polls that were still open
+/// when the server stopped. Polls whose deadline has passed in the meantime are closed right away.
+pub async fn close_open_polls_at_their_deadline(time_source: &TimeSource, room: &Room) -> Result<(), DatabaseError> {
+	for poll in room.open_polls().await? {
+		tokio::spawn(close_poll_at_deadline(
+			time_source.clone(),
+			room.clone(),
+			poll.uuid,
+			poll.deadline,
+		));
+	}
+	Ok(())
+}
+
+/// Waits until the deadline and then closes the poll, unless it has been closed already.
+async fn close_poll_at_deadline(time_source: TimeSource, room: Room, poll_uuid: Uuid, deadline: DateTime) {
+	let duration = (*deadline - *room.reference_date_time()).to_std().unwrap_or_default();
+	// The pending future never finishes, so this only returns once the duration has passed.
+	let _ = time_source.timeout(duration, std::future::pending::<()>()).await;
+
+	match room.close_poll_by_server(poll_uuid).await {
+		Ok(Some(poll)) => {
+			info!("Closed poll at its deadline.");
+			// Errors have already been logged.
+			let _ = broadcast_closed_poll(&room, poll).await;
+		}
+		Ok(None) => {}
+		Err(error) => error!("Failed to close poll: {error}"),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::chat::filter::{ChatFilterConfiguration, ChatFilterKind, FilterAction};
+	use crate::database::test::DefaultTestFactory;
+	use crate::database::test::TestFactory;
+	use crate::lifecycle::{handle_messages, handle_request, register_client};
+	use crate::message::client_request::{
+		CancelScheduledPlayRequest, FingerprintRequest, ListBookmarksRequest, MediumRequest, PauseRequest,
+		ProfileRequest, ReactionsForMediumRequest,
+	};
+	use crate::message::outgoing::broadcast_message::{
+		ChatBroadcast, ChatMention, ClientProfileChangedBroadcast, ClientRenamedBroadcast, EmoteBroadcast,
+		MediumBroadcast, ReactionBroadcast,
+	};
+	use crate::message::outgoing::error_message::ErrorMessageType;
+	use crate::message::outgoing::moderator_notice::ModeratorNotice;
+	use crate::message::outgoing::private_message::PrivateMessage;
+	use crate::message::outgoing::success_message::{
+		BookmarkResponse, ChatKindResponse, MediumResponse, PlaybackStateResponse, ProfileResponse,
+		SubtitleTrackResponse, SubtitlesResponse, VersionedMediumResponse,
+	};
+	use crate::reference_time::ReferenceTimer;
+	use crate::room::client::Readiness;
+	use crate::room::medium::VersionedMedium;
+	use crate::room::medium::live::LiveMedium;
+	use crate::room::medium::playback_state::PlaybackState;
+	use crate::room::medium::subtitles::SubtitleUpload;
+	use crate::room::session_id::SessionId;
+	use crate::user::UserService;
+	use crate::user::model::{Avatar, Presence};
+	use crate::utils::fake_message_sender::FakeMessageSender;
+	use crate::utils::test_client::WebsocketTestClient;
+	use chrono::DateTime;
+	use js_int::{int, uint};
+	use std::num::NonZeroUsize;
+
+	#[tokio::test]
+	async fn chat_messages_and_reactions_should_be_anchored_to_the_medium_position() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (bob, _bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		room.insert_medium(LiveMedium::new("Rocket Launch".to_string()), uint!(0))
+			.expect("Failed to insert medium");
+		let paused_medium = room
+			.pause_medium(Duration::seconds(60), uint!(1))
+			.expect("Failed to pause medium");
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			ReactionRequest {
+				emoji: "🚀".to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to send reaction");
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			ReactionBroadcast {
+				sender_id: alice.id(),
+				sender_name: alice.name(),
+				emoji: "🚀".to_string(),
+				medium_position_in_milliseconds: Some(uint!(60_000)),
+			}
+			.into()
+		);
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&bob,
+			ChatRequest {
+				message: "Liftoff!".to_string(),
+				reply_to: None,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to send chat message");
+		let chat_broadcast = alice_test_client.receive_broadcast_message().await;
+		assert_eq!(
+			chat_broadcast,
+			ChatBroadcast {
+				id: chat_id(&chat_broadcast),
+				sender_id: bob.id(),
+				sender_name: bob.name(),
+				message: "Liftoff!".to_string(),
+				counter: uint!(0),
+				medium_position_in_milliseconds: Some(uint!(60_000)),
+				reply_to: None,
+				mentions: Vec::new(),
+				mentions_you: false,
+			}
+			.into()
+		);
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			ReactionsForMediumRequest {}.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to get reactions for medium");
+		assert_eq!(
+			SuccessMessage::ReactionsForMedium {
+				medium_version: paused_medium.version,
+				reactions: vec![
+					MediumReactionResponse {
+						kind: ChatKindResponse::Reaction,
+						sender_name: "Alice".to_string(),
+						message: "🚀".to_string(),
+						position_in_milliseconds: uint!(60_000),
+					},
+					MediumReactionResponse {
+						kind: ChatKindResponse::Message,
+						sender_name: "Bob".to_string(),
+						message: "Liftoff!".to_string(),
+						position_in_milliseconds: uint!(60_000),
+					},
+				],
+			},
+			response
+		);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_only_be_able_to_react_with_emoji() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		for emoji in ["", "lol", "👍 👍", &"👍".repeat(9)] {
+			let error = handle_request(
+				&test_application_context().await,
+				&room,
+				&alice,
+				ReactionRequest {
+					emoji: emoji.to_string(),
+				}
+				.into(),
+				uint!(0),
+			)
+			.await
+			.expect_err("Accepted reaction that isn't an emoji");
+
+			assert_eq!(ErrorMessageType::InvalidFormat, error.error);
+		}
+	}
+
+	#[tokio::test]
+	async fn the_client_should_get_an_error_for_too_long_chat_messages() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (client, _test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let context = application_context(
+			Configuration {
+				chat_message_length_limit: 3,
+				..Configuration::test()
+			},
+			TimeSource::default(),
+		)
+		.await;
+
+		let error = handle_request(
+			&context,
+			&room,
+			&client,
+			ChatRequest {
+				message: "Hi!!".to_string(),
+				reply_to: None,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Accepted too long chat message.");
+		handle_request(
+			&context,
+			&room,
+			&client,
+			ChatRequest {
+				message: "Hi!".to_string(),
+				reply_to: None,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to send chat message with maximum length");
+
+		assert_eq!(
+			error,
+			ErrorMessage::builder()
+				.error(ErrorMessageType::ChatMessageTooLong)
+				.message("Chat messages must not be longer than 3 bytes!".to_string())
+				.build()
+		);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_get_an_error_for_empty_chat_messages() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (client, mut test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		let empty_chat_request = ChatRequest {
+			message: " \t".to_string(),
+			reply_to: None,
+		};
+		let non_empty_chat_request = ChatRequest {
+			message: "Hi!".to_string(),
+			reply_to: None,
+		};
+		let error = handle_request(
+			&test_application_context().await,
+			&room,
+			&client,
+			empty_chat_request.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Accepted empty chat message.");
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&client,
+			non_empty_chat_request.clone().into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to send proper chat message");
+
+		assert_eq!(
+			error,
+			ErrorMessage::builder()
+				.error(ErrorMessageType::EmptyChatMessage)
+				.message("Chat messages must not be empty!".to_string())
+				.build()
+		);
+
+		// ensure we don't see the empty chat message
+		let received_message = test_client.receive_broadcast_message().await;
+		assert_eq!(
+			received_message,
+			BroadcastMessage::Chat(ChatBroadcast {
+				id: chat_id(&received_message),
 				sender_id: client.id(),
-				sender_name: client.name().to_string(),
+				sender_name: client.name(),
 				message: non_empty_chat_request.message,
 				counter: uint!(0),
+				medium_position_in_milliseconds: None,
+				reply_to: None,
+				mentions: Vec::new(),
+				mentions_you: false,
+			})
+		);
+	}
+
+	#[tokio::test]
+	async fn chat_messages_should_only_be_edited_and_deleted_by_their_author_or_a_moderator() {
+		let room = room(ReferenceTimer::default(), 3).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (bob, _bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let (moderator, _moderator_test_client) = WebsocketTestClient::in_room_as_moderator("Moderator", &room).await;
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			ChatRequest {
+				message: "Helo".to_string(),
+				reply_to: None,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to send chat message");
+		let id = chat_id(&alice_test_client.receive_broadcast_message().await);
+
+		let edit_by_bob_error = handle_request(
+			&test_application_context().await,
+			&room,
+			&bob,
+			EditChatRequest {
+				id: id.clone(),
+				message: "Bob was here".to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Bob could edit Alice's chat message");
+		assert_eq!(ErrorMessageType::NotAllowed, edit_by_bob_error.error);
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			EditChatRequest {
+				id: id.clone(),
+				message: "Hello".to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to edit chat message");
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			ChatEditedBroadcast {
+				id: id.clone(),
+				message: "Hello".to_string(),
+				edited_by_name: alice.name(),
+				edited_by_id: alice.id(),
+			}
+			.into()
+		);
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&moderator,
+			DeleteChatRequest { id: id.clone() }.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Moderator failed to delete chat message");
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			ChatDeletedBroadcast {
+				id: id.clone(),
+				deleted_by_name: moderator.name(),
+				deleted_by_id: moderator.id(),
+			}
+			.into()
+		);
+
+		let delete_again_error = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			DeleteChatRequest { id }.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Deleted chat message twice");
+		assert_eq!(ErrorMessageType::InvalidOperation, delete_again_error.error);
+	}
+
+	#[tokio::test]
+	async fn chat_replies_should_flag_mentions_for_the_mentioned_clients() {
+		let room = room(ReferenceTimer::default(), 3).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (bob, _bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let (_carol, mut carol_test_client) = WebsocketTestClient::in_room("Carol", &room).await;
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			ChatRequest {
+				message: "I'm back!".to_string(),
+				reply_to: None,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to send chat message");
+		let id = chat_id(&alice_test_client.receive_broadcast_message().await);
+		let _ = carol_test_client.receive_broadcast_message().await;
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&bob,
+			ChatRequest {
+				message: "Welcome back @Alice!".to_string(),
+				reply_to: Some(id.clone()),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to send reply");
+		let alice_broadcast = alice_test_client.receive_broadcast_message().await;
+		let expected_broadcast = ChatBroadcast {
+			id: chat_id(&alice_broadcast),
+			sender_id: bob.id(),
+			sender_name: bob.name(),
+			message: "Welcome back @Alice!".to_string(),
+			counter: uint!(1),
+			medium_position_in_milliseconds: None,
+			reply_to: Some(id),
+			mentions: vec![ChatMention {
+				id: alice.id(),
+				name: alice.name(),
+			}],
+			mentions_you: false,
+		};
+		assert_eq!(
+			BroadcastMessage::from(ChatBroadcast {
+				mentions_you: true,
+				..expected_broadcast.clone()
+			}),
+			alice_broadcast
+		);
+		assert_eq!(
+			BroadcastMessage::from(expected_broadcast),
+			carol_test_client.receive_broadcast_message().await
+		);
+
+		let unknown_reply_error = handle_request(
+			&test_application_context().await,
+			&room,
+			&bob,
+			ChatRequest {
+				message: "Reply to nothing".to_string(),
+				reply_to: Some(Uuid::new_v4().to_string()),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Replied to unknown chat message");
+		assert_eq!(ErrorMessageType::InvalidOperation, unknown_reply_error.error);
+	}
+
+	#[tokio::test]
+	async fn chat_commands_should_broadcast_emotes() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (_bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			chat_command("/me waves"),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to use /me");
+		assert_eq!(SuccessMessage::Success, response);
+
+		let expected_broadcast: BroadcastMessage = EmoteBroadcast {
+			sender_id: alice.id(),
+			sender_name: alice.name(),
+			action: "waves".to_string(),
+		}
+		.into();
+		assert_eq!(expected_broadcast, alice_test_client.receive_broadcast_message().await);
+		assert_eq!(expected_broadcast, bob_test_client.receive_broadcast_message().await);
+	}
+
+	#[tokio::test]
+	async fn escaped_chat_commands_should_be_sent_as_chat_messages() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			chat_command("//me is not a command"),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to send chat message");
+
+		let BroadcastMessage::Chat(ChatBroadcast { message, .. }) = alice_test_client.receive_broadcast_message().await
+		else {
+			panic!("Expected chat message");
+		};
+		assert_eq!("/me is not a command", message);
+	}
+
+	#[tokio::test]
+	async fn help_command_should_only_be_replied_to_the_client_and_list_permitted_commands() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (moderator, _moderator_test_client) = WebsocketTestClient::in_room_as_moderator("Moderator", &room).await;
+
+		let help = |client: Client| {
+			let room = room.clone();
+			async move {
+				let response = handle_request(
+					&test_application_context().await,
+					&room,
+					&client,
+					chat_command("/help"),
+					uint!(0),
+				)
+				.await
+				.expect("Failed to use /help");
+				let SuccessMessage::CommandReply { message } = response else {
+					panic!("Expected command reply, got {response:?}");
+				};
+				message
+			}
+		};
+
+		let alice_help = help(alice).await;
+		assert!(alice_help.contains("/roll"));
+		assert!(!alice_help.contains("/kick"));
+		assert!(help(moderator).await.contains("/kick"));
+	}
+
+	#[tokio::test]
+	async fn seek_command_should_jump_to_the_position() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			chat_command("/seek 12:30"),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to use /seek");
+
+		let BroadcastMessage::MediumStateChanged(MediumStateChangedBroadcast {
+			medium, changed_by_id, ..
+		}) = alice_test_client.receive_broadcast_message().await
+		else {
+			panic!("Expected medium state change");
+		};
+		assert_eq!(Some(alice.id()), changed_by_id);
+		assert_eq!(uint!(2), medium.version);
+		assert_eq!(
+			Some(PlaybackState::Paused {
+				at_position: Duration::minutes(12) + Duration::seconds(30)
+			}),
+			room.medium().medium.playback()
+		);
+
+		let usage_error = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			chat_command("/seek soon"),
+			uint!(0),
+		)
+		.await
+		.expect_err("Seeking without position should fail");
+		assert_eq!(ErrorMessageType::InvalidFormat, usage_error.error);
+	}
+
+	#[tokio::test]
+	async fn seek_command_should_only_jump_to_positions_in_the_medium() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let seek = async |position: &str| {
+			handle_request(
+				&test_application_context().await,
+				&room,
+				&alice,
+				chat_command(&format!("/seek {position}")),
+				uint!(0),
+			)
+			.await
+			.expect_err("Seeking should fail")
+			.message
+		};
+
+		assert_eq!("There is no medium to seek in.", seek("1:00").await);
+
+		let inserted_medium = room
+			.insert_medium(
+				FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153)),
+				uint!(0),
+			)
+			.expect("Failed to insert medium");
+		assert_eq!("The position is beyond the end of the medium.", seek("2:33:01").await);
+
+		room.insert_medium(LiveMedium::new("Rocket Launch".to_string()), inserted_medium.version)
+			.expect("Failed to insert medium");
+		assert_eq!("Live media can't be seeked.", seek("1:00").await);
+	}
+
+	#[tokio::test]
+	async fn seek_command_should_only_jump_to_positions_in_url_media_with_a_known_length() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let url = url::Url::parse("https://videos.example.com/metropolis.webm").expect("Invalid URL");
+
+		let inserted_medium = room
+			.insert_medium(
+				UrlMedium::new(url.clone(), None, Some(Duration::minutes(153))),
+				uint!(0),
+			)
+			.expect("Failed to insert medium");
+		let error = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			chat_command("/seek 2:33:01"),
+			uint!(0),
+		)
+		.await
+		.expect_err("Seeking beyond the end should fail");
+		assert_eq!("The position is beyond the end of the medium.", error.message);
+
+		room.insert_medium(UrlMedium::new(url, None, None), inserted_medium.version)
+			.expect("Failed to insert medium");
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			chat_command("/seek 2:33:01"),
+			uint!(0),
+		)
+		.await
+		.expect("Seeking in a URL medium of unknown length should succeed");
+	}
+
+	#[tokio::test]
+	async fn only_moderators_should_be_able_to_kick_clients() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (moderator, _moderator_test_client) = WebsocketTestClient::in_room_as_moderator("Moderator", &room).await;
+		let (mallory, _mallory_test_client) = WebsocketTestClient::in_room("Mallory", &room).await;
+
+		let not_allowed_error = handle_request(
+			&test_application_context().await,
+			&room,
+			&mallory,
+			chat_command("/kick Moderator"),
+			uint!(0),
+		)
+		.await
+		.expect_err("Mallory must not kick anyone");
+		assert_eq!(ErrorMessageType::NotAllowed, not_allowed_error.error);
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&moderator,
+			chat_command("/kick Mallory"),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to kick Mallory");
+		assert_eq!(
+			SuccessMessage::CommandReply {
+				message: "Kicked Mallory.".to_string()
+			},
+			response
+		);
+		tokio::time::timeout(std::time::Duration::from_secs(1), mallory.kicked())
+			.await
+			.expect("Mallory wasn't kicked");
+	}
+
+	#[tokio::test]
+	async fn clients_should_be_able_to_change_their_name() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			ChangeNameRequest {
+				name: "Alicia".to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to change name");
+
+		assert_eq!(
+			bob_test_client.receive_broadcast_message().await,
+			ClientRenamedBroadcast {
+				id: alice.id(),
+				old_name: "Alice".to_string(),
+				new_name: "Alicia".to_string(),
+			}
+			.into()
+		);
+		assert_eq!("Alicia", alice.name());
+		let renamed_client = room.client_by_name("Alicia").await.expect("Renamed client not found");
+		assert_eq!(alice.id(), renamed_client.id());
+		assert!(room.client_by_name("Alice").await.is_none());
+
+		for (name, expected_error) in [
+			("Alicia", ErrorMessageType::InvalidOperation),
+			(" ", ErrorMessageType::InvalidFormat),
+		] {
+			let error = handle_request(
+				&test_application_context().await,
+				&room,
+				&bob,
+				ChangeNameRequest { name: name.to_string() }.into(),
+				uint!(0),
+			)
+			.await
+			.expect_err("Bob could change to an invalid name");
+			assert_eq!(expected_error, error.error);
+		}
+		assert_eq!("Bob", bob.name());
+	}
+
+	#[tokio::test]
+	async fn clients_should_be_able_to_update_their_profile() {
+		let room = room(ReferenceTimer::default(), 3).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (_bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let profile_request = ProfileRequest {
+			presence: Presence::Away,
+			color: Some("#c0ffee".to_string()),
+			avatar: Some(Avatar::Cat),
+			pronouns: Some("she/her".to_string()),
+		};
+		let profile = Profile::from(profile_request.clone());
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			UpdateProfileRequest {
+				profile: profile_request.clone(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to update profile");
+
+		assert_eq!(
+			bob_test_client.receive_broadcast_message().await,
+			ClientProfileChangedBroadcast {
+				id: alice.id(),
+				name: "Alice".to_string(),
+				profile: profile.clone().into(),
+			}
+			.into()
+		);
+		assert_eq!(profile, alice.user().profile);
+
+		let error = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			UpdateProfileRequest {
+				profile: ProfileRequest {
+					color: Some("coffee".to_string()),
+					..profile_request
+				},
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Alice could update to an invalid color");
+		assert_eq!(ErrorMessageType::InvalidFormat, error.error);
+
+		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
+		let request_id = test_client
+			.send_request(RegisterRequest {
+				name: "Charlie".to_string(),
+				accepts_private_messages: true,
+				moderator_token: None,
 			})
+			.await;
+		register_client(&Configuration::test(), room, message_sender, message_receiver).await;
+		let response = test_client.receive_success_message(request_id).await;
+		let SuccessMessage::Hello { clients, .. } = response else {
+			panic!("Expected Hello response, got {response:?}");
+		};
+		let alice_response = clients
+			.iter()
+			.find(|client| client.id == alice.id())
+			.expect("Alice is missing in the Hello response");
+		assert_eq!(ProfileResponse::from(profile), alice_response.profile);
+	}
+
+	#[tokio::test]
+	async fn nick_command_should_change_the_name() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			chat_command("/nick Alicia"),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to change name with /nick");
+
+		assert_eq!(SuccessMessage::Success, response);
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			ClientRenamedBroadcast {
+				id: alice.id(),
+				old_name: "Alice".to_string(),
+				new_name: "Alicia".to_string(),
+			}
+			.into()
+		);
+	}
+
+	#[tokio::test]
+	async fn nick_command_should_not_take_the_name_of_a_moderator() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		let error = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			chat_command("/nick Moderator"),
+			uint!(0),
+		)
+		.await
+		.expect_err("Alice could take the name of a moderator with /nick");
+
+		assert_eq!(
+			ErrorMessage::builder()
+				.error(ErrorMessageType::InvalidOperation)
+				.message("Client name is reserved for a moderator.".to_string())
+				.build(),
+			error
+		);
+		assert_eq!("Alice", alice.name());
+	}
+
+	#[tokio::test]
+	async fn unknown_chat_commands_should_be_rejected() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		let error = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			chat_command("/dance"),
+			uint!(0),
+		)
+		.await
+		.expect_err("Unknown commands must fail");
+		assert_eq!(
+			ErrorMessage::builder()
+				.error(ErrorMessageType::InvalidOperation)
+				.message("Unknown command '/dance', see /help for a list of commands.".to_string())
+				.build(),
+			error
+		);
+	}
+
+	#[tokio::test]
+	async fn chat_filters_should_reject_messages() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let context = application_context(
+			Configuration {
+				chat_filters: vec![ChatFilterConfiguration {
+					filter: ChatFilterKind::Links,
+					action: FilterAction::Reject,
+				}],
+				..Configuration::test()
+			},
+			TimeSource::default(),
+		)
+		.await;
+
+		let error = handle_request(
+			&context,
+			&room,
+			&alice,
+			chat_command("Visit https://example.com"),
+			uint!(0),
+		)
+		.await
+		.expect_err("Links must be rejected");
+
+		assert_eq!(
+			ErrorMessage::builder()
+				.error(ErrorMessageType::ChatMessageFiltered)
+				.message("Chat message was rejected by the link filter.".to_string())
+				.build(),
+			error
+		);
+	}
+
+	#[tokio::test]
+	async fn flagged_chat_messages_should_be_delivered_and_reported_to_moderators() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (_moderator, mut moderator_test_client) =
+			WebsocketTestClient::in_room_as_moderator("Moderator", &room).await;
+		let context = application_context(
+			Configuration {
+				chat_filters: vec![ChatFilterConfiguration {
+					filter: ChatFilterKind::LineLimit {
+						maximum_lines: NonZeroUsize::new(1).unwrap(),
+					},
+					action: FilterAction::Flag,
+				}],
+				..Configuration::test()
+			},
+			TimeSource::default(),
+		)
+		.await;
+
+		handle_request(&context, &room, &alice, chat_command("Line\nby\nline"), uint!(0))
+			.await
+			.expect("Flagged messages must be delivered");
+
+		let id = chat_id(&alice_test_client.receive_broadcast_message().await);
+		assert_eq!(
+			ModeratorNotice::from(ChatFlaggedNotice {
+				id: Some(id),
+				sender_id: alice.id(),
+				sender_name: alice.name(),
+				message: "Line\nby\nline".to_string(),
+				flagged_by: vec!["line limit".to_string()],
+			}),
+			moderator_test_client.receive_moderator_notice().await
+		);
+	}
+
+	fn chat_command(message: &str) -> ClientRequest {
+		ChatRequest {
+			message: message.to_string(),
+			reply_to: None,
+		}
+		.into()
+	}
+
+	#[tokio::test]
+	async fn private_messages_should_only_be_delivered_to_sender_and_recipient() {
+		let room = room(ReferenceTimer::default(), 3).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let (carol, _carol_test_client) = WebsocketTestClient::in_room("Carol", &room).await;
+		carol.set_accepts_private_messages(false);
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			PrivateMessageRequest {
+				recipient_id: bob.id(),
+				message: "Psst!".to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to send private message");
+		let expected_private_message = PrivateMessage {
+			sender_id: alice.id(),
+			sender_name: alice.name(),
+			recipient_id: bob.id(),
+			recipient_name: bob.name(),
+			message: "Psst!".to_string(),
+		};
+		assert_eq!(
+			expected_private_message,
+			bob_test_client.receive_private_message().await
+		);
+		assert_eq!(
+			expected_private_message,
+			alice_test_client.receive_private_message().await
+		);
+
+		let not_accepted_error = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			PrivateMessageRequest {
+				recipient_id: carol.id(),
+				message: "Psst!".to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Sent private message to client that doesn't accept them");
+		assert_eq!(ErrorMessageType::NotAllowed, not_accepted_error.error);
+
+		let to_self_error = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			PrivateMessageRequest {
+				recipient_id: alice.id(),
+				message: "Note to self".to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Sent private message to self");
+		assert_eq!(ErrorMessageType::InvalidOperation, to_self_error.error);
+	}
+
+	#[tokio::test]
+	async fn private_messages_should_be_rate_limited() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (bob, _bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let context = application_context(Configuration::test(), TimeSource::default()).await;
+
+		let mut results = Vec::new();
+		for number in 0..=context.configuration.private_messages_per_minute.get() {
+			let result = handle_request(
+				&context,
+				&room,
+				&alice,
+				PrivateMessageRequest {
+					recipient_id: bob.id(),
+					message: format!("Message {number}"),
+				}
+				.into(),
+				uint!(0),
+			)
+			.await;
+			results.push(result.map_err(|error| error.error));
+		}
+
+		let (last_result, accepted_results) = results.split_last().expect("No results");
+		assert!(accepted_results.iter().all(Result::is_ok));
+		assert_eq!(&Err(ErrorMessageType::RateLimited), last_result);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_be_able_to_insert_a_medium() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (_bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			InsertMediumRequest {
+				medium: Medium::from(medium.clone()).into(),
+				previous_version: uint!(0),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to get successful response");
+		assert_eq!(response, SuccessMessage::Success);
+
+		let alice_broadcast = alice_test_client.receive_broadcast_message().await;
+		let bob_broadcast = bob_test_client.receive_broadcast_message().await;
+
+		let expected_broadcast = MediumStateChangedBroadcast {
+			changed_by_name: alice.name(),
+			changed_by_id: Some(alice.id()),
+			medium: VersionedMediumBroadcast::new(
+				VersionedMedium {
+					medium: medium.into(),
+					version: uint!(1),
+				},
+				false,
+			),
+		};
+
+		assert_eq!(alice_broadcast, expected_broadcast.clone().into());
+		assert_eq!(bob_broadcast, expected_broadcast.into());
+	}
+
+	#[tokio::test]
+	async fn the_client_should_not_be_able_to_insert_a_too_large_medium() {
+		let (alice_message_sender, _message_receiver, _alice_test_client) = WebsocketTestClient::new();
+
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, _) = room
+			.add_client_and_return_existing("Alice", false, alice_message_sender)
+			.await
+			.expect("Did not get client handle!");
+
+		let request = InsertMediumRequest {
+			medium: MediumRequest::FixedLength {
+				name: "Metropolis".to_string(),
+				length_in_milliseconds: UInt::try_from(Duration::days(400).num_milliseconds()).unwrap(),
+				fingerprint: None,
+			},
+			previous_version: uint!(0),
+		};
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			request.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Failed to ger error response");
+
+		assert_eq!(
+			response,
+			ErrorMessage::builder()
+				.error(ErrorMessageType::InvalidFormat)
+				.message("Length of a medium must not be larger than one year.".to_string())
+				.build()
+		);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_be_able_to_insert_a_url_medium() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let request = InsertMediumRequest {
+			previous_version: uint!(0),
+			medium: MediumRequest::Url {
+				url: "https://videos.example.com/metropolis.webm".to_string(),
+				mime_type: Some("video/webm".to_string()),
+				length_in_milliseconds: None,
+			},
+		};
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			request.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to insert URL medium");
+
+		assert_eq!(SuccessMessage::Success, response);
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			MediumStateChangedBroadcast {
+				changed_by_name: alice.name(),
+				changed_by_id: Some(alice.id()),
+				medium: VersionedMediumBroadcast {
+					medium: MediumBroadcast::Url {
+						url: "https://videos.example.com/metropolis.webm".to_string(),
+						mime_type: Some("video/webm".to_string()),
+						length_in_milliseconds: None,
+						subtitles: SubtitlesResponse::default(),
+						playback_skipped: false,
+						playback_state: PlaybackStateResponse::Paused {
+							position_in_milliseconds: uint!(0),
+						},
+					},
+					version: uint!(1),
+				},
+			}
+			.into()
+		);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_not_be_able_to_insert_a_url_medium_from_other_hosts() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let request = InsertMediumRequest {
+			previous_version: uint!(0),
+			medium: MediumRequest::Url {
+				url: "https://evil.org/metropolis.webm".to_string(),
+				mime_type: None,
+				length_in_milliseconds: None,
+			},
+		};
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			request.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Inserted URL medium from host that isn't allowed");
+
+		assert_eq!(
+			ErrorMessage::builder()
+				.error(ErrorMessageType::MediumHostNotAllowed)
+				.message("Media from 'evil.org' are not allowed.".to_string())
+				.build(),
+			response
+		);
+		assert_eq!(uint!(0), room.medium().version);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_be_able_to_insert_a_hosted_medium() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let context = test_application_context().await;
+		let hosted_medium = context
+			.media_directory
+			.media()
+			.find(|medium| medium.name == "silence.wav")
+			.expect("Failed to find hosted medium");
+		let request = InsertMediumRequest {
+			previous_version: uint!(0),
+			medium: MediumRequest::Hosted {
+				id: hosted_medium.id.clone(),
+			},
+		};
+
+		let response = handle_request(&context, &room, &alice, request.into(), uint!(0))
+			.await
+			.expect("Failed to insert hosted medium");
+
+		assert_eq!(SuccessMessage::Success, response);
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			MediumStateChangedBroadcast {
+				changed_by_name: alice.name(),
+				changed_by_id: Some(alice.id()),
+				medium: VersionedMediumBroadcast {
+					medium: MediumBroadcast::FixedLength {
+						name: "silence.wav".to_string(),
+						length_in_milliseconds: uint!(250),
+						fingerprint: None,
+						hosted_id: Some(hosted_medium.id.clone()),
+						subtitles: SubtitlesResponse::default(),
+						playback_skipped: false,
+						playback_state: PlaybackStateResponse::Paused {
+							position_in_milliseconds: uint!(0),
+						},
+					},
+					version: uint!(1),
+				},
+			}
+			.into()
+		);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_not_be_able_to_insert_an_unknown_hosted_medium() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let request = InsertMediumRequest {
+			previous_version: uint!(0),
+			medium: MediumRequest::Hosted {
+				id: "unknown".to_string(),
+			},
+		};
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			request.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Inserted unknown hosted medium");
+
+		assert_eq!(ErrorMessageType::InvalidOperation, response.error);
+		assert_eq!(uint!(0), room.medium().version);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_be_able_to_add_subtitles_to_the_medium() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let inserted_medium = room
+			.insert_medium(LiveMedium::new("Rocket Launch".to_string()), uint!(0))
+			.expect("Failed to insert medium");
+		let upload =
+			SubtitleUpload::parse("1\n00:00:03,000 --> 00:00:04,000\nLiftoff!\n").expect("Failed to parse subtitles");
+		room.upload_subtitles(upload.clone());
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			AddSubtitleTrackRequest {
+				medium_version: inserted_medium.version,
+				track_id: upload.id.clone(),
+				label: "English".to_string(),
+				language: Some("en".to_string()),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to add subtitles");
+		assert_eq!(SuccessMessage::Success, response);
+
+		let track = room
+			.medium()
+			.medium
+			.subtitles()
+			.expect("Medium has no subtitles")
+			.tracks[0]
+			.clone();
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			SubtitlesChangedBroadcast {
+				changed_by_name: alice.name(),
+				changed_by_id: alice.id(),
+				medium_version: inserted_medium.version,
+				subtitles: SubtitlesResponse {
+					tracks: vec![SubtitleTrackResponse {
+						id: track.id.clone(),
+						label: "English".to_string(),
+						language: Some("en".to_string()),
+					}],
+					offset_in_milliseconds: int!(0),
+				},
+			}
+			.into()
 		);
+		assert_eq!(Some(track.clone()), room.subtitle_track(&track.id));
 	}
 
 	#[tokio::test]
-	async fn the_client_should_be_able_to_insert_a_medium() {
+	async fn the_client_should_not_be_able_to_add_subtitles_that_werent_uploaded() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let inserted_medium = room
+			.insert_medium(LiveMedium::new("Rocket Launch".to_string()), uint!(0))
+			.expect("Failed to insert medium");
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			AddSubtitleTrackRequest {
+				medium_version: inserted_medium.version,
+				track_id: "unknown".to_string(),
+				label: "English".to_string(),
+				language: None,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Added subtitles that weren't uploaded");
+
+		assert_eq!(
+			ErrorMessage::builder()
+				.error(ErrorMessageType::InvalidOperation)
+				.message("There are no uploaded subtitles with id 'unknown'.".to_string())
+				.build(),
+			response
+		);
+		assert_eq!(Some(&Subtitles::default()), room.medium().medium.subtitles());
+	}
+
+	#[tokio::test]
+	async fn the_client_should_not_be_able_to_add_subtitles_without_a_medium() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let upload =
+			SubtitleUpload::parse("00:00:03,000 --> 00:00:04,000\nLiftoff!").expect("Failed to parse subtitles");
+		room.upload_subtitles(upload.clone());
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			AddSubtitleTrackRequest {
+				medium_version: uint!(0),
+				track_id: upload.id,
+				label: "English".to_string(),
+				language: None,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Added subtitles without a medium");
+
+		assert_eq!(
+			ErrorMessage::builder()
+				.error(ErrorMessageType::InvalidOperation)
+				.message("Can't change subtitles without a medium.".to_string())
+				.build(),
+			response
+		);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_be_able_to_shift_the_subtitles() {
 		let room = room(ReferenceTimer::default(), 2).await;
 		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
 		let (_bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let inserted_medium = room
+			.insert_medium(LiveMedium::new("Rocket Launch".to_string()), uint!(0))
+			.expect("Failed to insert medium");
 
-		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
 		let response = handle_request(
+			&test_application_context().await,
 			&room,
 			&alice,
-			InsertMediumRequest {
-				medium: Medium::from(medium.clone()).into(),
-				previous_version: uint!(0),
+			SubtitleOffsetRequest {
+				medium_version: inserted_medium.version,
+				offset_in_milliseconds: int!(-1500),
 			}
 			.into(),
+			uint!(0),
 		)
 		.await
-		.expect("Failed to get successful response");
-		assert_eq!(response, SuccessMessage::Success);
-
-		let alice_broadcast = alice_test_client.receive_broadcast_message().await;
-		let bob_broadcast = bob_test_client.receive_broadcast_message().await;
+		.expect("Failed to shift subtitles");
+		assert_eq!(SuccessMessage::Success, response);
 
-		let expected_broadcast = MediumStateChangedBroadcast {
-			changed_by_name: alice.name().to_string(),
+		let expected_broadcast: BroadcastMessage = SubtitlesChangedBroadcast {
+			changed_by_name: alice.name(),
 			changed_by_id: alice.id(),
-			medium: VersionedMediumBroadcast::new(
-				VersionedMedium {
-					medium: medium.into(),
-					version: uint!(1),
-				},
-				false,
-			),
-		};
+			medium_version: inserted_medium.version,
+			subtitles: SubtitlesResponse {
+				tracks: vec![],
+				offset_in_milliseconds: int!(-1500),
+			},
+		}
+		.into();
+		assert_eq!(expected_broadcast, alice_test_client.receive_broadcast_message().await);
+		assert_eq!(expected_broadcast, bob_test_client.receive_broadcast_message().await);
+	}
 
-		assert_eq!(alice_broadcast, expected_broadcast.clone().into());
-		assert_eq!(bob_broadcast, expected_broadcast.into());
+	#[tokio::test]
+	async fn changing_the_subtitles_should_not_invalidate_playback_requests() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let inserted_medium = room
+			.insert_medium(LiveMedium::new("Rocket Launch".to_string()), uint!(0))
+			.expect("Failed to insert medium");
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			SubtitleOffsetRequest {
+				medium_version: inserted_medium.version,
+				offset_in_milliseconds: int!(-1500),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to shift subtitles");
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			PauseRequest {
+				previous_version: inserted_medium.version,
+				skipped: false,
+				position_in_milliseconds: uint!(1000),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Shifting the subtitles invalidated the pause request");
+
+		assert_eq!(SuccessMessage::Success, response);
+		assert_eq!(inserted_medium.version + uint!(1), room.medium().version);
 	}
 
 	#[tokio::test]
-	async fn the_client_should_not_be_able_to_insert_a_too_large_medium() {
-		let (alice_message_sender, _message_receiver, _alice_test_client) = WebsocketTestClient::new();
+	async fn the_client_should_be_able_to_bookmark_the_medium_and_jump_to_the_bookmark() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (_bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		let inserted_medium = room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			AddBookmarkRequest {
+				medium_version: inserted_medium.version,
+				position_in_milliseconds: uint!(60_000),
+				label: "Tower of Babel".to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to add bookmark");
+		assert_eq!(SuccessMessage::Success, response);
+
+		let bookmark = room
+			.bookmarks(&inserted_medium.medium)
+			.await
+			.expect("Failed to get bookmarks")
+			.pop()
+			.expect("Bookmark wasn't persisted");
+		let expected_broadcast: BroadcastMessage = BookmarksChangedBroadcast {
+			changed_by_name: alice.name(),
+			changed_by_id: alice.id(),
+			medium_version: inserted_medium.version,
+			bookmarks: vec![BookmarkResponse {
+				id: bookmark.uuid.to_string(),
+				label: "Tower of Babel".to_string(),
+				position_in_milliseconds: 60_000,
+			}],
+		}
+		.into();
+		assert_eq!(expected_broadcast, alice_test_client.receive_broadcast_message().await);
+		assert_eq!(expected_broadcast, bob_test_client.receive_broadcast_message().await);
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			JumpToBookmarkRequest {
+				previous_version: inserted_medium.version,
+				id: bookmark.uuid.to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to jump to bookmark");
+		assert_eq!(SuccessMessage::Success, response);
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			MediumStateChangedBroadcast {
+				changed_by_name: alice.name(),
+				changed_by_id: Some(alice.id()),
+				medium: VersionedMediumBroadcast {
+					medium: MediumBroadcast::FixedLength {
+						name: "Metropolis".to_string(),
+						length_in_milliseconds: uint!(9_180_000),
+						fingerprint: None,
+						hosted_id: None,
+						subtitles: SubtitlesResponse::default(),
+						playback_skipped: true,
+						playback_state: PlaybackStateResponse::Paused {
+							position_in_milliseconds: uint!(60_000),
+						},
+					},
+					version: uint!(2),
+				},
+			}
+			.into()
+		);
+	}
 
+	#[tokio::test]
+	async fn bookmarks_should_be_kept_when_inserting_the_same_medium_again() {
 		let room = room(ReferenceTimer::default(), 2).await;
-		let (alice, _) = room
-			.add_client_and_return_existing("Alice", alice_message_sender)
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let inserted_medium = room
+			.insert_medium(LiveMedium::new("Rocket Launch".to_string()), uint!(0))
+			.expect("Failed to insert medium");
+		let bookmark = room
+			.add_bookmark(
+				&inserted_medium.medium.id().expect("Medium has no id"),
+				Duration::seconds(10),
+				"Liftoff",
+			)
 			.await
-			.expect("Did not get client handle!");
+			.expect("Failed to add bookmark");
+		let reinserted_medium = room
+			.insert_medium(LiveMedium::new("Rocket Launch".to_string()), inserted_medium.version)
+			.expect("Failed to insert medium again");
 
-		let request = InsertMediumRequest {
-			medium: MediumRequest::FixedLength {
-				name: "Metropolis".to_string(),
-				length_in_milliseconds: UInt::try_from(Duration::days(400).num_milliseconds()).unwrap(),
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			ListBookmarksRequest {}.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to list bookmarks");
+
+		assert_eq!(
+			SuccessMessage::Bookmarks {
+				medium_version: reinserted_medium.version,
+				bookmarks: vec![BookmarkResponse {
+					id: bookmark.uuid.to_string(),
+					label: "Liftoff".to_string(),
+					position_in_milliseconds: 10_000,
+				}],
 			},
-			previous_version: uint!(0),
+			response
+		);
+	}
+
+	#[tokio::test]
+	async fn should_get_bookmarks_of_the_current_medium_on_hello_response() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let inserted_medium = room
+			.insert_medium(LiveMedium::new("Rocket Launch".to_string()), uint!(0))
+			.expect("Failed to insert medium");
+		let bookmark = room
+			.add_bookmark(
+				&inserted_medium.medium.id().expect("Medium has no id"),
+				Duration::seconds(10),
+				"Liftoff",
+			)
+			.await
+			.expect("Failed to add bookmark");
+
+		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
+		let request_id = test_client
+			.send_request(RegisterRequest {
+				name: "Alice".to_string(),
+				accepts_private_messages: true,
+				moderator_token: None,
+			})
+			.await;
+		register_client(&Configuration::test(), room, message_sender, message_receiver).await;
+		let response = test_client.receive_success_message(request_id).await;
+
+		let SuccessMessage::Hello { current_medium, .. } = response else {
+			panic!("Expected Hello response, got {response:?}");
 		};
-		let response = handle_request(&room, &alice, request.into())
+		assert_eq!(
+			vec![BookmarkResponse {
+				id: bookmark.uuid.to_string(),
+				label: "Liftoff".to_string(),
+				position_in_milliseconds: 10_000,
+			}],
+			current_medium.bookmarks
+		);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_be_able_to_remove_a_bookmark() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let inserted_medium = room
+			.insert_medium(LiveMedium::new("Rocket Launch".to_string()), uint!(0))
+			.expect("Failed to insert medium");
+		let bookmark = room
+			.add_bookmark(
+				&inserted_medium.medium.id().expect("Medium has no id"),
+				Duration::seconds(10),
+				"Liftoff",
+			)
 			.await
-			.expect_err("Failed to ger error response");
+			.expect("Failed to add bookmark");
+		let remove_request = || RemoveBookmarkRequest {
+			id: bookmark.uuid.to_string(),
+		};
 
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			remove_request().into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to remove bookmark");
+		assert_eq!(SuccessMessage::Success, response);
 		assert_eq!(
-			response,
-			ErrorMessage::builder()
-				.error(ErrorMessageType::InvalidFormat)
-				.message("Length of a medium must not be larger than one year.".to_string())
-				.build()
+			alice_test_client.receive_broadcast_message().await,
+			BookmarksChangedBroadcast {
+				changed_by_name: alice.name(),
+				changed_by_id: alice.id(),
+				medium_version: inserted_medium.version,
+				bookmarks: vec![],
+			}
+			.into()
+		);
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			remove_request().into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Removed bookmark twice");
+		assert_eq!(ErrorMessageType::InvalidOperation, response.error);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_not_be_able_to_bookmark_beyond_the_end_of_the_medium() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		let inserted_medium = room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			AddBookmarkRequest {
+				medium_version: inserted_medium.version,
+				position_in_milliseconds: uint!(9_180_001),
+				label: "The End".to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Added bookmark beyond the end of the medium");
+
+		assert_eq!(ErrorMessageType::InvalidOperation, response.error);
+		assert!(
+			room.bookmarks(&inserted_medium.medium)
+				.await
+				.expect("Failed to get bookmarks")
+				.is_empty()
 		);
 	}
 
@@ -550,6 +3499,7 @@ mod test {
 			.expect("Failed to insert medium");
 
 		let response = handle_request(
+			&test_application_context().await,
 			&room,
 			&alice,
 			PlayRequest {
@@ -558,6 +3508,7 @@ mod test {
 				start_time_in_milliseconds: int!(-1024),
 			}
 			.into(),
+			uint!(0),
 		)
 		.await
 		.expect("Failed to get success response");
@@ -567,12 +3518,15 @@ mod test {
 		let bob_broadcast = bob_test_client.receive_broadcast_message().await;
 
 		let expected_broadcast = MediumStateChangedBroadcast {
-			changed_by_name: alice.name().to_string(),
-			changed_by_id: alice.id(),
+			changed_by_name: alice.name(),
+			changed_by_id: Some(alice.id()),
 			medium: VersionedMediumBroadcast {
 				medium: MediumBroadcast::FixedLength {
 					name: medium.name,
 					length_in_milliseconds: UInt::try_from(medium.length.num_milliseconds()).unwrap(),
+					fingerprint: None,
+					hosted_id: None,
+					subtitles: SubtitlesResponse::default(),
 					playback_skipped: true,
 					playback_state: PlaybackStateResponse::Playing {
 						start_time_in_milliseconds: int!(-1024),
@@ -586,6 +3540,52 @@ mod test {
 		assert_eq!(bob_broadcast, expected_broadcast.into());
 	}
 
+	#[tokio::test]
+	async fn a_live_medium_should_keep_playing_regardless_of_the_start_time() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let inserted_medium = room
+			.insert_medium(LiveMedium::new("Rocket Launch".to_string()), uint!(0))
+			.expect("Failed to insert medium");
+		let two_years_ago = Int::try_from(-Duration::days(2 * 365).num_milliseconds()).unwrap();
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			PlayRequest {
+				previous_version: inserted_medium.version,
+				skipped: true,
+				start_time_in_milliseconds: two_years_ago,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to get success response");
+		assert_eq!(response, SuccessMessage::Success);
+
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			MediumStateChangedBroadcast {
+				changed_by_name: alice.name(),
+				changed_by_id: Some(alice.id()),
+				medium: VersionedMediumBroadcast {
+					medium: MediumBroadcast::Live {
+						name: "Rocket Launch".to_string(),
+						subtitles: SubtitlesResponse::default(),
+						playback_skipped: true,
+						playback_state: PlaybackStateResponse::Playing {
+							start_time_in_milliseconds: two_years_ago,
+						},
+					},
+					version: uint!(2),
+				},
+			}
+			.into()
+		);
+	}
+
 	#[tokio::test]
 	async fn the_client_should_be_able_to_pause_the_inserted_medium() {
 		let room = room(ReferenceTimer::default(), 2).await;
@@ -601,6 +3601,7 @@ mod test {
 			.expect("Failed to play medium.");
 
 		let response = handle_request(
+			&test_application_context().await,
 			&room,
 			&bob,
 			PauseRequest {
@@ -609,6 +3610,7 @@ mod test {
 				position_in_milliseconds: uint!(1027),
 			}
 			.into(),
+			uint!(0),
 		)
 		.await
 		.expect("Failed to get success response");
@@ -618,12 +3620,15 @@ mod test {
 		let bob_broadcast = bob_test_client.receive_broadcast_message().await;
 
 		let expected_broadcast = MediumStateChangedBroadcast {
-			changed_by_name: bob.name().to_string(),
-			changed_by_id: bob.id(),
+			changed_by_name: bob.name(),
+			changed_by_id: Some(bob.id()),
 			medium: VersionedMediumBroadcast {
 				medium: MediumBroadcast::FixedLength {
 					name: medium.name,
 					length_in_milliseconds: UInt::try_from(medium.length.num_milliseconds()).unwrap(),
+					fingerprint: None,
+					hosted_id: None,
+					subtitles: SubtitlesResponse::default(),
 					playback_skipped: false,
 					playback_state: PlaybackStateResponse::Paused {
 						position_in_milliseconds: uint!(1027),
@@ -649,6 +3654,7 @@ mod test {
 			.expect("Failed to insert medium");
 
 		let response = handle_request(
+			&test_application_context().await,
 			&room,
 			&bob,
 			PauseRequest {
@@ -657,6 +3663,7 @@ mod test {
 				position_in_milliseconds: uint!(1000),
 			}
 			.into(),
+			uint!(0),
 		)
 		.await
 		.expect("Failed to get success response");
@@ -666,12 +3673,15 @@ mod test {
 		let bob_broadcast = bob_test_client.receive_broadcast_message().await;
 
 		let expected_broadcast = MediumStateChangedBroadcast {
-			changed_by_name: bob.name().to_string(),
-			changed_by_id: bob.id(),
+			changed_by_name: bob.name(),
+			changed_by_id: Some(bob.id()),
 			medium: VersionedMediumBroadcast {
 				medium: MediumBroadcast::FixedLength {
 					name: medium.name,
 					length_in_milliseconds: UInt::try_from(medium.length.num_milliseconds()).unwrap(),
+					fingerprint: None,
+					hosted_id: None,
+					subtitles: SubtitlesResponse::default(),
 					playback_skipped: true,
 					playback_state: PlaybackStateResponse::Paused {
 						position_in_milliseconds: uint!(1000),
@@ -691,7 +3701,7 @@ mod test {
 
 		let room = room(ReferenceTimer::default(), 1).await;
 		let (alice, _) = room
-			.add_client_and_return_existing("Alice", alice_message_sender)
+			.add_client_and_return_existing("Alice", false, alice_message_sender)
 			.await
 			.expect("Did not get client handle!");
 
@@ -699,6 +3709,7 @@ mod test {
 		let inserted_medium = room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
 
 		let response = handle_request(
+			&test_application_context().await,
 			&room,
 			&alice,
 			PlayRequest {
@@ -707,6 +3718,7 @@ mod test {
 				start_time_in_milliseconds: int!(0),
 			}
 			.into(),
+			uint!(0),
 		)
 		.await
 		.expect_err("Failed to get error response");
@@ -725,7 +3737,7 @@ mod test {
 
 		let room = room(ReferenceTimer::default(), 1).await;
 		let (alice, _) = room
-			.add_client_and_return_existing("Alice", alice_message_sender)
+			.add_client_and_return_existing("Alice", false, alice_message_sender)
 			.await
 			.expect("Did not get client handle!");
 
@@ -733,6 +3745,7 @@ mod test {
 		let inserted_medium = room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
 
 		let response = handle_request(
+			&test_application_context().await,
 			&room,
 			&alice,
 			PauseRequest {
@@ -741,29 +3754,524 @@ mod test {
 				position_in_milliseconds: uint!(0),
 			}
 			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Failed to get error response");
+		assert_eq!(
+			response,
+			ErrorMessage::builder()
+				.error(ErrorMessageType::IncorrectMediumVersion)
+				.message("Medium version is incorrect. Request had 2 but current version is 1.".to_string())
+				.build()
+		);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_not_be_able_to_insert_medium_with_incorrect_version() {
+		let (alice_message_sender, _message_receiver, _alice_test_client) = WebsocketTestClient::new();
+
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _) = room
+			.add_client_and_return_existing("Alice", false, alice_message_sender)
+			.await
+			.expect("Did not get client handle!");
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			InsertMediumRequest {
+				previous_version: uint!(1),
+				medium: MediumRequest::Empty,
+			}
+			.into(),
+			uint!(0),
 		)
 		.await
 		.expect_err("Failed to get error response");
 		assert_eq!(
-			response,
-			ErrorMessage::builder()
-				.error(ErrorMessageType::IncorrectMediumVersion)
-				.message("Medium version is incorrect. Request had 2 but current version is 1.".to_string())
-				.build()
+			response,
+			ErrorMessage::builder()
+				.error(ErrorMessageType::IncorrectMediumVersion)
+				.message("Medium version is incorrect. Request had 1 but current version is 0.".to_string())
+				.build()
+		);
+	}
+
+	#[tokio::test]
+	async fn should_answer_time_sync_requests_with_receive_and_transmit_time() {
+		let (clock, clock_mock) = quanta::Clock::mock();
+		let reference_timer = ReferenceTimer::default()
+			.with_clock(clock)
+			.with_start_time(DateTime::UNIX_EPOCH);
+		let room = room(reference_timer, 1).await;
+		let (client, _test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		clock_mock.increment(std::time::Duration::from_millis(1337));
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&client,
+			TimeSyncRequest {
+				originate_timestamp_in_milliseconds: uint!(42),
+			}
+			.into(),
+			uint!(1000),
+		)
+		.await
+		.expect("Failed to synchronize time");
+
+		assert_eq!(
+			SuccessMessage::TimeSync(TimeSyncResponse {
+				originate_timestamp_in_milliseconds: Some(uint!(42)),
+				receive_timestamp_in_milliseconds: uint!(1000),
+				transmit_timestamp_in_milliseconds: uint!(1337),
+			}),
+			response
+		);
+	}
+
+	#[tokio::test]
+	async fn should_accept_position_reports_within_the_resync_threshold() {
+		let (clock, _clock_mock) = quanta::Clock::mock();
+		let reference_timer = ReferenceTimer::default()
+			.with_clock(clock)
+			.with_start_time(DateTime::UNIX_EPOCH);
+		let room = room(reference_timer, 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+		let playing_medium = room
+			.play_medium(Duration::milliseconds(1000), uint!(1))
+			.expect("Failed to play medium");
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			PositionReportRequest {
+				medium_version: playing_medium.version,
+				position_in_milliseconds: uint!(9_000),
+			}
+			.into(),
+			uint!(10_200),
+		)
+		.await
+		.expect("Failed to report position");
+
+		assert_eq!(SuccessMessage::Success, response);
+		assert_eq!(Some(Duration::milliseconds(-200)), alice.drift(playing_medium.version));
+	}
+
+	#[tokio::test]
+	async fn should_request_resync_when_drifting_past_the_threshold() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+		let paused_medium = room
+			.pause_medium(Duration::seconds(10), uint!(1))
+			.expect("Failed to pause medium");
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			PositionReportRequest {
+				medium_version: paused_medium.version,
+				position_in_milliseconds: uint!(12_000),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to report position");
+
+		assert_eq!(
+			SuccessMessage::Resync {
+				drift_in_milliseconds: int!(2_000),
+				current_medium: VersionedMediumResponse::new(paused_medium.clone(), Vec::new()),
+			},
+			response
+		);
+		assert_eq!(Some(Duration::seconds(2)), alice.drift(paused_medium.version));
+		assert_eq!(None, alice.drift(paused_medium.version + uint!(1)));
+	}
+
+	#[tokio::test]
+	async fn the_client_should_not_be_able_to_report_position_with_incorrect_version() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			PositionReportRequest {
+				medium_version: uint!(1),
+				position_in_milliseconds: uint!(0),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Position report should have failed");
+
+		assert_eq!(ErrorMessageType::IncorrectMediumVersion, response.error);
+		assert_eq!(None, alice.drift(uint!(1)));
+	}
+
+	#[tokio::test]
+	async fn the_client_should_not_be_able_to_report_position_without_medium() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			PositionReportRequest {
+				medium_version: uint!(0),
+				position_in_milliseconds: uint!(0),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Position report should have failed");
+
+		assert_eq!(
+			ErrorMessage::builder()
+				.error(ErrorMessageType::InvalidOperation)
+				.message("Can't report a position without a medium.".to_string())
+				.build(),
+			response
+		);
+	}
+
+	#[tokio::test]
+	async fn should_list_drift_of_other_clients_when_joining_a_room() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (stephanie, _) = room
+			.add_client_and_return_existing("Stephanie", false, FakeMessageSender::default().into())
+			.await
+			.unwrap();
+		stephanie.update_drift(uint!(0), Duration::milliseconds(-42));
+
+		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
+		let request_id = test_client
+			.send_request(RegisterRequest {
+				name: "Johnny 5".to_string(),
+				accepts_private_messages: true,
+				moderator_token: None,
+			})
+			.await;
+		register_client(&Configuration::test(), room, message_sender, message_receiver).await;
+		let response = test_client.receive_success_message(request_id).await;
+
+		let SuccessMessage::Hello { clients, .. } = response else {
+			panic!("Expected Hello response, got {response:?}");
+		};
+		assert_eq!(Some(int!(-42)), clients[0].drift_in_milliseconds);
+	}
+
+	#[tokio::test]
+	async fn should_pause_while_a_client_is_buffering_and_resume_once_everyone_is_ready() {
+		let (clock, clock_mock) = quanta::Clock::mock();
+		let reference_timer = ReferenceTimer::default()
+			.with_clock(clock)
+			.with_start_time(DateTime::UNIX_EPOCH);
+		let room = room_with_pause_while_buffering(reference_timer, 2, true).await;
+		let (_alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium.clone(), uint!(0))
+			.expect("Failed to insert medium");
+		room.play_medium(Duration::zero(), uint!(1))
+			.expect("Failed to play medium");
+
+		clock_mock.increment(std::time::Duration::from_secs(5));
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&bob,
+			ReadinessRequest {
+				readiness: Readiness::Buffering,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to report buffering");
+		assert_eq!(SuccessMessage::Success, response);
+
+		let expected_pause_broadcast = MediumStateChangedBroadcast::by_server(VersionedMediumBroadcast {
+			medium: MediumBroadcast::FixedLength {
+				name: medium.name.clone(),
+				length_in_milliseconds: UInt::try_from(medium.length.num_milliseconds()).unwrap(),
+				fingerprint: None,
+				hosted_id: None,
+				subtitles: SubtitlesResponse::default(),
+				playback_skipped: false,
+				playback_state: PlaybackStateResponse::Paused {
+					position_in_milliseconds: uint!(5000),
+				},
+			},
+			version: uint!(3),
+		});
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			expected_pause_broadcast.clone().into()
+		);
+		assert_eq!(
+			bob_test_client.receive_broadcast_message().await,
+			expected_pause_broadcast.into()
+		);
+
+		clock_mock.increment(std::time::Duration::from_secs(3));
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&bob,
+			ReadinessRequest {
+				readiness: Readiness::Ready,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to report readiness");
+
+		let expected_resume_broadcast = MediumStateChangedBroadcast::by_server(VersionedMediumBroadcast {
+			medium: MediumBroadcast::FixedLength {
+				name: medium.name,
+				length_in_milliseconds: UInt::try_from(medium.length.num_milliseconds()).unwrap(),
+				fingerprint: None,
+				hosted_id: None,
+				subtitles: SubtitlesResponse::default(),
+				playback_skipped: false,
+				playback_state: PlaybackStateResponse::Playing {
+					start_time_in_milliseconds: int!(3000),
+				},
+			},
+			version: uint!(4),
+		});
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			expected_resume_broadcast.clone().into()
+		);
+		assert_eq!(
+			bob_test_client.receive_broadcast_message().await,
+			expected_resume_broadcast.into()
+		);
+	}
+
+	#[tokio::test]
+	async fn should_not_pause_for_buffering_clients_unless_enabled() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+		let playing_medium = room
+			.play_medium(Duration::zero(), uint!(1))
+			.expect("Failed to play medium");
+
+		handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			ReadinessRequest {
+				readiness: Readiness::Buffering,
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to report buffering");
+
+		assert_eq!(Readiness::Buffering, alice.readiness());
+		assert_eq!(playing_medium, room.medium());
+	}
+
+	#[tokio::test]
+	async fn should_not_resume_a_medium_that_was_changed_while_buffering() {
+		let (clock, _clock_mock) = quanta::Clock::mock();
+		let reference_timer = ReferenceTimer::default()
+			.with_clock(clock)
+			.with_start_time(DateTime::UNIX_EPOCH);
+		let room = room_with_pause_while_buffering(reference_timer, 1, true).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+		room.play_medium(Duration::zero(), uint!(1))
+			.expect("Failed to play medium");
+
+		alice.update_readiness(Readiness::Buffering);
+		let paused_medium = room.update_buffering_pause().await.expect("Medium wasn't paused");
+		let skipped_medium = room
+			.pause_medium(Duration::seconds(42), paused_medium.version)
+			.expect("Failed to skip medium");
+		alice.update_readiness(Readiness::Ready);
+
+		assert_eq!(None, room.update_buffering_pause().await);
+		assert_eq!(skipped_medium, room.medium());
+	}
+
+	#[tokio::test]
+	async fn should_start_playing_at_the_scheduled_time() {
+		let (clock, _clock_mock) = quanta::Clock::mock();
+		let reference_timer = ReferenceTimer::default()
+			.with_clock(clock)
+			.with_start_time(DateTime::UNIX_EPOCH);
+		let time_source = TimeSource::test();
+		let context = application_context(Configuration::test(), time_source.clone()).await;
+		let room = room(reference_timer, 2).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (_bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium.clone(), uint!(0))
+			.expect("Failed to insert medium");
+		room.pause_medium(Duration::seconds(10), uint!(1))
+			.expect("Failed to pause medium");
+
+		let response = handle_request(
+			&context,
+			&room,
+			&alice,
+			SchedulePlayRequest {
+				previous_version: uint!(2),
+				scheduled_time_in_milliseconds: uint!(60_000),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to schedule play");
+		assert_eq!(SuccessMessage::Success, response);
+
+		let expected_scheduled_broadcast = PlaybackScheduledBroadcast {
+			changed_by_name: alice.name(),
+			changed_by_id: Some(alice.id()),
+			medium_version: uint!(2),
+			scheduled_time_in_milliseconds: Some(uint!(60_000)),
+		};
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			expected_scheduled_broadcast.clone().into()
+		);
+		assert_eq!(
+			bob_test_client.receive_broadcast_message().await,
+			expected_scheduled_broadcast.into()
+		);
+
+		time_source.wait_for_time_request().await;
+		time_source.advance_time(std::time::Duration::from_secs(60));
+
+		let expected_playing_broadcast = MediumStateChangedBroadcast::by_server(VersionedMediumBroadcast {
+			medium: MediumBroadcast::FixedLength {
+				name: medium.name,
+				length_in_milliseconds: UInt::try_from(medium.length.num_milliseconds()).unwrap(),
+				fingerprint: None,
+				hosted_id: None,
+				subtitles: SubtitlesResponse::default(),
+				playback_skipped: false,
+				playback_state: PlaybackStateResponse::Playing {
+					start_time_in_milliseconds: int!(50_000),
+				},
+			},
+			version: uint!(3),
+		});
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			expected_playing_broadcast.clone().into()
+		);
+		assert_eq!(
+			bob_test_client.receive_broadcast_message().await,
+			expected_playing_broadcast.into()
+		);
+	}
+
+	#[tokio::test]
+	async fn the_client_should_not_be_able_to_schedule_play_in_the_past() {
+		let (clock, clock_mock) = quanta::Clock::mock();
+		let reference_timer = ReferenceTimer::default()
+			.with_clock(clock)
+			.with_start_time(DateTime::UNIX_EPOCH);
+		let room = room(reference_timer, 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+
+		clock_mock.increment(std::time::Duration::from_secs(60));
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			SchedulePlayRequest {
+				previous_version: uint!(1),
+				scheduled_time_in_milliseconds: uint!(60_000),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Scheduling play should have failed");
+
+		assert_eq!(
+			ErrorMessage::builder()
+				.error(ErrorMessageType::InvalidOperation)
+				.message("Playback can only be scheduled in the future.".to_string())
+				.build(),
+			response
+		);
+		assert_eq!(None, room.cancel_scheduled_play());
+	}
+
+	#[tokio::test]
+	async fn the_client_should_be_able_to_cancel_scheduled_play() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+		room.schedule_play(Duration::days(365 * 100), uint!(1))
+			.expect("Failed to schedule play");
+
+		let response = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			CancelScheduledPlayRequest {}.into(),
+			uint!(0),
+		)
+		.await
+		.expect("Failed to cancel scheduled play");
+
+		assert_eq!(SuccessMessage::Success, response);
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			PlaybackScheduledBroadcast {
+				changed_by_name: alice.name(),
+				changed_by_id: Some(alice.id()),
+				medium_version: uint!(1),
+				scheduled_time_in_milliseconds: None,
+			}
+			.into()
 		);
+		assert_eq!(None, room.cancel_scheduled_play());
 	}
 
 	#[tokio::test]
-	async fn the_client_should_not_be_able_to_insert_medium_with_incorrect_version() {
-		let (alice_message_sender, _message_receiver, _alice_test_client) = WebsocketTestClient::new();
-
+	async fn changing_the_medium_should_cancel_scheduled_play() {
 		let room = room(ReferenceTimer::default(), 1).await;
-		let (alice, _) = room
-			.add_client_and_return_existing("Alice", alice_message_sender)
-			.await
-			.expect("Did not get client handle!");
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+		room.schedule_play(Duration::days(365 * 100), uint!(1))
+			.expect("Failed to schedule play");
 
-		let response = handle_request(
+		handle_request(
+			&test_application_context().await,
 			&room,
 			&alice,
 			InsertMediumRequest {
@@ -771,16 +4279,97 @@ mod test {
 				medium: MediumRequest::Empty,
 			}
 			.into(),
+			uint!(0),
 		)
 		.await
-		.expect_err("Failed to get error response");
+		.expect("Failed to eject medium");
+
+		assert!(matches!(
+			alice_test_client.receive_broadcast_message().await,
+			BroadcastMessage::MediumStateChanged(_)
+		));
 		assert_eq!(
-			response,
-			ErrorMessage::builder()
-				.error(ErrorMessageType::IncorrectMediumVersion)
-				.message("Medium version is incorrect. Request had 1 but current version is 0.".to_string())
-				.build()
+			alice_test_client.receive_broadcast_message().await,
+			PlaybackScheduledBroadcast {
+				changed_by_name: alice.name(),
+				changed_by_id: Some(alice.id()),
+				medium_version: uint!(1),
+				scheduled_time_in_milliseconds: None,
+			}
+			.into()
+		);
+		assert_eq!(None, room.cancel_scheduled_play());
+	}
+
+	#[tokio::test]
+	async fn should_broadcast_clients_with_mismatching_fingerprints() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let fingerprint = Fingerprint {
+			size_in_bytes: 4_700_000_000,
+			hash: "cafebabe".to_string(),
+		};
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153))
+			.with_fingerprint(fingerprint.clone());
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+
+		let report_fingerprint = |client: &Client, hash: &str| {
+			let request = FingerprintReportRequest {
+				medium_version: uint!(1),
+				fingerprint: FingerprintRequest {
+					size_in_bytes: UInt::new_wrapping(4_700_000_000),
+					hash: hash.to_string(),
+				},
+			};
+			let room = room.clone();
+			let client = client.clone();
+			async move {
+				handle_request(
+					&test_application_context().await,
+					&room,
+					&client,
+					request.into(),
+					uint!(0),
+				)
+				.await
+				.expect("Failed to report fingerprint")
+			}
+		};
+
+		assert_eq!(SuccessMessage::Success, report_fingerprint(&bob, "deadbeef").await);
+		let expected_mismatch_broadcast = FingerprintMismatchBroadcast {
+			medium_version: uint!(1),
+			mismatching_clients: vec![MismatchingClient {
+				id: bob.id(),
+				name: bob.name(),
+			}],
+		};
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			expected_mismatch_broadcast.clone().into()
+		);
+		assert_eq!(
+			bob_test_client.receive_broadcast_message().await,
+			expected_mismatch_broadcast.into()
+		);
+
+		// matching fingerprints don't change the list of mismatching clients
+		assert_eq!(SuccessMessage::Success, report_fingerprint(&alice, "cafebabe").await);
+		assert_eq!(SuccessMessage::Success, report_fingerprint(&bob, "cafebabe").await);
+		let expected_resolved_broadcast = FingerprintMismatchBroadcast {
+			medium_version: uint!(1),
+			mismatching_clients: Vec::new(),
+		};
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			expected_resolved_broadcast.clone().into()
+		);
+		assert_eq!(
+			bob_test_client.receive_broadcast_message().await,
+			expected_resolved_broadcast.into()
 		);
+		assert_eq!(Some(fingerprint), alice.fingerprint(uint!(1)));
 	}
 
 	#[tokio::test]
@@ -797,12 +4386,21 @@ mod test {
 			async move {
 				let room = &room;
 				let (pong_sender, _pong_receiver) = mpsc::channel(0);
-				handle_messages(room, client_handle, message_receiver, pong_sender).await;
+				handle_messages(
+					&test_application_context().await,
+					room,
+					client_handle,
+					message_receiver,
+					pong_sender,
+				)
+				.await;
 			}
 		});
 
 		let register_message = RegisterRequest {
 			name: "Parcival".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
 		};
 
 		let request_id = test_client.send_request(register_message).await;
@@ -821,10 +4419,14 @@ mod test {
 		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
 		let reference_timer = ReferenceTimer::default();
 		let room = room(reference_timer, 10).await;
-		let register_request = RegisterRequest { name: "	 ".to_string() };
+		let register_request = RegisterRequest {
+			name: "	 ".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
+		};
 
 		let request_id = test_client.send_request(register_request).await;
-		register_client(room, message_sender, message_receiver).await;
+		register_client(&Configuration::test(), room, message_sender, message_receiver).await;
 		let response = test_client.receive_error_message(Some(request_id)).await;
 
 		assert_eq!(
@@ -843,7 +4445,7 @@ mod test {
 
 		// "Ferris" is already a registered client
 		let fake_message_sender = FakeMessageSender::default().into();
-		room.add_client_and_return_existing("Ferris", fake_message_sender)
+		room.add_client_and_return_existing("Ferris", false, fake_message_sender)
 			.await
 			.expect("Could not register 'Ferris'!");
 
@@ -851,10 +4453,12 @@ mod test {
 		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
 		let register_request = RegisterRequest {
 			name: "Ferris".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
 		};
 
 		let request_id = test_client.send_request(register_request).await;
-		register_client(room, message_sender, message_receiver).await;
+		register_client(&Configuration::test(), room, message_sender, message_receiver).await;
 		let response = test_client.receive_error_message(Some(request_id)).await;
 
 		// Then I expect an error
@@ -867,13 +4471,86 @@ mod test {
 		);
 	}
 
+	#[tokio::test]
+	async fn should_not_register_clients_with_a_moderator_name_without_the_moderator_token() {
+		let room = room(ReferenceTimer::default(), 10).await;
+
+		for moderator_token in [None, Some("guessed-token".to_string())] {
+			let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
+			let register_request = RegisterRequest {
+				name: "Moderator".to_string(),
+				accepts_private_messages: true,
+				moderator_token,
+			};
+
+			let request_id = test_client.send_request(register_request).await;
+			register_client(&Configuration::test(), room.clone(), message_sender, message_receiver).await;
+			let response = test_client.receive_error_message(Some(request_id)).await;
+
+			assert_eq!(
+				ErrorMessage::builder()
+					.error(ErrorMessageType::NotAllowed)
+					.message("Client name is reserved for a moderator.".to_string())
+					.build(),
+				response
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn should_register_moderators_with_the_moderator_token() {
+		let room = room(ReferenceTimer::default(), 10).await;
+		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
+		let register_request = RegisterRequest {
+			name: "Moderator".to_string(),
+			accepts_private_messages: true,
+			moderator_token: Some("moderator-token".to_string()),
+		};
+
+		let request_id = test_client.send_request(register_request).await;
+		let (client, _) = register_client(&Configuration::test(), room, message_sender, message_receiver)
+			.await
+			.expect("Failed to register the moderator");
+		test_client.receive_success_message(request_id).await;
+
+		assert!(client.is_moderator());
+	}
+
+	#[tokio::test]
+	async fn clients_should_not_be_able_to_take_the_name_of_a_moderator() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		let error = handle_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			ChangeNameRequest {
+				name: "Moderator".to_string(),
+			}
+			.into(),
+			uint!(0),
+		)
+		.await
+		.expect_err("Alice could take the name of a moderator");
+
+		assert_eq!(
+			ErrorMessage::builder()
+				.error(ErrorMessageType::NotAllowed)
+				.message("Client name is reserved for a moderator.".to_string())
+				.build(),
+			error
+		);
+		assert_eq!("Alice", alice.name());
+	}
+
 	#[tokio::test]
 	async fn should_not_register_clients_if_room_is_full() {
 		let reference_timer = ReferenceTimer::default();
 		let room = room(reference_timer, 1).await;
 		{
 			let message_sender = MessageSender::from(FakeMessageSender::default());
-			room.add_client_and_return_existing("Fake", message_sender)
+			room.add_client_and_return_existing("Fake", false, message_sender)
 				.await
 				.unwrap();
 		}
@@ -881,10 +4558,12 @@ mod test {
 		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
 		let register_request = RegisterRequest {
 			name: "second".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
 		};
 
 		let request_id = test_client.send_request(register_request).await;
-		register_client(room, message_sender, message_receiver).await;
+		register_client(&Configuration::test(), room, message_sender, message_receiver).await;
 		let response = test_client.receive_error_message(Some(request_id)).await;
 
 		assert_eq!(
@@ -912,25 +4591,34 @@ mod test {
 		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
 		let register_request = RegisterRequest {
 			name: "Johnny 5".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
 		};
 
 		let request_id = test_client.send_request(register_request).await;
-		register_client(room, message_sender, message_receiver).await;
+		let (client, _) = register_client(&Configuration::test(), room, message_sender, message_receiver)
+			.await
+			.expect("Failed to register client");
 		let response = test_client.receive_success_message(request_id).await;
 
 		assert_eq!(
 			SuccessMessage::Hello {
 				id: SessionId::from(0),
+				session_token: client.session_token().to_string(),
 				clients: vec![],
 				current_medium: VersionedMediumResponse {
 					medium: MediumResponse::FixedLength {
 						name: video_name,
 						length_in_milliseconds: u64::try_from(video_length.num_milliseconds()).unwrap(),
+						fingerprint: None,
+						hosted_id: None,
+						subtitles: SubtitlesResponse::default(),
 						playback_state: PlaybackStateResponse::Playing {
 							start_time_in_milliseconds: int!(0),
 						}
 					},
 					version: uint!(2),
+					bookmarks: vec![],
 				}
 			},
 			response
@@ -943,27 +4631,35 @@ mod test {
 		let room = room(reference_timer, 2).await;
 		let fake_message_sender = FakeMessageSender::default();
 		let (stephanie, _) = room
-			.add_client_and_return_existing("Stephanie", fake_message_sender.into())
+			.add_client_and_return_existing("Stephanie", false, fake_message_sender.into())
 			.await
 			.unwrap();
 
 		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
 		let register_request = RegisterRequest {
 			name: "Johnny 5".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
 		};
 
 		let request_id = test_client.send_request(register_request).await;
-		register_client(room, message_sender, message_receiver).await;
+		let (client, _) = register_client(&Configuration::test(), room, message_sender, message_receiver)
+			.await
+			.expect("Failed to register client");
 		let response = test_client.receive_success_message(request_id).await;
 
 		assert_eq!(
 			SuccessMessage::Hello {
 				id: SessionId::from(1),
+				session_token: client.session_token().to_string(),
 				clients: vec![ClientResponse {
 					id: stephanie.id(),
-					name: stephanie.name().to_string(),
+					name: stephanie.name(),
+					drift_in_milliseconds: None,
+					accepts_private_messages: true,
+					profile: ProfileResponse::default(),
 				}],
-				current_medium: VersionedMedium::default().into(),
+				current_medium: VersionedMediumResponse::new(VersionedMedium::default(), Vec::new()),
 			},
 			response
 		);
@@ -976,17 +4672,22 @@ mod test {
 		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
 		let register_request = RegisterRequest {
 			name: "Johnny 5".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
 		};
 
 		let request_id = test_client.send_request(register_request).await;
-		register_client(room, message_sender, message_receiver).await;
+		let (client, _) = register_client(&Configuration::test(), room, message_sender, message_receiver)
+			.await
+			.expect("Failed to register client");
 		let response = test_client.receive_success_message(request_id).await;
 
 		assert_eq!(
 			SuccessMessage::Hello {
 				id: SessionId::from(0),
+				session_token: client.session_token().to_string(),
 				clients: vec![],
-				current_medium: VersionedMedium::default().into(),
+				current_medium: VersionedMediumResponse::new(VersionedMedium::default(), Vec::new()),
 			},
 			response
 		);
@@ -1004,31 +4705,371 @@ mod test {
 		let (message_sender, message_receiver, mut test_client) = WebsocketTestClient::new();
 		let register_request = RegisterRequest {
 			name: "Johnny 5".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
 		};
 
 		let request_id = test_client.send_request(register_request).await;
-		register_client(room, message_sender, message_receiver).await;
+		let (client, _) = register_client(&Configuration::test(), room, message_sender, message_receiver)
+			.await
+			.expect("Failed to register client");
 		let response = test_client.receive_success_message(request_id).await;
 
 		assert_eq!(
 			SuccessMessage::Hello {
 				id: SessionId::from(0),
+				session_token: client.session_token().to_string(),
 				clients: vec![],
 				current_medium: VersionedMediumResponse {
 					medium: MediumResponse::FixedLength {
 						name: video_name,
 						length_in_milliseconds: u64::try_from(video_length.num_milliseconds()).unwrap(),
+						fingerprint: None,
+						hosted_id: None,
+						subtitles: SubtitlesResponse::default(),
 						playback_state: PlaybackStateResponse::Paused {
 							position_in_milliseconds: uint!(0),
 						}
 					},
 					version: uint!(1),
+					bookmarks: vec![],
 				}
 			},
 			response
 		);
 	}
 
+	#[tokio::test]
+	async fn typing_should_only_be_broadcast_when_it_changes() {
+		let room = room(ReferenceTimer::default(), 2).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (_bob, mut bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+
+		let report_typing = |typing| {
+			let room = room.clone();
+			let alice = alice.clone();
+			async move {
+				handle_request(
+					&test_application_context().await,
+					&room,
+					&alice,
+					TypingRequest { typing }.into(),
+					uint!(0),
+				)
+				.await
+				.expect("Failed to report typing")
+			}
+		};
+		let typing_broadcast = |typing| {
+			BroadcastMessage::from(TypingBroadcast {
+				id: alice.id(),
+				name: alice.name(),
+				typing,
+			})
+		};
+
+		report_typing(true).await;
+		assert_eq!(
+			typing_broadcast(true),
+			bob_test_client.receive_broadcast_message().await
+		);
+
+		// Reporting the same state again is not broadcast
+		report_typing(true).await;
+		report_typing(false).await;
+		assert_eq!(
+			typing_broadcast(false),
+			bob_test_client.receive_broadcast_message().await
+		);
+	}
+
+	#[tokio::test]
+	async fn typing_should_expire_unless_reported_again() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let time_source = TimeSource::test();
+		let context = application_context(Configuration::test(), time_source.clone()).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		handle_request(&context, &room, &alice, TypingRequest { typing: true }.into(), uint!(0))
+			.await
+			.expect("Failed to report typing");
+		let BroadcastMessage::Typing(TypingBroadcast { typing: true, .. }) =
+			alice_test_client.receive_broadcast_message().await
+		else {
+			panic!("Expected typing to start");
+		};
+
+		tokio::spawn({
+			let room = room.clone();
+			let alice = alice.clone();
+			let time_source = time_source.clone();
+			async move { expire_typing(&room, &alice, &time_source).await }
+		});
+		time_source.wait_for_time_request().await;
+		time_source.advance_time(TYPING_EXPIRY);
+
+		assert_eq!(
+			BroadcastMessage::from(TypingBroadcast {
+				id: alice.id(),
+				name: alice.name(),
+				typing: false,
+			}),
+			alice_test_client.receive_broadcast_message().await
+		);
+	}
+
+	#[tokio::test]
+	async fn votes_should_be_counted_and_changed_until_the_poll_is_closed() {
+		let room = room(ReferenceTimer::default(), 3).await;
+		let context = application_context(Configuration::test(), TimeSource::default()).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (bob, _bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+
+		handle_poll_request(
+			&context,
+			&room,
+			&alice,
+			CreatePollRequest {
+				duration_in_milliseconds: uint!(60_000),
+				poll: PollRequest::Custom {
+					question: "What should we watch next?".to_string(),
+					options: vec!["Metropolis".to_string(), "Nosferatu".to_string()],
+				},
+			},
+		)
+		.await
+		.expect("Failed to create poll");
+		let (poll_id, votes, closed) = poll_state(&alice_test_client.receive_broadcast_message().await);
+		assert_eq!((vec![uint!(0), uint!(0)], false), (votes, closed));
+
+		for option in [uint!(0), uint!(1)] {
+			handle_poll_request(
+				&context,
+				&room,
+				&bob,
+				VoteRequest {
+					poll_id: poll_id.clone(),
+					option,
+				},
+			)
+			.await
+			.expect("Failed to vote");
+		}
+		let (_, votes, _) = poll_state(&alice_test_client.receive_broadcast_message().await);
+		assert_eq!(vec![uint!(1), uint!(0)], votes);
+		let (_, votes, _) = poll_state(&alice_test_client.receive_broadcast_message().await);
+		assert_eq!(vec![uint!(0), uint!(1)], votes);
+
+		let close_by_bob_error = handle_poll_request(
+			&context,
+			&room,
+			&bob,
+			ClosePollRequest {
+				poll_id: poll_id.clone(),
+			},
+		)
+		.await
+		.expect_err("Bob could close Alice's poll");
+		assert_eq!(ErrorMessageType::NotAllowed, close_by_bob_error.error);
+
+		handle_poll_request(
+			&context,
+			&room,
+			&alice,
+			ClosePollRequest {
+				poll_id: poll_id.clone(),
+			},
+		)
+		.await
+		.expect("Failed to close poll");
+		let (_, votes, closed) = poll_state(&alice_test_client.receive_broadcast_message().await);
+		assert_eq!((vec![uint!(0), uint!(1)], true), (votes, closed));
+
+		let late_vote_error = handle_poll_request(
+			&context,
+			&room,
+			&alice,
+			VoteRequest {
+				poll_id,
+				option: uint!(0),
+			},
+		)
+		.await
+		.expect_err("Could vote in a closed poll");
+		assert_eq!(ErrorMessageType::InvalidOperation, late_vote_error.error);
+	}
+
+	#[tokio::test]
+	async fn skip_polls_should_eject_the_medium_once_the_majority_voted_yes() {
+		let room = room(ReferenceTimer::default(), 3).await;
+		let context = application_context(Configuration::test(), TimeSource::default()).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let (bob, _bob_test_client) = WebsocketTestClient::in_room("Bob", &room).await;
+		let (_carol, _carol_test_client) = WebsocketTestClient::in_room("Carol", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+
+		handle_poll_request(
+			&context,
+			&room,
+			&alice,
+			CreatePollRequest {
+				duration_in_milliseconds: uint!(60_000),
+				poll: PollRequest::Skip {
+					medium_version: uint!(1),
+				},
+			},
+		)
+		.await
+		.expect("Failed to create skip poll");
+		let (poll_id, _, _) = poll_state(&alice_test_client.receive_broadcast_message().await);
+
+		for voter in [&alice, &bob] {
+			handle_poll_request(
+				&context,
+				&room,
+				voter,
+				VoteRequest {
+					poll_id: poll_id.clone(),
+					option: UInt::from(YES_OPTION),
+				},
+			)
+			.await
+			.expect("Failed to vote");
+			let (_, _, closed) = poll_state(&alice_test_client.receive_broadcast_message().await);
+			assert!(!closed, "Poll was closed before everyone voted");
+		}
+
+		let (_, votes, closed) = poll_state(&alice_test_client.receive_broadcast_message().await);
+		assert_eq!((vec![uint!(2), uint!(0)], true), (votes, closed));
+		assert_eq!(
+			alice_test_client.receive_broadcast_message().await,
+			MediumStateChangedBroadcast::by_server(VersionedMediumBroadcast {
+				medium: MediumBroadcast::Empty,
+				version: uint!(2),
+			})
+			.into()
+		);
+		assert_eq!(Medium::Empty, room.medium().medium);
+	}
+
+	#[tokio::test]
+	async fn skip_polls_should_not_be_created_for_an_outdated_medium() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, _alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+
+		let error = handle_poll_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			CreatePollRequest {
+				duration_in_milliseconds: uint!(60_000),
+				poll: PollRequest::Skip {
+					medium_version: uint!(0),
+				},
+			},
+		)
+		.await
+		.expect_err("Created skip poll for an outdated medium");
+		assert_eq!(ErrorMessageType::IncorrectMediumVersion, error.error);
+	}
+
+	#[tokio::test]
+	async fn polls_should_be_closed_at_their_deadline() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let time_source = TimeSource::test();
+		let context = application_context(Configuration::test(), time_source.clone()).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+
+		handle_poll_request(
+			&context,
+			&room,
+			&alice,
+			CreatePollRequest {
+				duration_in_milliseconds: uint!(60_000),
+				poll: PollRequest::Custom {
+					question: "Popcorn?".to_string(),
+					options: vec!["Sweet".to_string(), "Salty".to_string()],
+				},
+			},
+		)
+		.await
+		.expect("Failed to create poll");
+		let (poll_id, _, _) = poll_state(&alice_test_client.receive_broadcast_message().await);
+
+		time_source.wait_for_time_request().await;
+		time_source.advance_time(std::time::Duration::from_secs(60));
+
+		let (closed_poll_id, _, closed) = poll_state(&alice_test_client.receive_broadcast_message().await);
+		assert_eq!(poll_id, closed_poll_id);
+		assert!(closed);
+	}
+
+	#[tokio::test]
+	async fn poll_deadlines_should_be_in_reference_time() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let reference_time_before = room.reference_time_milliseconds();
+
+		handle_poll_request(
+			&test_application_context().await,
+			&room,
+			&alice,
+			CreatePollRequest {
+				duration_in_milliseconds: uint!(60_000),
+				poll: PollRequest::Custom {
+					question: "Popcorn?".to_string(),
+					options: vec!["Sweet".to_string(), "Salty".to_string()],
+				},
+			},
+		)
+		.await
+		.expect("Failed to create poll");
+
+		let BroadcastMessage::Poll(PollBroadcast {
+			deadline_in_milliseconds,
+			..
+		}) = alice_test_client.receive_broadcast_message().await
+		else {
+			panic!("Expected poll broadcast");
+		};
+		let reference_time_after = room.reference_time_milliseconds();
+		assert!(deadline_in_milliseconds >= reference_time_before + uint!(60_000));
+		assert!(deadline_in_milliseconds <= reference_time_after + uint!(60_000));
+	}
+
+	#[tokio::test]
+	async fn polls_that_were_open_before_a_restart_should_be_closed_at_their_deadline() {
+		let room = room(ReferenceTimer::default(), 1).await;
+		let (alice, mut alice_test_client) = WebsocketTestClient::in_room("Alice", &room).await;
+		let new_poll = |deadline: Duration| NewPoll {
+			kind: PollKind::Custom,
+			question: "Popcorn?".to_string(),
+			options: vec!["Sweet".to_string(), "Salty".to_string()],
+			medium_version: None,
+			deadline: (*room.reference_date_time() + deadline).into(),
+		};
+		let overdue_poll = room
+			.create_poll(&alice, new_poll(-Duration::minutes(1)))
+			.await
+			.expect("Failed to create poll");
+		let pending_poll = room
+			.create_poll(&alice, new_poll(Duration::minutes(1)))
+			.await
+			.expect("Failed to create poll");
+
+		close_open_polls_at_their_deadline(&TimeSource::test(), &room)
+			.await
+			.expect("Failed to close open polls");
+
+		let (closed_poll_id, _, closed) = poll_state(&alice_test_client.receive_broadcast_message().await);
+		assert_eq!(overdue_poll.poll.uuid.to_string(), closed_poll_id);
+		assert!(closed);
+		let open_polls = room.open_polls().await.expect("Failed to get open polls");
+		assert_eq!(vec![pending_poll.poll], open_polls);
+	}
+
 	#[tokio::test]
 	async fn should_send_heartbeats_with_test_time_source() {
 		let room = room(ReferenceTimer::default(), 1).await;
@@ -1135,14 +5176,19 @@ mod test {
 		message_receiver: MessageReceiver,
 		mut test_client: WebsocketTestClient,
 	) -> (Client, MessageReceiver, WebsocketTestClient) {
-		let register_request = RegisterRequest { name: name.into() };
+		let register_request = RegisterRequest {
+			name: name.into(),
+			accepts_private_messages: true,
+			moderator_token: None,
+		};
 
 		let request_id = test_client.send_request(register_request).await;
 
 		// run server code required for client registration
-		let (client, message_receiver) = register_client(room.clone(), message_sender, message_receiver)
-			.await
-			.unwrap();
+		let (client, message_receiver) =
+			register_client(&Configuration::test(), room.clone(), message_sender, message_receiver)
+				.await
+				.unwrap();
 
 		let response = test_client.receive_success_message(request_id).await;
 
@@ -1153,13 +5199,58 @@ mod test {
 
 		let joined_response = client.wait_for_broadcast().await;
 		assert!(matches!(
-			joined_response,
-			BroadcastMessage::ClientJoined(ClientJoinedBroadcast { id: _, name: _ })
+			joined_response.message(),
+			BroadcastMessage::ClientJoined(ClientJoinedBroadcast { .. })
 		));
 		(client, message_receiver, test_client)
 	}
 
+	fn chat_id(broadcast: &BroadcastMessage) -> String {
+		let BroadcastMessage::Chat(ChatBroadcast { id, .. }) = broadcast else {
+			panic!("Expected chat broadcast, got {broadcast:?}");
+		};
+		id.clone()
+	}
+
+	/// Returns the id, the votes per option and whether the poll is closed.
+	fn poll_state(broadcast: &BroadcastMessage) -> (String, Vec<UInt>, bool) {
+		let BroadcastMessage::Poll(PollBroadcast {
+			id, options, closed, ..
+		}) = broadcast
+		else {
+			panic!("Expected poll broadcast, got {broadcast:?}");
+		};
+		(id.clone(), options.iter().map(|option| option.votes).collect(), *closed)
+	}
+
+	async fn handle_poll_request(
+		context: &ApplicationContext,
+		room: &Room,
+		client: &Client,
+		request: impl Into<ClientRequest>,
+	) -> Result<SuccessMessage, ErrorMessage> {
+		handle_request(context, room, client, request.into(), uint!(0)).await
+	}
+
+	async fn test_application_context() -> ApplicationContext {
+		application_context(Configuration::test(), TimeSource::default()).await
+	}
+
+	async fn application_context(configuration: Configuration, time_source: TimeSource) -> ApplicationContext {
+		ApplicationContext::new(configuration, time_source)
+			.await
+			.expect("Failed to create application context")
+	}
+
 	async fn room(reference_timer: ReferenceTimer, room_size_limit: usize) -> Room {
+		room_with_pause_while_buffering(reference_timer, room_size_limit, false).await
+	}
+
+	async fn room_with_pause_while_buffering(
+		reference_timer: ReferenceTimer,
+		room_size_limit: usize,
+		pause_while_buffering: bool,
+	) -> Room {
 		let database = DefaultTestFactory::database().await;
 		let test_room = DefaultTestFactory::repository()
 			.room()
@@ -1175,7 +5266,11 @@ mod test {
 		Room::new(
 			test_room.uuid,
 			reference_timer,
-			room_size_limit,
+			&Configuration {
+				room_size_limit,
+				pause_while_buffering,
+				..Configuration::test()
+			},
 			database,
 			user_service,
 			repository,
@@ -2,6 +2,7 @@ use crate::commandline::Commandline;
 use crate::error::CommunityviError;
 use clap::Parser;
 
+mod bookmark;
 mod chat;
 mod commandline;
 mod configuration;
@@ -10,7 +11,9 @@ mod context;
 mod database;
 mod error;
 mod lifecycle;
+mod media_directory;
 mod message;
+mod poll;
 mod reference_time;
 mod room;
 mod server;
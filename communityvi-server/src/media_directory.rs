@@ -0,0 +1,209 @@
+use crate::room::medium::fixed_length::FixedLengthMedium;
+use chrono::{DateTime, Duration, Utc};
+use mime::Mime;
+use mime_guess::MimeGuess;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSourceStream, MediaSourceStreamOptions};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tracing::{debug, info};
+
+/// Index of the media files in the configured media directory, which the server hosts itself.
+/// The directory is only indexed once, changes to it require a restart.
+#[derive(Clone, Debug, Default)]
+pub struct MediaDirectory {
+	media_by_id: Arc<BTreeMap<String, HostedMedium>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostedMedium {
+	pub id: String,
+	/// Path relative to the media directory, separated by '/'.
+	pub name: String,
+	pub path: PathBuf,
+	pub length: Duration,
+	pub size_in_bytes: u64,
+	pub mime_type: Mime,
+	pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl MediaDirectory {
+	/// Recursively index all media files in `directory`, skipping files whose length can't be determined.
+	pub fn index(directory: impl AsRef<Path>) -> io::Result<Self> {
+		let directory = directory.as_ref();
+		let mut media_by_id = BTreeMap::new();
+		index_directory(directory, directory, &mut media_by_id)?;
+		info!("Indexed {} media in '{}'.", media_by_id.len(), directory.display());
+
+		Ok(Self {
+			media_by_id: Arc::new(media_by_id),
+		})
+	}
+
+	pub fn media(&self) -> impl Iterator<Item = &HostedMedium> {
+		self.media_by_id.values()
+	}
+
+	pub fn medium(&self, id: &str) -> Option<&HostedMedium> {
+		self.media_by_id.get(id)
+	}
+}
+
+impl HostedMedium {
+	/// Derived from the file's metadata because hashing large media files on every start would be too slow.
+	pub fn etag(&self) -> String {
+		let last_modified = self
+			.last_modified
+			.map(|last_modified| last_modified.timestamp_millis())
+			.unwrap_or_default();
+		format!(r#""{}-{:x}-{last_modified:x}""#, self.id, self.size_in_bytes)
+	}
+}
+
+impl From<&HostedMedium> for FixedLengthMedium {
+	fn from(hosted_medium: &HostedMedium) -> Self {
+		FixedLengthMedium::new(hosted_medium.name.clone(), hosted_medium.length)
+			.with_hosted_id(hosted_medium.id.clone())
+	}
+}
+
+fn index_directory(root: &Path, directory: &Path, media_by_id: &mut BTreeMap<String, HostedMedium>) -> io::Result<()> {
+	for entry in std::fs::read_dir(directory)? {
+		let entry = entry?;
+		// Symlinks are not followed so nothing outside of the media directory is ever served.
+		let file_type = entry.file_type()?;
+		let path = entry.path();
+		if entry.file_name().to_string_lossy().starts_with('.') {
+			continue;
+		}
+
+		if file_type.is_dir() {
+			index_directory(root, &path, media_by_id)?;
+		} else if file_type.is_file() {
+			match index_file(root, path.clone()) {
+				Ok(Some(medium)) => {
+					media_by_id.insert(medium.id.clone(), medium);
+				}
+				Ok(None) => debug!("Skipping '{}', its length is unknown.", path.display()),
+				Err(error) => debug!("Skipping '{}', it isn't a supported medium: {error}", path.display()),
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn index_file(root: &Path, path: PathBuf) -> Result<Option<HostedMedium>, symphonia::core::errors::Error> {
+	let name = path
+		.strip_prefix(root)
+		.unwrap_or(&path)
+		.components()
+		.map(|component| component.as_os_str().to_string_lossy())
+		.collect::<Vec<_>>()
+		.join("/");
+	let metadata = std::fs::metadata(&path)?;
+	let Some(length) = probe_length(&path)? else {
+		return Ok(None);
+	};
+
+	Ok(Some(HostedMedium {
+		id: medium_id(&name),
+		mime_type: MimeGuess::from_path(&path).first_or_octet_stream(),
+		name,
+		path,
+		length,
+		size_in_bytes: metadata.len(),
+		last_modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+	}))
+}
+
+/// Length of the longest track in the file.
+fn probe_length(path: &Path) -> Result<Option<Duration>, symphonia::core::errors::Error> {
+	let mut hint = Hint::new();
+	if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+		hint.with_extension(extension);
+	}
+	let source = MediaSourceStream::new(Box::new(File::open(path)?), MediaSourceStreamOptions::default());
+	let probed = symphonia::default::get_probe().format(
+		&hint,
+		source,
+		&FormatOptions::default(),
+		&MetadataOptions::default(),
+	)?;
+
+	Ok(probed
+		.format
+		.tracks()
+		.iter()
+		.filter_map(|track| {
+			let time = track.codec_params.time_base?.calc_time(track.codec_params.n_frames?);
+			#[allow(clippy::cast_possible_truncation)]
+			let milliseconds = (time.frac * 1000.0).round() as i64;
+			Some(Duration::seconds(i64::try_from(time.seconds).ok()?) + Duration::milliseconds(milliseconds))
+		})
+		.max())
+}
+
+/// Stable across restarts as long as the file isn't moved.
+fn medium_id(name: &str) -> String {
+	let hash = Sha256::digest(name.as_bytes());
+	hex::encode(&hash[..16])
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const TEST_DIRECTORY: &str = "test/media";
+
+	#[test]
+	fn should_index_media_recursively() {
+		let media_directory = MediaDirectory::index(TEST_DIRECTORY).expect("Failed to index media directory");
+
+		let mut names = media_directory
+			.media()
+			.map(|medium| medium.name.as_str())
+			.collect::<Vec<_>>();
+		names.sort_unstable();
+
+		assert_eq!(vec!["music/half-a-second.wav", "silence.wav"], names);
+	}
+
+	#[test]
+	fn should_determine_length_size_and_type() {
+		let media_directory = MediaDirectory::index(TEST_DIRECTORY).expect("Failed to index media directory");
+
+		let medium = media_directory
+			.medium(&medium_id("silence.wav"))
+			.expect("Failed to find indexed medium");
+
+		assert_eq!(Duration::milliseconds(250), medium.length);
+		assert_eq!(2044, medium.size_in_bytes);
+		assert_eq!("audio/wav", medium.mime_type.essence_str());
+	}
+
+	#[test]
+	fn should_convert_to_fixed_length_medium() {
+		let media_directory = MediaDirectory::index(TEST_DIRECTORY).expect("Failed to index media directory");
+		let hosted_medium = media_directory
+			.medium(&medium_id("music/half-a-second.wav"))
+			.expect("Failed to find indexed medium");
+
+		let medium = FixedLengthMedium::from(hosted_medium);
+
+		assert_eq!("music/half-a-second.wav", medium.name);
+		assert_eq!(Duration::milliseconds(500), medium.length);
+		assert_eq!(Some(hosted_medium.id.as_str()), medium.hosted_id.as_deref());
+	}
+
+	#[test]
+	fn should_fail_to_index_nonexistent_directory() {
+		assert!(MediaDirectory::index("test/nonexistent").is_err());
+	}
+}
@@ -1,12 +1,22 @@
 use serde::{Deserialize, Serialize};
 
+use crate::media_directory::MediaDirectory;
+use crate::message::format::MessageFormat;
 use crate::message::outgoing::error_message::{ErrorMessage, ErrorMessageType};
 use crate::message::{MessageError, WebSocketMessage};
+use crate::room::client::Readiness;
 use crate::room::medium::Medium;
+use crate::room::medium::fingerprint::Fingerprint;
 use crate::room::medium::fixed_length::FixedLengthMedium;
+use crate::room::medium::live::LiveMedium;
+use crate::room::medium::url::UrlMedium;
+use crate::room::session_id::SessionId;
+use crate::user::model::{Avatar, Presence, Profile};
 use chrono::Duration;
 use js_int::{Int, UInt};
+use mime::Mime;
 use tracing::error;
+use url::Url;
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct ClientRequestWithId {
@@ -20,10 +30,33 @@ pub struct ClientRequestWithId {
 #[serde(rename_all = "snake_case")]
 pub enum ClientRequest {
 	Register(RegisterRequest),
+	ChangeName(ChangeNameRequest),
+	UpdateProfile(UpdateProfileRequest),
 	Chat(ChatRequest),
+	EditChat(EditChatRequest),
+	DeleteChat(DeleteChatRequest),
+	PrivateMessage(PrivateMessageRequest),
 	InsertMedium(InsertMediumRequest),
 	Play(PlayRequest),
 	Pause(PauseRequest),
+	TimeSync(TimeSyncRequest),
+	PositionReport(PositionReportRequest),
+	Readiness(ReadinessRequest),
+	SchedulePlay(SchedulePlayRequest),
+	CancelScheduledPlay(CancelScheduledPlayRequest),
+	FingerprintReport(FingerprintReportRequest),
+	AddSubtitleTrack(AddSubtitleTrackRequest),
+	SubtitleOffset(SubtitleOffsetRequest),
+	AddBookmark(AddBookmarkRequest),
+	RemoveBookmark(RemoveBookmarkRequest),
+	ListBookmarks(ListBookmarksRequest),
+	JumpToBookmark(JumpToBookmarkRequest),
+	Reaction(ReactionRequest),
+	ReactionsForMedium(ReactionsForMediumRequest),
+	Typing(TypingRequest),
+	CreatePoll(CreatePollRequest),
+	Vote(VoteRequest),
+	ClosePoll(ClosePollRequest),
 }
 
 impl ClientRequest {
@@ -31,10 +64,33 @@ impl ClientRequest {
 		use ClientRequest::*;
 		match self {
 			Register(_) => "Register",
+			ChangeName(_) => "ChangeName",
+			UpdateProfile(_) => "UpdateProfile",
 			Chat(_) => "Chat",
+			EditChat(_) => "EditChat",
+			DeleteChat(_) => "DeleteChat",
+			PrivateMessage(_) => "PrivateMessage",
 			InsertMedium(_) => "InsertMedium",
 			Play(_) => "Play",
 			Pause(_) => "Pause",
+			TimeSync(_) => "TimeSync",
+			PositionReport(_) => "PositionReport",
+			Readiness(_) => "Readiness",
+			SchedulePlay(_) => "SchedulePlay",
+			CancelScheduledPlay(_) => "CancelScheduledPlay",
+			FingerprintReport(_) => "FingerprintReport",
+			AddSubtitleTrack(_) => "AddSubtitleTrack",
+			SubtitleOffset(_) => "SubtitleOffset",
+			AddBookmark(_) => "AddBookmark",
+			RemoveBookmark(_) => "RemoveBookmark",
+			ListBookmarks(_) => "ListBookmarks",
+			JumpToBookmark(_) => "JumpToBookmark",
+			Reaction(_) => "Reaction",
+			ReactionsForMedium(_) => "ReactionsForMedium",
+			Typing(_) => "Typing",
+			CreatePoll(_) => "CreatePoll",
+			Vote(_) => "Vote",
+			ClosePoll(_) => "ClosePoll",
 		}
 	}
 }
@@ -72,17 +128,100 @@ macro_rules! client_request_from_struct {
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct RegisterRequest {
 	pub name: String,
+	#[serde(default = "accepts_private_messages_by_default")]
+	pub accepts_private_messages: bool,
+	/// Required for the names of moderators, see `moderators` in the configuration.
+	#[serde(default)]
+	pub moderator_token: Option<String>,
+}
+
+fn accepts_private_messages_by_default() -> bool {
+	true
 }
 
 client_request_from_struct!(Register, RegisterRequest);
 
+/// The new name is validated like the name in [`RegisterRequest`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ChangeNameRequest {
+	pub name: String,
+}
+
+client_request_from_struct!(ChangeName, ChangeNameRequest);
+
+/// Replaces the whole profile, attributes that are left out are removed.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct UpdateProfileRequest {
+	#[serde(flatten)]
+	pub profile: ProfileRequest,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ProfileRequest {
+	#[serde(default)]
+	pub presence: Presence,
+	/// Hex color like `#1a2b3c`.
+	pub color: Option<String>,
+	pub avatar: Option<Avatar>,
+	pub pronouns: Option<String>,
+}
+
+impl From<ProfileRequest> for Profile {
+	fn from(
+		ProfileRequest {
+			presence,
+			color,
+			avatar,
+			pronouns,
+		}: ProfileRequest,
+	) -> Self {
+		Self {
+			presence,
+			color,
+			avatar,
+			pronouns,
+		}
+	}
+}
+
+client_request_from_struct!(UpdateProfile, UpdateProfileRequest);
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct ChatRequest {
 	pub message: String,
+	/// Id of the chat message this one replies to.
+	#[serde(default)]
+	pub reply_to: Option<String>,
 }
 
 client_request_from_struct!(Chat, ChatRequest);
 
+/// Replace the text of a chat message, only allowed for its author and moderators.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct EditChatRequest {
+	pub id: String,
+	pub message: String,
+}
+
+client_request_from_struct!(EditChat, EditChatRequest);
+
+/// Delete a chat message, only allowed for its author and moderators.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct DeleteChatRequest {
+	pub id: String,
+}
+
+client_request_from_struct!(DeleteChat, DeleteChatRequest);
+
+/// Send a message that only the recipient (and the sender) receives.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PrivateMessageRequest {
+	pub recipient_id: SessionId,
+	pub message: String,
+}
+
+client_request_from_struct!(PrivateMessage, PrivateMessageRequest);
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct InsertMediumRequest {
 	pub previous_version: UInt,
@@ -93,43 +232,161 @@ pub struct InsertMediumRequest {
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub enum MediumRequest {
-	FixedLength { name: String, length_in_milliseconds: UInt },
+	FixedLength {
+		name: String,
+		length_in_milliseconds: UInt,
+		fingerprint: Option<FingerprintRequest>,
+	},
+	Url {
+		url: String,
+		mime_type: Option<String>,
+		length_in_milliseconds: Option<UInt>,
+	},
+	Live {
+		name: String,
+	},
+	/// A medium from the server's media directory, see `/api/media`.
+	Hosted {
+		id: String,
+	},
 	Empty,
 }
 
-client_request_from_struct!(InsertMedium, InsertMediumRequest);
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct FingerprintRequest {
+	pub size_in_bytes: UInt,
+	pub hash: String,
+}
+
+/// Longer than the hex encoding of any common hash function.
+const FINGERPRINT_HASH_LENGTH_LIMIT: usize = 128;
 
-impl TryFrom<MediumRequest> for Medium {
+impl TryFrom<FingerprintRequest> for Fingerprint {
 	type Error = ErrorMessage;
 
-	fn try_from(request: MediumRequest) -> Result<Self, Self::Error> {
-		match request {
+	fn try_from(FingerprintRequest { size_in_bytes, hash }: FingerprintRequest) -> Result<Self, Self::Error> {
+		if hash.is_empty() || hash.len() > FINGERPRINT_HASH_LENGTH_LIMIT {
+			return Err(ErrorMessage::builder()
+				.error(ErrorMessageType::InvalidFormat)
+				.message(format!(
+					"Fingerprint hash must be between 1 and {FINGERPRINT_HASH_LENGTH_LIMIT} bytes long."
+				))
+				.build());
+		}
+
+		Ok(Fingerprint {
+			size_in_bytes: size_in_bytes.into(),
+			hash,
+		})
+	}
+}
+
+impl From<Fingerprint> for FingerprintRequest {
+	fn from(Fingerprint { size_in_bytes, hash }: Fingerprint) -> Self {
+		Self {
+			size_in_bytes: UInt::try_from(size_in_bytes).unwrap_or(UInt::MAX),
+			hash,
+		}
+	}
+}
+
+client_request_from_struct!(InsertMedium, InsertMediumRequest);
+
+impl MediumRequest {
+	/// Hosted media are resolved through the `media_directory`, all other media are validated on their own.
+	pub fn into_medium(self, media_directory: &MediaDirectory) -> Result<Medium, ErrorMessage> {
+		match self {
 			MediumRequest::FixedLength {
 				name,
 				length_in_milliseconds,
+				fingerprint,
 			} => {
-				if length_in_milliseconds > (UInt::try_from(Duration::days(365).num_milliseconds()).unwrap()) {
-					Err(ErrorMessage::builder()
+				let medium = FixedLengthMedium::new(name, medium_length(length_in_milliseconds)?);
+				Ok(match fingerprint {
+					Some(fingerprint) => medium.with_fingerprint(fingerprint.try_into()?),
+					None => medium,
+				}
+				.into())
+			}
+			MediumRequest::Url {
+				url,
+				mime_type,
+				length_in_milliseconds,
+			} => {
+				let url = Url::parse(&url).map_err(|error| {
+					ErrorMessage::builder()
 						.error(ErrorMessageType::InvalidFormat)
-						.message("Length of a medium must not be larger than one year.".to_string())
-						.build())
-				} else {
-					Ok(FixedLengthMedium::new(name, Duration::milliseconds(i64::from(length_in_milliseconds))).into())
+						.message(format!("Invalid URL: {error}"))
+						.build()
+				})?;
+				if !MEDIUM_URL_SCHEMES.contains(&url.scheme()) {
+					return Err(ErrorMessage::builder()
+						.error(ErrorMessageType::InvalidFormat)
+						.message(format!("URL scheme must be one of {MEDIUM_URL_SCHEMES:?}."))
+						.build());
 				}
+
+				let mime_type = mime_type
+					.map(|mime_type| mime_type.parse::<Mime>())
+					.transpose()
+					.map_err(|error| {
+						ErrorMessage::builder()
+							.error(ErrorMessageType::InvalidFormat)
+							.message(format!("Invalid MIME type: {error}"))
+							.build()
+					})?;
+				let length = length_in_milliseconds.map(medium_length).transpose()?;
+
+				Ok(UrlMedium::new(url, mime_type, length).into())
 			}
+			MediumRequest::Live { name } => Ok(LiveMedium::new(name).into()),
+			MediumRequest::Hosted { id } => media_directory
+				.medium(&id)
+				.map(|hosted_medium| FixedLengthMedium::from(hosted_medium).into())
+				.ok_or_else(|| {
+					ErrorMessage::builder()
+						.error(ErrorMessageType::InvalidOperation)
+						.message(format!("There is no hosted medium with id '{id}'."))
+						.build()
+				}),
 			MediumRequest::Empty => Ok(Medium::Empty),
 		}
 	}
 }
 
+const MEDIUM_URL_SCHEMES: [&str; 2] = ["https", "http"];
+
+fn medium_length(length_in_milliseconds: UInt) -> Result<Duration, ErrorMessage> {
+	if length_in_milliseconds > (UInt::try_from(Duration::days(365).num_milliseconds()).unwrap()) {
+		return Err(ErrorMessage::builder()
+			.error(ErrorMessageType::InvalidFormat)
+			.message("Length of a medium must not be larger than one year.".to_string())
+			.build());
+	}
+
+	Ok(Duration::milliseconds(i64::from(length_in_milliseconds)))
+}
+
 impl From<Medium> for MediumRequest {
 	fn from(medium: Medium) -> Self {
 		match medium {
 			Medium::Empty => MediumRequest::Empty,
+			Medium::FixedLength(FixedLengthMedium {
+				hosted_id: Some(id), ..
+			}) => MediumRequest::Hosted { id },
 			Medium::FixedLength(fixed_length) => MediumRequest::FixedLength {
 				name: fixed_length.name,
 				length_in_milliseconds: UInt::try_from(fixed_length.length.num_milliseconds()).unwrap(),
+				fingerprint: fixed_length.fingerprint.map(Into::into),
+			},
+			Medium::Url(url_medium) => MediumRequest::Url {
+				url: url_medium.url.into(),
+				mime_type: url_medium.mime_type.map(|mime_type| mime_type.to_string()),
+				length_in_milliseconds: url_medium
+					.length
+					.map(|length| UInt::try_from(length.num_milliseconds()).unwrap()),
 			},
+			Medium::Live(live_medium) => MediumRequest::Live { name: live_medium.name },
 		}
 	}
 }
@@ -152,10 +409,181 @@ pub struct PauseRequest {
 
 client_request_from_struct!(Pause, PauseRequest);
 
+/// NTP-style time synchronization. The originate timestamp is the client's local time when sending
+/// the request, it is returned unchanged together with the server's receive and transmit times.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TimeSyncRequest {
+	pub originate_timestamp_in_milliseconds: UInt,
+}
+
+client_request_from_struct!(TimeSync, TimeSyncRequest);
+
+/// Periodic report of the client's playback position, compared with the position expected
+/// at the reference time the server received the report.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PositionReportRequest {
+	pub medium_version: UInt,
+	pub position_in_milliseconds: UInt,
+}
+
+client_request_from_struct!(PositionReport, PositionReportRequest);
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ReadinessRequest {
+	pub readiness: Readiness,
+}
+
+client_request_from_struct!(Readiness, ReadinessRequest);
+
+/// Start playing the medium for everyone at a reference time in the future, e.g. to show a countdown.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SchedulePlayRequest {
+	pub previous_version: UInt,
+	pub scheduled_time_in_milliseconds: UInt,
+}
+
+client_request_from_struct!(SchedulePlay, SchedulePlayRequest);
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CancelScheduledPlayRequest {}
+
+client_request_from_struct!(CancelScheduledPlay, CancelScheduledPlayRequest);
+
+/// Fingerprint of the file a client has loaded for the medium with `medium_version`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct FingerprintReportRequest {
+	pub medium_version: UInt,
+	pub fingerprint: FingerprintRequest,
+}
+
+client_request_from_struct!(FingerprintReport, FingerprintReportRequest);
+
+/// Add subtitles to the medium with `medium_version`. They are uploaded to `/api/subtitles` first because
+/// subtitle files are usually larger than the websocket message size limit.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AddSubtitleTrackRequest {
+	pub medium_version: UInt,
+	/// As returned by the upload.
+	pub track_id: String,
+	pub label: String,
+	pub language: Option<String>,
+}
+
+client_request_from_struct!(AddSubtitleTrack, AddSubtitleTrackRequest);
+
+/// Shift all subtitles of the medium with `medium_version`, positive values show them later.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SubtitleOffsetRequest {
+	pub medium_version: UInt,
+	pub offset_in_milliseconds: Int,
+}
+
+client_request_from_struct!(SubtitleOffset, SubtitleOffsetRequest);
+
+/// Bookmark a position in the medium with `medium_version`. Bookmarks belong to the content, so they
+/// are still there when the same medium is inserted again.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AddBookmarkRequest {
+	pub medium_version: UInt,
+	pub position_in_milliseconds: UInt,
+	pub label: String,
+}
+
+client_request_from_struct!(AddBookmark, AddBookmarkRequest);
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct RemoveBookmarkRequest {
+	pub id: String,
+}
+
+client_request_from_struct!(RemoveBookmark, RemoveBookmarkRequest);
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ListBookmarksRequest {}
+
+client_request_from_struct!(ListBookmarks, ListBookmarksRequest);
+
+/// Move the current medium to a bookmark for everyone, keeping it playing or paused.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct JumpToBookmarkRequest {
+	pub previous_version: UInt,
+	pub id: String,
+}
+
+client_request_from_struct!(JumpToBookmark, JumpToBookmarkRequest);
+
+/// A single emoji, anchored to the current position of the medium like chat messages.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ReactionRequest {
+	pub emoji: String,
+}
+
+client_request_from_struct!(Reaction, ReactionRequest);
+
+/// Chat messages and reactions of the current medium, e.g. to replay them when watching it again.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ReactionsForMediumRequest {}
+
+client_request_from_struct!(ReactionsForMedium, ReactionsForMediumRequest);
+
+/// Whether the client is currently typing a chat message. Typing expires unless it is reported again
+/// within 5 seconds.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TypingRequest {
+	pub typing: bool,
+}
+
+client_request_from_struct!(Typing, TypingRequest);
+
+/// Ask everyone in the room, the poll is closed automatically after `duration_in_milliseconds`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CreatePollRequest {
+	pub duration_in_milliseconds: UInt,
+	pub poll: PollRequest,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum PollRequest {
+	Custom {
+		question: String,
+		options: Vec<String>,
+	},
+	/// Vote on ejecting the medium with `medium_version`, which happens as soon as more than half of the room
+	/// voted "Yes".
+	Skip {
+		medium_version: UInt,
+	},
+	/// Vote on pausing the medium with `medium_version`, which happens as soon as more than half of the room
+	/// voted "Yes".
+	Pause {
+		medium_version: UInt,
+	},
+}
+
+client_request_from_struct!(CreatePoll, CreatePollRequest);
+
+/// Vote for the option with index `option` of an open poll, voting again changes the vote.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct VoteRequest {
+	pub poll_id: String,
+	pub option: UInt,
+}
+
+client_request_from_struct!(Vote, VoteRequest);
+
+/// Close a poll before its deadline, only allowed for its creator and moderators.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ClosePollRequest {
+	pub poll_id: String,
+}
+
+client_request_from_struct!(ClosePoll, ClosePollRequest);
+
 impl From<&ClientRequestWithId> for WebSocketMessage {
 	fn from(request: &ClientRequestWithId) -> Self {
-		let json = serde_json::to_string(request).expect("Failed to serialize request to JSON.");
-		WebSocketMessage::text(json)
+		MessageFormat::Json.encode(request)
 	}
 }
 
@@ -163,15 +591,7 @@ impl TryFrom<&WebSocketMessage> for ClientRequestWithId {
 	type Error = MessageError;
 
 	fn try_from(websocket_message: &WebSocketMessage) -> Result<Self, Self::Error> {
-		match websocket_message {
-			WebSocketMessage::Text(json) => {
-				serde_json::from_str(json).map_err(|error| MessageError::DeserializationFailed {
-					error: error.to_string(),
-					json: json.to_string(),
-				})
-			}
-			_ => Err(MessageError::WrongMessageType(websocket_message.clone())),
-		}
+		MessageFormat::Json.decode(websocket_message)
 	}
 }
 
@@ -184,15 +604,14 @@ impl TryFrom<&WebSocketMessage> for RequestIdOnly {
 	type Error = ();
 
 	fn try_from(websocket_message: &WebSocketMessage) -> Result<Self, Self::Error> {
-		match websocket_message {
-			WebSocketMessage::Text(json) => serde_json::from_str(json)
-				.map_err(|error| error!("Error while deserializing websocket message from JSON: {error:?}")),
-			_ => Err(()),
-		}
+		MessageFormat::Json
+			.decode(websocket_message)
+			.map_err(|error| error!("Error while deserializing websocket message: {error}"))
 	}
 }
 
 #[cfg(test)]
+#[allow(clippy::non_ascii_literal)]
 mod test {
 	use super::*;
 	use js_int::{int, uint};
@@ -201,36 +620,84 @@ mod test {
 	fn chat_request_should_serialize_and_deserialize() {
 		let chat_request = ClientRequest::Chat(ChatRequest {
 			message: "hello".into(),
+			reply_to: None,
 		})
 		.with_id(uint!(42));
 		let json = serde_json::to_string(&chat_request).expect("Failed to serialize Chat request to JSON");
-		assert_eq!(r#"{"request_id":42,"type":"chat","message":"hello"}"#, json);
+		assert_eq!(
+			r#"{"request_id":42,"type":"chat","message":"hello","reply_to":null}"#,
+			json
+		);
 
 		let deserialized_chat_request: ClientRequestWithId =
 			serde_json::from_str(&json).expect("Failed to deserialize Chat request from JSON");
 		assert_eq!(chat_request, deserialized_chat_request);
 	}
 
+	#[test]
+	fn chat_request_without_reply_to_should_deserialize() {
+		let json = r#"{"request_id":42,"type":"chat","message":"hello"}"#;
+
+		let chat_request: ClientRequestWithId =
+			serde_json::from_str(json).expect("Failed to deserialize Chat request from JSON");
+
+		assert_eq!(
+			ClientRequest::Chat(ChatRequest {
+				message: "hello".into(),
+				reply_to: None,
+			})
+			.with_id(uint!(42)),
+			chat_request
+		);
+	}
+
 	#[test]
 	fn register_request_should_serialize_and_deserialize() {
 		let register_request = ClientRequest::Register(RegisterRequest {
 			name: "Ferris".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
 		})
 		.with_id(uint!(42));
 		let json = serde_json::to_string(&register_request).expect("Failed to serialize Register request to JSON");
-		assert_eq!(r#"{"request_id":42,"type":"register","name":"Ferris"}"#, json);
+		assert_eq!(
+			r#"{"request_id":42,"type":"register","name":"Ferris","accepts_private_messages":true,"moderator_token":null}"#,
+			json
+		);
 
 		let deserialized_register_request: ClientRequestWithId =
 			serde_json::from_str(&json).expect("Failed to deserialize Register request from JSON");
 		assert_eq!(register_request, deserialized_register_request);
 	}
 
+	#[test]
+	fn register_request_should_accept_private_messages_by_default() {
+		let json = r#"{"request_id":42,"type":"register","name":"Ferris"}"#;
+
+		let register_request: ClientRequestWithId =
+			serde_json::from_str(json).expect("Failed to deserialize Register request from JSON");
+
+		assert_eq!(
+			ClientRequest::Register(RegisterRequest {
+				name: "Ferris".to_string(),
+				accepts_private_messages: true,
+				moderator_token: None,
+			})
+			.with_id(uint!(42)),
+			register_request
+		);
+	}
+
 	#[test]
 	fn insert_medium_request_with_fixed_length_medium_should_serialize_and_deserialize() {
 		let insert_medium_request = ClientRequest::InsertMedium(InsertMediumRequest {
 			medium: MediumRequest::FixedLength {
 				name: "Blues Brothers".to_string(),
 				length_in_milliseconds: uint!(8_520_000),
+				fingerprint: Some(FingerprintRequest {
+					size_in_bytes: UInt::new_wrapping(4_700_000_000),
+					hash: "cafebabe".to_string(),
+				}),
 			},
 			previous_version: uint!(0),
 		})
@@ -238,7 +705,7 @@ mod test {
 		let json =
 			serde_json::to_string(&insert_medium_request).expect("Failed to serialize InsertMedium request to JSON");
 		assert_eq!(
-			r#"{"request_id":42,"type":"insert_medium","previous_version":0,"medium":{"type":"fixed_length","name":"Blues Brothers","length_in_milliseconds":8520000}}"#,
+			r#"{"request_id":42,"type":"insert_medium","previous_version":0,"medium":{"type":"fixed_length","name":"Blues Brothers","length_in_milliseconds":8520000,"fingerprint":{"size_in_bytes":4700000000,"hash":"cafebabe"}}}"#,
 			json
 		);
 
@@ -304,6 +771,472 @@ mod test {
 		assert_eq!(pause_request, deserialized_pause_request);
 	}
 
+	#[test]
+	fn time_sync_request_should_serialize_and_deserialize() {
+		let time_sync_request = ClientRequest::TimeSync(TimeSyncRequest {
+			originate_timestamp_in_milliseconds: uint!(1337),
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&time_sync_request).expect("Failed to serialize TimeSync request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"time_sync","originate_timestamp_in_milliseconds":1337}"#,
+			json
+		);
+
+		let deserialized_time_sync_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize TimeSync request from JSON");
+		assert_eq!(time_sync_request, deserialized_time_sync_request);
+	}
+
+	#[test]
+	fn position_report_request_should_serialize_and_deserialize() {
+		let position_report_request = ClientRequest::PositionReport(PositionReportRequest {
+			medium_version: uint!(3),
+			position_in_milliseconds: uint!(1337),
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&position_report_request)
+			.expect("Failed to serialize PositionReport request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"position_report","medium_version":3,"position_in_milliseconds":1337}"#,
+			json
+		);
+
+		let deserialized_position_report_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize PositionReport request from JSON");
+		assert_eq!(position_report_request, deserialized_position_report_request);
+	}
+
+	#[test]
+	fn readiness_request_should_serialize_and_deserialize() {
+		let readiness_request = ClientRequest::Readiness(ReadinessRequest {
+			readiness: Readiness::Buffering,
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&readiness_request).expect("Failed to serialize Readiness request to JSON");
+		assert_eq!(r#"{"request_id":42,"type":"readiness","readiness":"buffering"}"#, json);
+
+		let deserialized_readiness_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize Readiness request from JSON");
+		assert_eq!(readiness_request, deserialized_readiness_request);
+	}
+
+	#[test]
+	fn schedule_play_request_should_serialize_and_deserialize() {
+		let schedule_play_request = ClientRequest::SchedulePlay(SchedulePlayRequest {
+			previous_version: uint!(3),
+			scheduled_time_in_milliseconds: uint!(72_000_000),
+		})
+		.with_id(uint!(42));
+		let json =
+			serde_json::to_string(&schedule_play_request).expect("Failed to serialize SchedulePlay request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"schedule_play","previous_version":3,"scheduled_time_in_milliseconds":72000000}"#,
+			json
+		);
+
+		let deserialized_schedule_play_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize SchedulePlay request from JSON");
+		assert_eq!(schedule_play_request, deserialized_schedule_play_request);
+	}
+
+	#[test]
+	fn cancel_scheduled_play_request_should_serialize_and_deserialize() {
+		let cancel_request = ClientRequest::CancelScheduledPlay(CancelScheduledPlayRequest {}).with_id(uint!(42));
+		let json =
+			serde_json::to_string(&cancel_request).expect("Failed to serialize CancelScheduledPlay request to JSON");
+		assert_eq!(r#"{"request_id":42,"type":"cancel_scheduled_play"}"#, json);
+
+		let deserialized_cancel_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize CancelScheduledPlay request from JSON");
+		assert_eq!(cancel_request, deserialized_cancel_request);
+	}
+
+	#[test]
+	fn fingerprint_report_request_should_serialize_and_deserialize() {
+		let fingerprint_report_request = ClientRequest::FingerprintReport(FingerprintReportRequest {
+			medium_version: uint!(3),
+			fingerprint: FingerprintRequest {
+				size_in_bytes: uint!(1_234_567_890),
+				hash: "cafebabe".to_string(),
+			},
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&fingerprint_report_request)
+			.expect("Failed to serialize FingerprintReport request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"fingerprint_report","medium_version":3,"fingerprint":{"size_in_bytes":1234567890,"hash":"cafebabe"}}"#,
+			json
+		);
+
+		let deserialized_fingerprint_report_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize FingerprintReport request from JSON");
+		assert_eq!(fingerprint_report_request, deserialized_fingerprint_report_request);
+	}
+
+	#[test]
+	fn should_not_accept_empty_fingerprint_hash() {
+		let fingerprint_request = FingerprintRequest {
+			size_in_bytes: uint!(42),
+			hash: String::new(),
+		};
+
+		let error = Fingerprint::try_from(fingerprint_request).expect_err("Empty hash was accepted");
+
+		assert_eq!(ErrorMessageType::InvalidFormat, error.error);
+	}
+
+	#[test]
+	fn insert_medium_request_with_url_medium_should_serialize_and_deserialize() {
+		let insert_medium_request = ClientRequest::InsertMedium(InsertMediumRequest {
+			previous_version: uint!(0),
+			medium: MediumRequest::Url {
+				url: "https://example.com/blues-brothers.mp4".to_string(),
+				mime_type: Some("video/mp4".to_string()),
+				length_in_milliseconds: Some(uint!(8_520_000)),
+			},
+		})
+		.with_id(uint!(42));
+		let json =
+			serde_json::to_string(&insert_medium_request).expect("Failed to serialize InsertMedium request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"insert_medium","previous_version":0,"medium":{"type":"url","url":"https://example.com/blues-brothers.mp4","mime_type":"video/mp4","length_in_milliseconds":8520000}}"#,
+			json
+		);
+
+		let deserialized_insert_medium_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize InsertMedium request from JSON");
+		assert_eq!(insert_medium_request, deserialized_insert_medium_request);
+	}
+
+	#[test]
+	fn insert_medium_request_with_live_medium_should_serialize_and_deserialize() {
+		let insert_medium_request = ClientRequest::InsertMedium(InsertMediumRequest {
+			previous_version: uint!(3),
+			medium: MediumRequest::Live {
+				name: "Rocket Launch".to_string(),
+			},
+		})
+		.with_id(uint!(42));
+		let json =
+			serde_json::to_string(&insert_medium_request).expect("Failed to serialize InsertMedium request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"insert_medium","previous_version":3,"medium":{"type":"live","name":"Rocket Launch"}}"#,
+			json
+		);
+
+		let deserialized_insert_medium_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize InsertMedium request from JSON");
+		assert_eq!(insert_medium_request, deserialized_insert_medium_request);
+	}
+
+	#[test]
+	fn insert_medium_request_with_hosted_medium_should_serialize_and_deserialize() {
+		let insert_medium_request = ClientRequest::InsertMedium(InsertMediumRequest {
+			previous_version: uint!(0),
+			medium: MediumRequest::Hosted {
+				id: "2cf24dba5fb0a30e26e83b2ac5b9e29e".to_string(),
+			},
+		})
+		.with_id(uint!(42));
+		let json =
+			serde_json::to_string(&insert_medium_request).expect("Failed to serialize InsertMedium request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"insert_medium","previous_version":0,"medium":{"type":"hosted","id":"2cf24dba5fb0a30e26e83b2ac5b9e29e"}}"#,
+			json
+		);
+
+		let deserialized_insert_medium_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize InsertMedium request from JSON");
+		assert_eq!(insert_medium_request, deserialized_insert_medium_request);
+	}
+
+	#[test]
+	fn add_subtitle_track_request_should_serialize_and_deserialize() {
+		let add_subtitle_track_request = ClientRequest::AddSubtitleTrack(AddSubtitleTrackRequest {
+			medium_version: uint!(1),
+			track_id: "5d2f0b6c3e8a4f1d9b7c6a5e4d3c2b1a".to_string(),
+			label: "Deutsch".to_string(),
+			language: Some("de".to_string()),
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&add_subtitle_track_request)
+			.expect("Failed to serialize AddSubtitleTrack request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"add_subtitle_track","medium_version":1,"track_id":"5d2f0b6c3e8a4f1d9b7c6a5e4d3c2b1a","label":"Deutsch","language":"de"}"#,
+			json
+		);
+
+		let deserialized_add_subtitle_track_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize AddSubtitleTrack request from JSON");
+		assert_eq!(add_subtitle_track_request, deserialized_add_subtitle_track_request);
+	}
+
+	#[test]
+	fn subtitle_offset_request_should_serialize_and_deserialize() {
+		let subtitle_offset_request = ClientRequest::SubtitleOffset(SubtitleOffsetRequest {
+			medium_version: uint!(2),
+			offset_in_milliseconds: int!(-1500),
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&subtitle_offset_request)
+			.expect("Failed to serialize SubtitleOffset request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"subtitle_offset","medium_version":2,"offset_in_milliseconds":-1500}"#,
+			json
+		);
+
+		let deserialized_subtitle_offset_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize SubtitleOffset request from JSON");
+		assert_eq!(subtitle_offset_request, deserialized_subtitle_offset_request);
+	}
+
+	#[test]
+	fn add_bookmark_request_should_serialize_and_deserialize() {
+		let add_bookmark_request = ClientRequest::AddBookmark(AddBookmarkRequest {
+			medium_version: uint!(3),
+			position_in_milliseconds: uint!(90_000),
+			label: "Opening credits end".to_string(),
+		})
+		.with_id(uint!(42));
+		let json =
+			serde_json::to_string(&add_bookmark_request).expect("Failed to serialize AddBookmark request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"add_bookmark","medium_version":3,"position_in_milliseconds":90000,"label":"Opening credits end"}"#,
+			json
+		);
+
+		let deserialized_add_bookmark_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize AddBookmark request from JSON");
+		assert_eq!(add_bookmark_request, deserialized_add_bookmark_request);
+	}
+
+	#[test]
+	fn jump_to_bookmark_request_should_serialize_and_deserialize() {
+		let jump_to_bookmark_request = ClientRequest::JumpToBookmark(JumpToBookmarkRequest {
+			previous_version: uint!(4),
+			id: "9ee7f1c8-5d58-4b0e-9a3d-2c4a3c0f8a51".to_string(),
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&jump_to_bookmark_request)
+			.expect("Failed to serialize JumpToBookmark request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"jump_to_bookmark","previous_version":4,"id":"9ee7f1c8-5d58-4b0e-9a3d-2c4a3c0f8a51"}"#,
+			json
+		);
+
+		let deserialized_jump_to_bookmark_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize JumpToBookmark request from JSON");
+		assert_eq!(jump_to_bookmark_request, deserialized_jump_to_bookmark_request);
+	}
+
+	#[test]
+	fn edit_chat_request_should_serialize_and_deserialize() {
+		let edit_chat_request = ClientRequest::EditChat(EditChatRequest {
+			id: "5b1c0c6e-3f6d-4a8e-9d51-2c0f3a7d8b14".to_string(),
+			message: "Hello".to_string(),
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&edit_chat_request).expect("Failed to serialize EditChat request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"edit_chat","id":"5b1c0c6e-3f6d-4a8e-9d51-2c0f3a7d8b14","message":"Hello"}"#,
+			json
+		);
+
+		let deserialized_edit_chat_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize EditChat request from JSON");
+		assert_eq!(edit_chat_request, deserialized_edit_chat_request);
+	}
+
+	#[test]
+	fn private_message_request_should_serialize_and_deserialize() {
+		let private_message_request = ClientRequest::PrivateMessage(PrivateMessageRequest {
+			recipient_id: SessionId::from(7),
+			message: "Psst!".to_string(),
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&private_message_request)
+			.expect("Failed to serialize PrivateMessage request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"private_message","recipient_id":7,"message":"Psst!"}"#,
+			json
+		);
+
+		let deserialized_private_message_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize PrivateMessage request from JSON");
+		assert_eq!(private_message_request, deserialized_private_message_request);
+	}
+
+	#[test]
+	fn reaction_request_should_serialize_and_deserialize() {
+		let reaction_request = ClientRequest::Reaction(ReactionRequest {
+			emoji: "🎉".to_string(),
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&reaction_request).expect("Failed to serialize Reaction request to JSON");
+		assert_eq!(r#"{"request_id":42,"type":"reaction","emoji":"🎉"}"#, json);
+
+		let deserialized_reaction_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize Reaction request from JSON");
+		assert_eq!(reaction_request, deserialized_reaction_request);
+	}
+
+	#[test]
+	fn typing_request_should_serialize_and_deserialize() {
+		let typing_request = ClientRequest::Typing(TypingRequest { typing: true }).with_id(uint!(42));
+		let json = serde_json::to_string(&typing_request).expect("Failed to serialize Typing request to JSON");
+		assert_eq!(r#"{"request_id":42,"type":"typing","typing":true}"#, json);
+
+		let deserialized_typing_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize Typing request from JSON");
+		assert_eq!(typing_request, deserialized_typing_request);
+	}
+
+	#[test]
+	fn change_name_request_should_serialize_and_deserialize() {
+		let change_name_request = ClientRequest::ChangeName(ChangeNameRequest {
+			name: "Alice".to_string(),
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&change_name_request).expect("Failed to serialize ChangeName request to JSON");
+		assert_eq!(r#"{"request_id":42,"type":"change_name","name":"Alice"}"#, json);
+
+		let deserialized_change_name_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize ChangeName request from JSON");
+		assert_eq!(change_name_request, deserialized_change_name_request);
+	}
+
+	#[test]
+	fn update_profile_request_should_serialize_and_deserialize() {
+		let update_profile_request = ClientRequest::UpdateProfile(UpdateProfileRequest {
+			profile: ProfileRequest {
+				presence: Presence::Away,
+				color: Some("#ff8800".to_string()),
+				avatar: Some(Avatar::Fox),
+				pronouns: Some("he/him".to_string()),
+			},
+		})
+		.with_id(uint!(42));
+		let json =
+			serde_json::to_string(&update_profile_request).expect("Failed to serialize UpdateProfile request to JSON");
+		assert_eq!(
+			r##"{"request_id":42,"type":"update_profile","presence":"away","color":"#ff8800","avatar":"fox","pronouns":"he/him"}"##,
+			json
+		);
+
+		let deserialized_update_profile_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize UpdateProfile request from JSON");
+		assert_eq!(update_profile_request, deserialized_update_profile_request);
+	}
+
+	#[test]
+	fn update_profile_request_should_default_to_active_without_attributes() {
+		let json = r#"{"request_id":42,"type":"update_profile"}"#;
+
+		let update_profile_request: ClientRequestWithId =
+			serde_json::from_str(json).expect("Failed to deserialize UpdateProfile request from JSON");
+
+		assert_eq!(
+			UpdateProfileRequest {
+				profile: ProfileRequest::default()
+			}
+			.with_id(uint!(42)),
+			update_profile_request
+		);
+	}
+
+	#[test]
+	fn update_profile_request_should_reject_unknown_avatars() {
+		let json = r#"{"request_id":42,"type":"update_profile","avatar":"dragon"}"#;
+
+		let result = serde_json::from_str::<ClientRequestWithId>(json);
+
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn create_poll_request_should_serialize_and_deserialize() {
+		let create_poll_request = ClientRequest::CreatePoll(CreatePollRequest {
+			duration_in_milliseconds: uint!(60_000),
+			poll: PollRequest::Custom {
+				question: "What should we watch next?".to_string(),
+				options: vec!["Metropolis".to_string(), "Nosferatu".to_string()],
+			},
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&create_poll_request).expect("Failed to serialize CreatePoll request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"create_poll","duration_in_milliseconds":60000,"poll":{"type":"custom","question":"What should we watch next?","options":["Metropolis","Nosferatu"]}}"#,
+			json
+		);
+
+		let deserialized_create_poll_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize CreatePoll request from JSON");
+		assert_eq!(create_poll_request, deserialized_create_poll_request);
+	}
+
+	#[test]
+	fn vote_request_should_serialize_and_deserialize() {
+		let vote_request = ClientRequest::Vote(VoteRequest {
+			poll_id: "0f3c2a8e-6b1d-4c5e-8a7f-9d2b1e4c6a30".to_string(),
+			option: uint!(1),
+		})
+		.with_id(uint!(42));
+		let json = serde_json::to_string(&vote_request).expect("Failed to serialize Vote request to JSON");
+		assert_eq!(
+			r#"{"request_id":42,"type":"vote","poll_id":"0f3c2a8e-6b1d-4c5e-8a7f-9d2b1e4c6a30","option":1}"#,
+			json
+		);
+
+		let deserialized_vote_request: ClientRequestWithId =
+			serde_json::from_str(&json).expect("Failed to deserialize Vote request from JSON");
+		assert_eq!(vote_request, deserialized_vote_request);
+	}
+
+	#[test]
+	fn should_only_accept_web_urls_for_media() {
+		let medium_request = |url: &str| MediumRequest::Url {
+			url: url.to_string(),
+			mime_type: None,
+			length_in_milliseconds: None,
+		};
+		let media_directory = MediaDirectory::default();
+
+		assert!(
+			medium_request("https://example.com/video.mp4")
+				.into_medium(&media_directory)
+				.is_ok()
+		);
+		assert!(
+			medium_request("http://example.com/video.mp4")
+				.into_medium(&media_directory)
+				.is_ok()
+		);
+		let error = medium_request("file:///etc/passwd")
+			.into_medium(&media_directory)
+			.expect_err("Accepted file URL");
+		assert_eq!(ErrorMessageType::InvalidFormat, error.error);
+		let error = medium_request("not a url")
+			.into_medium(&media_directory)
+			.expect_err("Accepted invalid URL");
+		assert_eq!(ErrorMessageType::InvalidFormat, error.error);
+	}
+
+	#[test]
+	fn should_not_accept_invalid_mime_types_for_media() {
+		let medium_request = MediumRequest::Url {
+			url: "https://example.com/video.mp4".to_string(),
+			mime_type: Some("video".to_string()),
+			length_in_milliseconds: None,
+		};
+
+		let error = medium_request
+			.into_medium(&MediaDirectory::default())
+			.expect_err("Accepted invalid MIME type");
+
+		assert_eq!(ErrorMessageType::InvalidFormat, error.error);
+	}
+
 	#[test]
 	fn request_id_only_should_serialize_and_deserialize() {
 		let request_id_only = RequestIdOnly { request_id: uint!(42) };
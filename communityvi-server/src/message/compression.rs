@@ -0,0 +1,128 @@
+use crate::message::WebSocketMessage;
+use crate::message::format::MessageFormat;
+use flate2::Compression;
+use flate2::write::DeflateEncoder;
+use std::io::Write;
+
+/// Application level compression of outgoing messages.
+///
+/// The websocket implementation doesn't support the `permessage-deflate` extension, so compression is
+/// negotiated by appending [`MessageCompression::WEBSOCKET_PROTOCOL_SUFFIX`] to the websocket subprotocol
+/// of the [`MessageFormat`], e.g. `communityvi-json+deflate`.
+///
+/// Outgoing messages of at least `threshold` bytes are then sent as binary websocket messages
+/// consisting of [`MessageCompression::MARKER`] followed by the raw DEFLATE stream of the encoded message.
+/// `0xc1` is never used in `MessagePack` and JSON is always sent as text, so this is unambiguous.
+/// Messages from the client are never compressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessageCompression {
+	pub threshold: usize,
+	pub level: Compression,
+}
+
+impl MessageCompression {
+	pub const WEBSOCKET_PROTOCOL_SUFFIX: &'static str = "+deflate";
+	pub const MARKER: u8 = 0xc1;
+
+	/// All supported websocket subprotocols in order of preference, compressed ones first.
+	pub fn websocket_protocols() -> impl Iterator<Item = String> {
+		MessageFormat::ALL.into_iter().flat_map(|format| {
+			let protocol = format.websocket_protocol();
+			[
+				format!("{protocol}{}", Self::WEBSOCKET_PROTOCOL_SUFFIX),
+				protocol.to_string(),
+			]
+		})
+	}
+
+	/// Returns the [`MessageFormat`] of a websocket subprotocol and whether it requests compression.
+	pub fn parse_websocket_protocol(protocol: &str) -> Option<(MessageFormat, bool)> {
+		match protocol.strip_suffix(Self::WEBSOCKET_PROTOCOL_SUFFIX) {
+			Some(format_protocol) => {
+				MessageFormat::from_websocket_protocol(format_protocol).map(|format| (format, true))
+			}
+			None => MessageFormat::from_websocket_protocol(protocol).map(|format| (format, false)),
+		}
+	}
+
+	pub fn compress(self, websocket_message: WebSocketMessage) -> WebSocketMessage {
+		let payload = match &websocket_message {
+			WebSocketMessage::Text(text) => text.as_bytes(),
+			WebSocketMessage::Binary(bytes) => bytes.as_ref(),
+			_ => return websocket_message,
+		};
+		if payload.len() < self.threshold {
+			return websocket_message;
+		}
+
+		let mut encoder = DeflateEncoder::new(vec![Self::MARKER], self.level);
+		encoder
+			.write_all(payload)
+			.expect("Failed to compress message into memory.");
+		let compressed = encoder.finish().expect("Failed to compress message into memory.");
+		WebSocketMessage::binary(compressed)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use flate2::read::DeflateDecoder;
+	use std::io::Read;
+
+	const COMPRESSION: MessageCompression = MessageCompression {
+		threshold: 100,
+		level: Compression::new(6),
+	};
+
+	#[test]
+	fn should_prefer_compressed_websocket_protocols() {
+		let protocols = MessageCompression::websocket_protocols().collect::<Vec<_>>();
+
+		assert_eq!(
+			vec![
+				"communityvi-messagepack+deflate",
+				"communityvi-messagepack",
+				"communityvi-json+deflate",
+				"communityvi-json",
+			],
+			protocols
+		);
+	}
+
+	#[test]
+	fn should_parse_all_websocket_protocols() {
+		assert_eq!(
+			Some((MessageFormat::Json, true)),
+			MessageCompression::parse_websocket_protocol("communityvi-json+deflate")
+		);
+		assert_eq!(
+			Some((MessageFormat::MessagePack, false)),
+			MessageCompression::parse_websocket_protocol("communityvi-messagepack")
+		);
+		assert_eq!(None, MessageCompression::parse_websocket_protocol("graphql-ws+deflate"));
+	}
+
+	#[test]
+	fn should_not_compress_messages_below_threshold() {
+		let message = WebSocketMessage::text("a".repeat(99));
+
+		assert_eq!(message.clone(), COMPRESSION.compress(message));
+	}
+
+	#[test]
+	fn should_compress_messages_above_threshold() {
+		let text = "a".repeat(100);
+
+		let WebSocketMessage::Binary(compressed) = COMPRESSION.compress(WebSocketMessage::text(text.clone())) else {
+			panic!("Compressed message must be binary.");
+		};
+
+		assert_eq!(MessageCompression::MARKER, compressed[0]);
+		let mut decompressed = String::new();
+		DeflateDecoder::new(&compressed[1..])
+			.read_to_string(&mut decompressed)
+			.expect("Failed to decompress message");
+		assert_eq!(text, decompressed);
+	}
+}
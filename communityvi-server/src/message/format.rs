@@ -0,0 +1,153 @@
+use crate::message::{MessageError, WebSocketMessage};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Serialization format of the messages exchanged over a single connection.
+///
+/// The format is negotiated once when connecting via the websocket subprotocol
+/// (see [`MessageFormat::websocket_protocol`]). Connections that don't request any
+/// subprotocol use JSON.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MessageFormat {
+	/// JSON in websocket text messages.
+	#[default]
+	Json,
+	/// `MessagePack` in websocket binary messages.
+	MessagePack,
+}
+
+impl MessageFormat {
+	/// All supported formats, in order of preference if a client offers more than one.
+	pub const ALL: [MessageFormat; 2] = [MessageFormat::MessagePack, MessageFormat::Json];
+
+	pub fn websocket_protocol(self) -> &'static str {
+		match self {
+			MessageFormat::Json => "communityvi-json",
+			MessageFormat::MessagePack => "communityvi-messagepack",
+		}
+	}
+
+	pub fn from_websocket_protocol(protocol: &str) -> Option<Self> {
+		Self::ALL
+			.into_iter()
+			.find(|format| format.websocket_protocol() == protocol)
+	}
+
+	pub fn encode(self, message: &impl Serialize) -> WebSocketMessage {
+		match self {
+			MessageFormat::Json => {
+				let json = serde_json::to_string(message).expect("Failed to serialize message to JSON.");
+				WebSocketMessage::text(json)
+			}
+			MessageFormat::MessagePack => {
+				// Structs need to be encoded as maps (instead of arrays) for internally tagged enums to work.
+				let bytes = rmp_serde::to_vec_named(message).expect("Failed to serialize message to MessagePack.");
+				WebSocketMessage::binary(bytes)
+			}
+		}
+	}
+
+	pub fn decode<Message: DeserializeOwned>(
+		self,
+		websocket_message: &WebSocketMessage,
+	) -> Result<Message, MessageError> {
+		match (self, websocket_message) {
+			(MessageFormat::Json, WebSocketMessage::Text(json)) => {
+				serde_json::from_str(json).map_err(|error| MessageError::DeserializationFailed {
+					error: error.to_string(),
+					message: json.to_string(),
+				})
+			}
+			(MessageFormat::MessagePack, WebSocketMessage::Binary(bytes)) => {
+				rmp_serde::from_slice(bytes).map_err(|error| MessageError::DeserializationFailed {
+					error: error.to_string(),
+					message: format!("{bytes:?}"),
+				})
+			}
+			_ => Err(MessageError::WrongMessageType(websocket_message.clone())),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::message::client_request::{ChatRequest, ClientRequest, ClientRequestWithId, RequestIdOnly};
+	use crate::message::outgoing::OutgoingMessage;
+	use crate::message::outgoing::broadcast_message::{BroadcastMessage, ClientJoinedBroadcast};
+	use crate::message::outgoing::success_message::ProfileResponse;
+	use crate::room::session_id::SessionId;
+	use js_int::uint;
+
+	#[test]
+	fn should_find_formats_by_their_websocket_protocol() {
+		for format in MessageFormat::ALL {
+			assert_eq!(
+				Some(format),
+				MessageFormat::from_websocket_protocol(format.websocket_protocol())
+			);
+		}
+		assert_eq!(None, MessageFormat::from_websocket_protocol("graphql-ws"));
+	}
+
+	#[test]
+	fn client_request_should_encode_and_decode_as_message_pack() {
+		let request = ClientRequestWithId {
+			request_id: uint!(42),
+			request: ClientRequest::Chat(ChatRequest {
+				message: "hello".to_string(),
+				reply_to: None,
+			}),
+		};
+
+		let websocket_message = MessageFormat::MessagePack.encode(&request);
+		assert!(websocket_message.is_binary());
+
+		let decoded_request: ClientRequestWithId = MessageFormat::MessagePack
+			.decode(&websocket_message)
+			.expect("Failed to decode request from MessagePack");
+		assert_eq!(request, decoded_request);
+
+		let request_id_only: RequestIdOnly = MessageFormat::MessagePack
+			.decode(&websocket_message)
+			.expect("Failed to decode request id from MessagePack");
+		assert_eq!(uint!(42), request_id_only.request_id);
+	}
+
+	#[test]
+	fn outgoing_message_should_encode_and_decode_as_message_pack() {
+		let outgoing_message = OutgoingMessage::Broadcast {
+			message: BroadcastMessage::ClientJoined(ClientJoinedBroadcast {
+				id: SessionId::from(99),
+				name: "Luftballons".to_string(),
+				accepts_private_messages: true,
+				profile: ProfileResponse::default(),
+			}),
+		};
+
+		let websocket_message = MessageFormat::MessagePack.encode(&outgoing_message);
+		let decoded_message: OutgoingMessage = MessageFormat::MessagePack
+			.decode(&websocket_message)
+			.expect("Failed to decode outgoing message from MessagePack");
+
+		assert_eq!(outgoing_message, decoded_message);
+	}
+
+	#[test]
+	fn should_not_decode_message_pack_from_text_messages() {
+		let websocket_message = MessageFormat::Json.encode(&RequestIdOnly { request_id: uint!(1) });
+
+		let result = MessageFormat::MessagePack.decode::<RequestIdOnly>(&websocket_message);
+
+		assert!(matches!(result, Err(MessageError::WrongMessageType(_))));
+	}
+
+	#[test]
+	fn should_not_decode_json_from_binary_messages() {
+		let websocket_message = MessageFormat::MessagePack.encode(&RequestIdOnly { request_id: uint!(1) });
+
+		let result = MessageFormat::Json.decode::<RequestIdOnly>(&websocket_message);
+
+		assert!(matches!(result, Err(MessageError::WrongMessageType(_))));
+	}
+}
@@ -1,5 +1,8 @@
+use crate::message::format::MessageFormat;
 use crate::message::outgoing::broadcast_message::BroadcastMessage;
 use crate::message::outgoing::error_message::ErrorMessage;
+use crate::message::outgoing::moderator_notice::ModeratorNotice;
+use crate::message::outgoing::private_message::PrivateMessage;
 use crate::message::outgoing::success_message::SuccessMessage;
 use crate::message::{MessageError, WebSocketMessage};
 use js_int::UInt;
@@ -7,6 +10,8 @@ use serde::{Deserialize, Serialize};
 
 pub mod broadcast_message;
 pub mod error_message;
+pub mod moderator_notice;
+pub mod private_message;
 pub mod success_message;
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -24,12 +29,17 @@ pub enum OutgoingMessage {
 	Broadcast {
 		message: BroadcastMessage,
 	},
+	Private {
+		message: PrivateMessage,
+	},
+	ModeratorNotice {
+		message: ModeratorNotice,
+	},
 }
 
 impl From<&OutgoingMessage> for WebSocketMessage {
 	fn from(response: &OutgoingMessage) -> Self {
-		let json = serde_json::to_string(response).expect("Failed to serialize response to JSON.");
-		WebSocketMessage::text(json)
+		MessageFormat::Json.encode(response)
 	}
 }
 
@@ -37,15 +47,7 @@ impl TryFrom<&WebSocketMessage> for OutgoingMessage {
 	type Error = MessageError;
 
 	fn try_from(websocket_message: &WebSocketMessage) -> Result<Self, MessageError> {
-		match websocket_message {
-			WebSocketMessage::Text(json) => {
-				serde_json::from_str(json).map_err(|error| MessageError::DeserializationFailed {
-					error: error.to_string(),
-					json: json.to_string(),
-				})
-			}
-			_ => Err(MessageError::WrongMessageType(websocket_message.clone())),
-		}
+		MessageFormat::Json.decode(websocket_message)
 	}
 }
 
@@ -54,6 +56,8 @@ mod test {
 	use super::*;
 	use crate::message::outgoing::broadcast_message::ClientJoinedBroadcast;
 	use crate::message::outgoing::error_message::ErrorMessageType;
+	use crate::message::outgoing::moderator_notice::ChatFlaggedNotice;
+	use crate::message::outgoing::success_message::ProfileResponse;
 	use crate::room::session_id::SessionId;
 	use js_int::uint;
 
@@ -120,11 +124,13 @@ mod test {
 			message: BroadcastMessage::ClientJoined(ClientJoinedBroadcast {
 				id: SessionId::from(99),
 				name: "Luftballons".to_string(),
+				accepts_private_messages: true,
+				profile: ProfileResponse::default(),
 			}),
 		};
 		let json = serde_json::to_string(&broadcast_message).expect("Failed to serialize broadcast message to JSON");
 		assert_eq!(
-			r#"{"type":"broadcast","message":{"type":"client_joined","id":99,"name":"Luftballons"}}"#,
+			r#"{"type":"broadcast","message":{"type":"client_joined","id":99,"name":"Luftballons","accepts_private_messages":true,"presence":"active","color":null,"avatar":null,"pronouns":null}}"#,
 			json
 		);
 
@@ -132,4 +138,49 @@ mod test {
 			serde_json::from_str(&json).expect("Failed to deserialize broadcast message from JSON");
 		assert_eq!(broadcast_message, deserialized_broadcast_message);
 	}
+
+	#[test]
+	fn private_message_should_serialize_and_deserialize() {
+		let private_message = OutgoingMessage::Private {
+			message: PrivateMessage {
+				sender_id: SessionId::from(1),
+				sender_name: "Alice".to_string(),
+				recipient_id: SessionId::from(2),
+				recipient_name: "Bob".to_string(),
+				message: "Psst!".to_string(),
+			},
+		};
+		let json = serde_json::to_string(&private_message).expect("Failed to serialize private message to JSON");
+		assert_eq!(
+			r#"{"type":"private","message":{"sender_id":1,"sender_name":"Alice","recipient_id":2,"recipient_name":"Bob","message":"Psst!"}}"#,
+			json
+		);
+
+		let deserialized_private_message: OutgoingMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize private message from JSON");
+		assert_eq!(private_message, deserialized_private_message);
+	}
+
+	#[test]
+	fn moderator_notice_should_serialize_and_deserialize() {
+		let moderator_notice = OutgoingMessage::ModeratorNotice {
+			message: ChatFlaggedNotice {
+				id: None,
+				sender_id: SessionId::from(1),
+				sender_name: "Mallory".to_string(),
+				message: "Spam".to_string(),
+				flagged_by: vec!["flood".to_string()],
+			}
+			.into(),
+		};
+		let json = serde_json::to_string(&moderator_notice).expect("Failed to serialize moderator notice to JSON");
+		assert_eq!(
+			r#"{"type":"moderator_notice","message":{"type":"chat_flagged","id":null,"sender_id":1,"sender_name":"Mallory","message":"Spam","flagged_by":["flood"]}}"#,
+			json
+		);
+
+		let deserialized_moderator_notice: OutgoingMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize moderator notice from JSON");
+		assert_eq!(moderator_notice, deserialized_moderator_notice);
+	}
 }
@@ -1,5 +1,10 @@
-use crate::message::outgoing::success_message::PlaybackStateResponse;
+use crate::message::format::MessageFormat;
+use crate::message::outgoing::success_message::ProfileResponse;
+use crate::message::outgoing::success_message::{
+	BookmarkResponse, FingerprintResponse, PlaybackStateResponse, SubtitlesResponse,
+};
 use crate::message::{MessageError, WebSocketMessage};
+use crate::poll::model::{PollKind, PollOption};
 use crate::room::medium::{Medium, VersionedMedium};
 use crate::room::session_id::SessionId;
 use js_int::UInt;
@@ -11,8 +16,20 @@ use serde::{Deserialize, Serialize};
 pub enum BroadcastMessage {
 	ClientJoined(ClientJoinedBroadcast),
 	ClientLeft(ClientLeftBroadcast),
+	ClientRenamed(ClientRenamedBroadcast),
+	ClientProfileChanged(ClientProfileChangedBroadcast),
 	Chat(ChatBroadcast),
+	ChatEdited(ChatEditedBroadcast),
+	ChatDeleted(ChatDeletedBroadcast),
+	Emote(EmoteBroadcast),
+	Typing(TypingBroadcast),
+	Reaction(ReactionBroadcast),
 	MediumStateChanged(MediumStateChangedBroadcast),
+	PlaybackScheduled(PlaybackScheduledBroadcast),
+	FingerprintMismatch(FingerprintMismatchBroadcast),
+	BookmarksChanged(BookmarksChangedBroadcast),
+	SubtitlesChanged(SubtitlesChangedBroadcast),
+	Poll(PollBroadcast),
 }
 
 macro_rules! broadcast_from_struct {
@@ -29,6 +46,9 @@ macro_rules! broadcast_from_struct {
 pub struct ClientJoinedBroadcast {
 	pub id: SessionId,
 	pub name: String,
+	pub accepts_private_messages: bool,
+	#[serde(flatten)]
+	pub profile: ProfileResponse,
 }
 
 broadcast_from_struct!(ClientJoined, ClientJoinedBroadcast);
@@ -45,27 +65,126 @@ pub struct ClientLeftBroadcast {
 pub enum LeftReason {
 	Closed,
 	Timeout,
+	Kicked,
 }
 
 broadcast_from_struct!(ClientLeft, ClientLeftBroadcast);
 
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ClientRenamedBroadcast {
+	pub id: SessionId,
+	pub old_name: String,
+	pub new_name: String,
+}
+
+broadcast_from_struct!(ClientRenamed, ClientRenamedBroadcast);
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ClientProfileChangedBroadcast {
+	pub id: SessionId,
+	pub name: String,
+	#[serde(flatten)]
+	pub profile: ProfileResponse,
+}
+
+broadcast_from_struct!(ClientProfileChanged, ClientProfileChangedBroadcast);
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct ChatBroadcast {
+	pub id: String,
 	pub sender_id: SessionId,
 	pub sender_name: String,
 	pub message: String,
 	pub counter: UInt,
+	/// Position of the medium when the message was sent, `None` if there was no medium.
+	pub medium_position_in_milliseconds: Option<UInt>,
+	/// Id of the chat message this one replies to.
+	pub reply_to: Option<String>,
+	/// Clients in the room that were mentioned with `@name`.
+	pub mentions: Vec<ChatMention>,
+	/// Whether the client receiving this broadcast is one of the `mentions`.
+	pub mentions_you: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ChatMention {
+	pub id: SessionId,
+	pub name: String,
 }
 
 broadcast_from_struct!(Chat, ChatBroadcast);
 
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ChatEditedBroadcast {
+	pub id: String,
+	pub message: String,
+	pub edited_by_name: String,
+	pub edited_by_id: SessionId,
+}
+
+broadcast_from_struct!(ChatEdited, ChatEditedBroadcast);
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ChatDeletedBroadcast {
+	pub id: String,
+	pub deleted_by_name: String,
+	pub deleted_by_id: SessionId,
+}
+
+broadcast_from_struct!(ChatDeleted, ChatDeletedBroadcast);
+
+/// An action of the sender, like `/me waves` or the result of `/roll`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct EmoteBroadcast {
+	pub sender_id: SessionId,
+	pub sender_name: String,
+	pub action: String,
+}
+
+broadcast_from_struct!(Emote, EmoteBroadcast);
+
+/// Only sent when the typing state of a client changes, including when it expires.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TypingBroadcast {
+	pub id: SessionId,
+	pub name: String,
+	pub typing: bool,
+}
+
+broadcast_from_struct!(Typing, TypingBroadcast);
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ReactionBroadcast {
+	pub sender_id: SessionId,
+	pub sender_name: String,
+	pub emoji: String,
+	/// Position of the medium when the reaction was sent, `None` if there was no medium.
+	pub medium_position_in_milliseconds: Option<UInt>,
+}
+
+broadcast_from_struct!(Reaction, ReactionBroadcast);
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct MediumStateChangedBroadcast {
 	pub changed_by_name: String,
-	pub changed_by_id: SessionId,
+	/// `None` if the change was made by the server itself, e.g. because a client is buffering.
+	pub changed_by_id: Option<SessionId>,
 	pub medium: VersionedMediumBroadcast,
 }
 
+impl MediumStateChangedBroadcast {
+	/// Name the server uses when it changes the medium by itself.
+	pub const SERVER_NAME: &'static str = "Server";
+
+	pub fn by_server(medium: VersionedMediumBroadcast) -> Self {
+		Self {
+			changed_by_name: Self::SERVER_NAME.to_string(),
+			changed_by_id: None,
+			medium,
+		}
+	}
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct VersionedMediumBroadcast {
 	pub version: UInt,
@@ -89,6 +208,23 @@ pub enum MediumBroadcast {
 	FixedLength {
 		name: String,
 		length_in_milliseconds: UInt,
+		fingerprint: Option<FingerprintResponse>,
+		hosted_id: Option<String>,
+		subtitles: SubtitlesResponse,
+		playback_skipped: bool,
+		playback_state: PlaybackStateResponse,
+	},
+	Url {
+		url: String,
+		mime_type: Option<String>,
+		length_in_milliseconds: Option<UInt>,
+		subtitles: SubtitlesResponse,
+		playback_skipped: bool,
+		playback_state: PlaybackStateResponse,
+	},
+	Live {
+		name: String,
+		subtitles: SubtitlesResponse,
 		playback_skipped: bool,
 		playback_state: PlaybackStateResponse,
 	},
@@ -101,6 +237,25 @@ impl MediumBroadcast {
 			Medium::FixedLength(medium) => MediumBroadcast::FixedLength {
 				name: medium.name,
 				length_in_milliseconds: UInt::try_from(medium.length.num_milliseconds()).unwrap(),
+				fingerprint: medium.fingerprint.map(Into::into),
+				hosted_id: medium.hosted_id,
+				subtitles: medium.subtitles.into(),
+				playback_skipped: skipped,
+				playback_state: medium.playback.into(),
+			},
+			Medium::Url(medium) => MediumBroadcast::Url {
+				url: medium.url.into(),
+				mime_type: medium.mime_type.map(|mime_type| mime_type.to_string()),
+				length_in_milliseconds: medium
+					.length
+					.map(|length| UInt::try_from(length.num_milliseconds()).unwrap()),
+				subtitles: medium.subtitles.into(),
+				playback_skipped: skipped,
+				playback_state: medium.playback.into(),
+			},
+			Medium::Live(medium) => MediumBroadcast::Live {
+				name: medium.name,
+				subtitles: medium.subtitles.into(),
 				playback_skipped: skipped,
 				playback_state: medium.playback.into(),
 			},
@@ -111,45 +266,148 @@ impl MediumBroadcast {
 
 broadcast_from_struct!(MediumStateChanged, MediumStateChangedBroadcast);
 
+/// Playback of the medium with `medium_version` was scheduled to start at a reference time.
+/// The scheduled time is `None` if a previously scheduled start was cancelled, either explicitly
+/// or because the medium changed before playback started.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PlaybackScheduledBroadcast {
+	pub changed_by_name: String,
+	/// `None` if the medium was changed by the server itself, see [`MediumStateChangedBroadcast`].
+	pub changed_by_id: Option<SessionId>,
+	pub medium_version: UInt,
+	pub scheduled_time_in_milliseconds: Option<UInt>,
+}
+
+broadcast_from_struct!(PlaybackScheduled, PlaybackScheduledBroadcast);
+
+/// All clients that reported a fingerprint for the medium with `medium_version` that doesn't match its own.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct FingerprintMismatchBroadcast {
+	pub medium_version: UInt,
+	pub mismatching_clients: Vec<MismatchingClient>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct MismatchingClient {
+	pub id: SessionId,
+	pub name: String,
+}
+
+broadcast_from_struct!(FingerprintMismatch, FingerprintMismatchBroadcast);
+
+/// All bookmarks of the medium with `medium_version` after one was added or removed, ordered by position.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BookmarksChangedBroadcast {
+	pub changed_by_name: String,
+	pub changed_by_id: SessionId,
+	pub medium_version: UInt,
+	pub bookmarks: Vec<BookmarkResponse>,
+}
+
+broadcast_from_struct!(BookmarksChanged, BookmarksChangedBroadcast);
+
+/// All subtitles of the medium with `medium_version` after a track was added or they were shifted.
+/// Subtitles don't change the medium version, so this doesn't interfere with playback.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SubtitlesChangedBroadcast {
+	pub changed_by_name: String,
+	pub changed_by_id: SessionId,
+	pub medium_version: UInt,
+	pub subtitles: SubtitlesResponse,
+}
+
+broadcast_from_struct!(SubtitlesChanged, SubtitlesChangedBroadcast);
+
+/// The current state of a poll, sent when it is created, on every vote and when it is closed.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PollBroadcast {
+	pub id: String,
+	pub kind: PollKindBroadcast,
+	pub question: String,
+	pub options: Vec<PollOptionBroadcast>,
+	pub created_by_name: String,
+	/// The medium version a skip or pause poll applies to.
+	pub medium_version: Option<UInt>,
+	pub deadline_in_milliseconds: UInt,
+	pub closed: bool,
+}
+
+broadcast_from_struct!(Poll, PollBroadcast);
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PollKindBroadcast {
+	Custom,
+	Skip,
+	Pause,
+}
+
+impl From<PollKind> for PollKindBroadcast {
+	fn from(kind: PollKind) -> Self {
+		match kind {
+			PollKind::Custom => Self::Custom,
+			PollKind::Skip => Self::Skip,
+			PollKind::Pause => Self::Pause,
+		}
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PollOptionBroadcast {
+	pub label: String,
+	pub votes: UInt,
+}
+
+impl From<PollOption> for PollOptionBroadcast {
+	fn from(PollOption { label, votes }: PollOption) -> Self {
+		Self {
+			label,
+			votes: votes.into(),
+		}
+	}
+}
+
 impl TryFrom<&WebSocketMessage> for BroadcastMessage {
 	type Error = MessageError;
 
 	fn try_from(websocket_message: &WebSocketMessage) -> Result<Self, Self::Error> {
-		match websocket_message {
-			WebSocketMessage::Text(json) => {
-				serde_json::from_str(json).map_err(|error| MessageError::DeserializationFailed {
-					error: error.to_string(),
-					json: json.to_string(),
-				})
-			}
-			_ => Err(MessageError::WrongMessageType(websocket_message.clone())),
-		}
+		MessageFormat::Json.decode(websocket_message)
 	}
 }
 
 impl From<&BroadcastMessage> for WebSocketMessage {
 	fn from(message: &BroadcastMessage) -> Self {
-		let json = serde_json::to_string(message).expect("Failed to serialize broadcast message to JSON.");
-		WebSocketMessage::text(json)
+		MessageFormat::Json.encode(message)
 	}
 }
 
 #[cfg(test)]
+#[allow(clippy::non_ascii_literal)]
 mod test {
 	use super::*;
+	use crate::message::outgoing::success_message::SubtitleTrackResponse;
+	use crate::user::model::{Avatar, Presence};
 	use js_int::{int, uint};
 
 	#[test]
 	fn chat_broadcast_should_serialize_and_deserialize() {
 		let chat_broadcast = BroadcastMessage::Chat(ChatBroadcast {
+			id: "5b1c0c6e-3f6d-4a8e-9d51-2c0f3a7d8b14".to_string(),
 			sender_id: SessionId::from(42),
 			sender_name: "Hedwig".to_string(),
 			message: "hello".to_string(),
 			counter: uint!(1337),
+			medium_position_in_milliseconds: Some(uint!(90_000)),
+			reply_to: Some("0c5b4e1e-8d1f-4f4a-b5a2-6a3f1c9d2e77".to_string()),
+			mentions: vec![ChatMention {
+				id: SessionId::from(7),
+				name: "Errol".to_string(),
+			}],
+			mentions_you: true,
 		});
 		let json = serde_json::to_string(&chat_broadcast).expect("Failed to serialize Chat broadcast to JSON");
 		assert_eq!(
-			r#"{"type":"chat","sender_id":42,"sender_name":"Hedwig","message":"hello","counter":1337}"#,
+			r#"{"type":"chat","id":"5b1c0c6e-3f6d-4a8e-9d51-2c0f3a7d8b14","sender_id":42,"sender_name":"Hedwig","message":"hello","counter":1337,"medium_position_in_milliseconds":90000,"reply_to":"0c5b4e1e-8d1f-4f4a-b5a2-6a3f1c9d2e77","mentions":[{"id":7,"name":"Errol"}],"mentions_you":true}"#,
 			json
 		);
 
@@ -158,15 +416,111 @@ mod test {
 		assert_eq!(chat_broadcast, deserialized_chat_broadcast);
 	}
 
+	#[test]
+	fn chat_edited_broadcast_should_serialize_and_deserialize() {
+		let chat_edited_broadcast = BroadcastMessage::ChatEdited(ChatEditedBroadcast {
+			id: "5b1c0c6e-3f6d-4a8e-9d51-2c0f3a7d8b14".to_string(),
+			message: "Hello".to_string(),
+			edited_by_name: "Hedwig".to_string(),
+			edited_by_id: SessionId::from(42),
+		});
+		let json =
+			serde_json::to_string(&chat_edited_broadcast).expect("Failed to serialize ChatEdited broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"chat_edited","id":"5b1c0c6e-3f6d-4a8e-9d51-2c0f3a7d8b14","message":"Hello","edited_by_name":"Hedwig","edited_by_id":42}"#,
+			json
+		);
+
+		let deserialized_chat_edited_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize ChatEdited broadcast from JSON");
+		assert_eq!(chat_edited_broadcast, deserialized_chat_edited_broadcast);
+	}
+
+	#[test]
+	fn chat_deleted_broadcast_should_serialize_and_deserialize() {
+		let chat_deleted_broadcast = BroadcastMessage::ChatDeleted(ChatDeletedBroadcast {
+			id: "5b1c0c6e-3f6d-4a8e-9d51-2c0f3a7d8b14".to_string(),
+			deleted_by_name: "Hedwig".to_string(),
+			deleted_by_id: SessionId::from(42),
+		});
+		let json =
+			serde_json::to_string(&chat_deleted_broadcast).expect("Failed to serialize ChatDeleted broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"chat_deleted","id":"5b1c0c6e-3f6d-4a8e-9d51-2c0f3a7d8b14","deleted_by_name":"Hedwig","deleted_by_id":42}"#,
+			json
+		);
+
+		let deserialized_chat_deleted_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize ChatDeleted broadcast from JSON");
+		assert_eq!(chat_deleted_broadcast, deserialized_chat_deleted_broadcast);
+	}
+
+	#[test]
+	fn emote_broadcast_should_serialize_and_deserialize() {
+		let emote_broadcast = BroadcastMessage::Emote(EmoteBroadcast {
+			sender_id: SessionId::from(42),
+			sender_name: "Hedwig".to_string(),
+			action: "hoots".to_string(),
+		});
+		let json = serde_json::to_string(&emote_broadcast).expect("Failed to serialize Emote broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"emote","sender_id":42,"sender_name":"Hedwig","action":"hoots"}"#,
+			json
+		);
+
+		let deserialized_emote_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize Emote broadcast from JSON");
+		assert_eq!(emote_broadcast, deserialized_emote_broadcast);
+	}
+
+	#[test]
+	fn typing_broadcast_should_serialize_and_deserialize() {
+		let typing_broadcast = BroadcastMessage::Typing(TypingBroadcast {
+			id: SessionId::from(42),
+			name: "Hedwig".to_string(),
+			typing: true,
+		});
+		let json = serde_json::to_string(&typing_broadcast).expect("Failed to serialize Typing broadcast to JSON");
+		assert_eq!(r#"{"type":"typing","id":42,"name":"Hedwig","typing":true}"#, json);
+
+		let deserialized_typing_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize Typing broadcast from JSON");
+		assert_eq!(typing_broadcast, deserialized_typing_broadcast);
+	}
+
+	#[test]
+	fn reaction_broadcast_should_serialize_and_deserialize() {
+		let reaction_broadcast = BroadcastMessage::Reaction(ReactionBroadcast {
+			sender_id: SessionId::from(42),
+			sender_name: "Hedwig".to_string(),
+			emoji: "🦉".to_string(),
+			medium_position_in_milliseconds: None,
+		});
+		let json = serde_json::to_string(&reaction_broadcast).expect("Failed to serialize Reaction broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"reaction","sender_id":42,"sender_name":"Hedwig","emoji":"🦉","medium_position_in_milliseconds":null}"#,
+			json
+		);
+
+		let deserialized_reaction_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize Reaction broadcast from JSON");
+		assert_eq!(reaction_broadcast, deserialized_reaction_broadcast);
+	}
+
 	#[test]
 	fn client_joined_broadcast_should_serialize_and_deserialize() {
 		let joined_broadcast = BroadcastMessage::ClientJoined(ClientJoinedBroadcast {
 			id: SessionId::from(42),
 			name: "Hedwig".to_string(),
+			accepts_private_messages: true,
+			profile: ProfileResponse::default(),
 		});
 		let json =
 			serde_json::to_string(&joined_broadcast).expect("Failed to serialize ClientJoined broadcast to JSON");
-		assert_eq!(r#"{"type":"client_joined","id":42,"name":"Hedwig"}"#, json);
+		assert_eq!(
+			r#"{"type":"client_joined","id":42,"name":"Hedwig","accepts_private_messages":true,"presence":"active","color":null,"avatar":null,"pronouns":null}"#,
+			json
+		);
 
 		let deserialized_joined_broadcast: BroadcastMessage =
 			serde_json::from_str(&json).expect("Failed to deserialize ClientJoined broadcast from JSON");
@@ -192,15 +546,64 @@ mod test {
 		assert_eq!(client_left_broadcast, deserialized_client_left_broadcast);
 	}
 
+	#[test]
+	fn client_renamed_broadcast_should_serialize_and_deserialize() {
+		let client_renamed_broadcast = BroadcastMessage::ClientRenamed(ClientRenamedBroadcast {
+			id: SessionId::from(42),
+			old_name: "Hedwig".to_string(),
+			new_name: "Errol".to_string(),
+		});
+		let json = serde_json::to_string(&client_renamed_broadcast)
+			.expect("Failed to serialize ClientRenamed broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"client_renamed","id":42,"old_name":"Hedwig","new_name":"Errol"}"#,
+			json
+		);
+
+		let deserialized_client_renamed_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize ClientRenamed broadcast from JSON");
+		assert_eq!(client_renamed_broadcast, deserialized_client_renamed_broadcast);
+	}
+
+	#[test]
+	fn client_profile_changed_broadcast_should_serialize_and_deserialize() {
+		let client_profile_changed_broadcast = BroadcastMessage::ClientProfileChanged(ClientProfileChangedBroadcast {
+			id: SessionId::from(42),
+			name: "Hedwig".to_string(),
+			profile: ProfileResponse {
+				presence: Presence::DoNotDisturb,
+				color: Some("#f0f0f0".to_string()),
+				avatar: Some(Avatar::Owl),
+				pronouns: Some("she/her".to_string()),
+			},
+		});
+		let json = serde_json::to_string(&client_profile_changed_broadcast)
+			.expect("Failed to serialize ClientProfileChanged broadcast to JSON");
+		assert_eq!(
+			r##"{"type":"client_profile_changed","id":42,"name":"Hedwig","presence":"do_not_disturb","color":"#f0f0f0","avatar":"owl","pronouns":"she/her"}"##,
+			json
+		);
+
+		let deserialized_client_profile_changed_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize ClientProfileChanged broadcast from JSON");
+		assert_eq!(
+			client_profile_changed_broadcast,
+			deserialized_client_profile_changed_broadcast
+		);
+	}
+
 	#[test]
 	fn medium_state_changed_broadcast_for_paused_should_serialize_and_deserialize() {
 		let medium_state_changed_broadcast = BroadcastMessage::MediumStateChanged(MediumStateChangedBroadcast {
 			changed_by_name: "Squirrel".to_string(),
-			changed_by_id: SessionId::from(42),
+			changed_by_id: Some(SessionId::from(42)),
 			medium: VersionedMediumBroadcast {
 				medium: MediumBroadcast::FixedLength {
 					name: "The Acorn".to_string(),
 					length_in_milliseconds: UInt::from(20u32 * 60 * 1000),
+					fingerprint: None,
+					hosted_id: None,
+					subtitles: SubtitlesResponse::default(),
 					playback_skipped: false,
 					playback_state: PlaybackStateResponse::Paused {
 						position_in_milliseconds: uint!(0),
@@ -221,6 +624,12 @@ mod test {
     "type": "fixed_length",
     "name": "The Acorn",
     "length_in_milliseconds": 1200000,
+    "fingerprint": null,
+    "hosted_id": null,
+    "subtitles": {
+      "tracks": [],
+      "offset_in_milliseconds": 0
+    },
     "playback_skipped": false,
     "playback_state": {
       "type": "paused",
@@ -243,11 +652,14 @@ mod test {
 	fn medium_state_changed_broadcast_for_playing_should_serialize_and_deserialize() {
 		let medium_state_changed_broadcast = BroadcastMessage::MediumStateChanged(MediumStateChangedBroadcast {
 			changed_by_name: "Alice".to_string(),
-			changed_by_id: SessionId::from(0),
+			changed_by_id: Some(SessionId::from(0)),
 			medium: VersionedMediumBroadcast {
 				medium: MediumBroadcast::FixedLength {
 					name: "Metropolis".to_string(),
 					length_in_milliseconds: UInt::from(153u32 * 60 * 1000),
+					fingerprint: None,
+					hosted_id: None,
+					subtitles: SubtitlesResponse::default(),
 					playback_skipped: false,
 					playback_state: PlaybackStateResponse::Playing {
 						start_time_in_milliseconds: int!(-1337),
@@ -268,6 +680,12 @@ mod test {
     "type": "fixed_length",
     "name": "Metropolis",
     "length_in_milliseconds": 9180000,
+    "fingerprint": null,
+    "hosted_id": null,
+    "subtitles": {
+      "tracks": [],
+      "offset_in_milliseconds": 0
+    },
     "playback_skipped": false,
     "playback_state": {
       "type": "playing",
@@ -285,4 +703,185 @@ mod test {
 			deserialized_medium_state_changed_broadcast
 		);
 	}
+
+	#[test]
+	fn medium_state_changed_broadcast_by_server_should_serialize_and_deserialize() {
+		let medium_state_changed_broadcast =
+			BroadcastMessage::MediumStateChanged(MediumStateChangedBroadcast::by_server(VersionedMediumBroadcast {
+				medium: MediumBroadcast::Empty,
+				version: uint!(1),
+			}));
+		let json = serde_json::to_string(&medium_state_changed_broadcast)
+			.expect("Failed to serialize MediumStateChanged broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"medium_state_changed","changed_by_name":"Server","changed_by_id":null,"medium":{"version":1,"type":"empty"}}"#,
+			json
+		);
+
+		let deserialized_medium_state_changed_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize MediumStateChanged broadcast from JSON");
+		assert_eq!(
+			medium_state_changed_broadcast,
+			deserialized_medium_state_changed_broadcast
+		);
+	}
+
+	#[test]
+	fn medium_state_changed_broadcast_for_live_medium_should_serialize_and_deserialize() {
+		let medium_state_changed_broadcast = BroadcastMessage::MediumStateChanged(MediumStateChangedBroadcast {
+			changed_by_name: "Alice".to_string(),
+			changed_by_id: Some(SessionId::from(0)),
+			medium: VersionedMediumBroadcast {
+				medium: MediumBroadcast::Live {
+					name: "Rocket Launch".to_string(),
+					subtitles: SubtitlesResponse::default(),
+					playback_skipped: false,
+					playback_state: PlaybackStateResponse::Paused {
+						position_in_milliseconds: uint!(0),
+					},
+				},
+				version: uint!(1),
+			},
+		});
+		let json = serde_json::to_string(&medium_state_changed_broadcast)
+			.expect("Failed to serialize MediumStateChanged broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"medium_state_changed","changed_by_name":"Alice","changed_by_id":0,"medium":{"version":1,"type":"live","name":"Rocket Launch","subtitles":{"tracks":[],"offset_in_milliseconds":0},"playback_skipped":false,"playback_state":{"type":"paused","position_in_milliseconds":0}}}"#,
+			json
+		);
+
+		let deserialized_medium_state_changed_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize MediumStateChanged broadcast from JSON");
+		assert_eq!(
+			medium_state_changed_broadcast,
+			deserialized_medium_state_changed_broadcast
+		);
+	}
+
+	#[test]
+	fn playback_scheduled_broadcast_should_serialize_and_deserialize() {
+		let playback_scheduled_broadcast = BroadcastMessage::PlaybackScheduled(PlaybackScheduledBroadcast {
+			changed_by_name: "Alice".to_string(),
+			changed_by_id: Some(SessionId::from(0)),
+			medium_version: uint!(3),
+			scheduled_time_in_milliseconds: Some(uint!(72_000_000)),
+		});
+		let json = serde_json::to_string(&playback_scheduled_broadcast)
+			.expect("Failed to serialize PlaybackScheduled broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"playback_scheduled","changed_by_name":"Alice","changed_by_id":0,"medium_version":3,"scheduled_time_in_milliseconds":72000000}"#,
+			json
+		);
+
+		let deserialized_playback_scheduled_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize PlaybackScheduled broadcast from JSON");
+		assert_eq!(playback_scheduled_broadcast, deserialized_playback_scheduled_broadcast);
+	}
+
+	#[test]
+	fn bookmarks_changed_broadcast_should_serialize_and_deserialize() {
+		let bookmarks_changed_broadcast = BroadcastMessage::BookmarksChanged(BookmarksChangedBroadcast {
+			changed_by_name: "Alice".to_string(),
+			changed_by_id: SessionId::from(0),
+			medium_version: uint!(3),
+			bookmarks: vec![BookmarkResponse {
+				id: "9ee7f1c8-5d58-4b0e-9a3d-2c4a3c0f8a51".to_string(),
+				label: "Liftoff".to_string(),
+				position_in_milliseconds: 1337,
+			}],
+		});
+		let json = serde_json::to_string(&bookmarks_changed_broadcast)
+			.expect("Failed to serialize BookmarksChanged broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"bookmarks_changed","changed_by_name":"Alice","changed_by_id":0,"medium_version":3,"bookmarks":[{"id":"9ee7f1c8-5d58-4b0e-9a3d-2c4a3c0f8a51","label":"Liftoff","position_in_milliseconds":1337}]}"#,
+			json
+		);
+
+		let deserialized_bookmarks_changed_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize BookmarksChanged broadcast from JSON");
+		assert_eq!(bookmarks_changed_broadcast, deserialized_bookmarks_changed_broadcast);
+	}
+
+	#[test]
+	fn subtitles_changed_broadcast_should_serialize_and_deserialize() {
+		let subtitles_changed_broadcast = BroadcastMessage::SubtitlesChanged(SubtitlesChangedBroadcast {
+			changed_by_name: "Alice".to_string(),
+			changed_by_id: SessionId::from(0),
+			medium_version: uint!(3),
+			subtitles: SubtitlesResponse {
+				tracks: vec![SubtitleTrackResponse {
+					id: "5d2f0b6c3e8a4f1d9b7c6a5e4d3c2b1a".to_string(),
+					label: "Deutsch".to_string(),
+					language: Some("de".to_string()),
+				}],
+				offset_in_milliseconds: int!(-1500),
+			},
+		});
+		let json = serde_json::to_string(&subtitles_changed_broadcast)
+			.expect("Failed to serialize SubtitlesChanged broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"subtitles_changed","changed_by_name":"Alice","changed_by_id":0,"medium_version":3,"subtitles":{"tracks":[{"id":"5d2f0b6c3e8a4f1d9b7c6a5e4d3c2b1a","label":"Deutsch","language":"de"}],"offset_in_milliseconds":-1500}}"#,
+			json
+		);
+
+		let deserialized_subtitles_changed_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize SubtitlesChanged broadcast from JSON");
+		assert_eq!(subtitles_changed_broadcast, deserialized_subtitles_changed_broadcast);
+	}
+
+	#[test]
+	fn poll_broadcast_should_serialize_and_deserialize() {
+		let poll_broadcast = BroadcastMessage::Poll(PollBroadcast {
+			id: "0f3c2a8e-6b1d-4c5e-8a7f-9d2b1e4c6a30".to_string(),
+			kind: PollKindBroadcast::Skip,
+			question: "Skip the current medium?".to_string(),
+			options: vec![
+				PollOptionBroadcast {
+					label: "Yes".to_string(),
+					votes: uint!(2),
+				},
+				PollOptionBroadcast {
+					label: "No".to_string(),
+					votes: uint!(1),
+				},
+			],
+			created_by_name: "Alice".to_string(),
+			medium_version: Some(uint!(3)),
+			deadline_in_milliseconds: uint!(60_000),
+			closed: false,
+		});
+		let json = serde_json::to_string(&poll_broadcast).expect("Failed to serialize Poll broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"poll","id":"0f3c2a8e-6b1d-4c5e-8a7f-9d2b1e4c6a30","kind":"skip","question":"Skip the current medium?","options":[{"label":"Yes","votes":2},{"label":"No","votes":1}],"created_by_name":"Alice","medium_version":3,"deadline_in_milliseconds":60000,"closed":false}"#,
+			json
+		);
+
+		let deserialized_poll_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize Poll broadcast from JSON");
+		assert_eq!(poll_broadcast, deserialized_poll_broadcast);
+	}
+
+	#[test]
+	fn fingerprint_mismatch_broadcast_should_serialize_and_deserialize() {
+		let fingerprint_mismatch_broadcast = BroadcastMessage::FingerprintMismatch(FingerprintMismatchBroadcast {
+			medium_version: uint!(3),
+			mismatching_clients: vec![MismatchingClient {
+				id: SessionId::from(42),
+				name: "Bob".to_string(),
+			}],
+		});
+		let json = serde_json::to_string(&fingerprint_mismatch_broadcast)
+			.expect("Failed to serialize FingerprintMismatch broadcast to JSON");
+		assert_eq!(
+			r#"{"type":"fingerprint_mismatch","medium_version":3,"mismatching_clients":[{"id":42,"name":"Bob"}]}"#,
+			json
+		);
+
+		let deserialized_fingerprint_mismatch_broadcast: BroadcastMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize FingerprintMismatch broadcast from JSON");
+		assert_eq!(
+			fingerprint_mismatch_broadcast,
+			deserialized_fingerprint_mismatch_broadcast
+		);
+	}
 }
@@ -16,6 +16,11 @@ pub enum ErrorMessageType {
 	InternalServerError,
 	IncorrectMediumVersion,
 	EmptyChatMessage,
+	ChatMessageTooLong,
+	ChatMessageFiltered,
+	MediumHostNotAllowed,
+	NotAllowed,
+	RateLimited,
 }
 
 #[cfg(test)]
@@ -97,4 +102,46 @@ mod test {
 			deserialized_internal_server_error_error_message
 		);
 	}
+
+	#[test]
+	fn chat_message_too_long_error_message_should_serialize_and_deserialize() {
+		let chat_message_too_long_error_message = ErrorMessage::builder()
+			.error(ErrorMessageType::ChatMessageTooLong)
+			.message("Chat messages must not be longer than 2048 bytes!".to_string())
+			.build();
+		let json = serde_json::to_string(&chat_message_too_long_error_message)
+			.expect("Failed to serialize ChatMessageTooLong error message to JSON");
+		assert_eq!(
+			r#"{"error":"chat_message_too_long","message":"Chat messages must not be longer than 2048 bytes!"}"#,
+			json
+		);
+
+		let deserialized_chat_message_too_long_error_message: ErrorMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize ChatMessageTooLong error message from JSON");
+		assert_eq!(
+			chat_message_too_long_error_message,
+			deserialized_chat_message_too_long_error_message
+		);
+	}
+
+	#[test]
+	fn medium_host_not_allowed_error_message_should_serialize_and_deserialize() {
+		let medium_host_not_allowed_error_message = ErrorMessage::builder()
+			.error(ErrorMessageType::MediumHostNotAllowed)
+			.message("Media from 'evil.org' are not allowed.".to_string())
+			.build();
+		let json = serde_json::to_string(&medium_host_not_allowed_error_message)
+			.expect("Failed to serialize MediumHostNotAllowed error message to JSON");
+		assert_eq!(
+			r#"{"error":"medium_host_not_allowed","message":"Media from 'evil.org' are not allowed."}"#,
+			json
+		);
+
+		let deserialized_medium_host_not_allowed_error_message: ErrorMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize MediumHostNotAllowed error message from JSON");
+		assert_eq!(
+			medium_host_not_allowed_error_message,
+			deserialized_medium_host_not_allowed_error_message
+		);
+	}
 }
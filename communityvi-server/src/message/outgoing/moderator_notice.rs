@@ -0,0 +1,27 @@
+use crate::room::session_id::SessionId;
+use serde::{Deserialize, Serialize};
+
+/// Only delivered to moderators, see `moderators` in the configuration.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum ModeratorNotice {
+	ChatFlagged(ChatFlaggedNotice),
+}
+
+/// A chat message that was delivered, but violated chat filters with the `flag` action.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ChatFlaggedNotice {
+	/// Id of the chat message, missing for chat commands like `/me`.
+	pub id: Option<String>,
+	pub sender_id: SessionId,
+	pub sender_name: String,
+	pub message: String,
+	pub flagged_by: Vec<String>,
+}
+
+impl From<ChatFlaggedNotice> for ModeratorNotice {
+	fn from(notice: ChatFlaggedNotice) -> Self {
+		ModeratorNotice::ChatFlagged(notice)
+	}
+}
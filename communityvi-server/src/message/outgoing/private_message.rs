@@ -0,0 +1,12 @@
+use crate::room::session_id::SessionId;
+use serde::{Deserialize, Serialize};
+
+/// Only delivered to the sender and the recipient, never broadcast to the room.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PrivateMessage {
+	pub sender_id: SessionId,
+	pub sender_name: String,
+	pub recipient_id: SessionId,
+	pub recipient_name: String,
+	pub message: String,
+}
@@ -1,10 +1,16 @@
 use js_int::{Int, UInt};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::bookmark::model::Bookmark;
+use crate::chat::model::{ChatMessage, ChatMessageKind};
 use crate::room::client::Client;
+use crate::room::medium::fingerprint::Fingerprint;
 use crate::room::medium::playback_state::PlaybackState;
+use crate::room::medium::subtitles::{SubtitleTrack, Subtitles};
 use crate::room::medium::{Medium, VersionedMedium};
 use crate::room::session_id::SessionId;
+use crate::user::model::{Avatar, Presence, Profile};
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(tag = "type")]
@@ -12,23 +18,95 @@ use crate::room::session_id::SessionId;
 pub enum SuccessMessage {
 	Hello {
 		id: SessionId,
+		/// Authenticates REST requests like subtitle uploads as `Authorization: Bearer <session_token>`.
+		session_token: String,
 		clients: Vec<ClientResponse>,
 		current_medium: VersionedMediumResponse,
 	},
+	/// The client has drifted too far from the expected position and should resync to `current_medium`.
+	Resync {
+		drift_in_milliseconds: Int,
+		current_medium: VersionedMediumResponse,
+	},
+	TimeSync(TimeSyncResponse),
+	/// Bookmarks of the medium with `medium_version`, ordered by position.
+	Bookmarks {
+		medium_version: UInt,
+		bookmarks: Vec<BookmarkResponse>,
+	},
+	/// Chat messages and reactions of the medium with `medium_version`, ordered by position.
+	ReactionsForMedium {
+		medium_version: UInt,
+		reactions: Vec<MediumReactionResponse>,
+	},
+	/// Reply to a chat command that is only meant for the client that used it.
+	CommandReply {
+		message: String,
+	},
 	Success,
 }
 
+/// Like in NTP, the client's originate timestamp is returned together with the reference times
+/// the request was received and the response was sent. Also returned by the REST API.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[allow(clippy::struct_field_names)]
+pub struct TimeSyncResponse {
+	/// `None` if the REST API was called without an originate timestamp.
+	#[schemars(with = "Option<u64>")]
+	pub originate_timestamp_in_milliseconds: Option<UInt>,
+	#[schemars(with = "u64")]
+	pub receive_timestamp_in_milliseconds: UInt,
+	#[schemars(with = "u64")]
+	pub transmit_timestamp_in_milliseconds: UInt,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct ClientResponse {
 	pub id: SessionId,
 	pub name: String,
+	pub drift_in_milliseconds: Option<Int>,
+	pub accepts_private_messages: bool,
+	#[serde(flatten)]
+	pub profile: ProfileResponse,
 }
 
-impl From<Client> for ClientResponse {
-	fn from(client: Client) -> Self {
+impl ClientResponse {
+	/// Only includes the drift if it was reported for `medium_version`.
+	pub fn new(client: &Client, medium_version: UInt) -> Self {
 		Self {
 			id: client.id(),
-			name: client.name().to_string(),
+			name: client.name(),
+			drift_in_milliseconds: client
+				.drift(medium_version)
+				.map(|drift| Int::new_saturating(drift.num_milliseconds())),
+			accepts_private_messages: client.accepts_private_messages(),
+			profile: client.user().profile.into(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ProfileResponse {
+	pub presence: Presence,
+	pub color: Option<String>,
+	pub avatar: Option<Avatar>,
+	pub pronouns: Option<String>,
+}
+
+impl From<Profile> for ProfileResponse {
+	fn from(
+		Profile {
+			presence,
+			color,
+			avatar,
+			pronouns,
+		}: Profile,
+	) -> Self {
+		Self {
+			presence,
+			color,
+			avatar,
+			pronouns,
 		}
 	}
 }
@@ -38,6 +116,17 @@ pub struct VersionedMediumResponse {
 	pub version: UInt,
 	#[serde(flatten)]
 	pub medium: MediumResponse,
+	pub bookmarks: Vec<BookmarkResponse>,
+}
+
+impl VersionedMediumResponse {
+	pub fn new(versioned_medium: VersionedMedium, bookmarks: Vec<Bookmark>) -> Self {
+		Self {
+			medium: versioned_medium.medium.into(),
+			version: versioned_medium.version,
+			bookmarks: bookmarks.into_iter().map(Into::into).collect(),
+		}
+	}
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -47,11 +136,122 @@ pub enum MediumResponse {
 	FixedLength {
 		name: String,
 		length_in_milliseconds: u64,
+		fingerprint: Option<FingerprintResponse>,
+		hosted_id: Option<String>,
+		subtitles: SubtitlesResponse,
+		playback_state: PlaybackStateResponse,
+	},
+	Url {
+		url: String,
+		mime_type: Option<String>,
+		length_in_milliseconds: Option<u64>,
+		subtitles: SubtitlesResponse,
+		playback_state: PlaybackStateResponse,
+	},
+	Live {
+		name: String,
+		subtitles: SubtitlesResponse,
 		playback_state: PlaybackStateResponse,
 	},
 	Empty,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct FingerprintResponse {
+	pub size_in_bytes: u64,
+	pub hash: String,
+}
+
+impl From<Fingerprint> for FingerprintResponse {
+	fn from(Fingerprint { size_in_bytes, hash }: Fingerprint) -> Self {
+		Self { size_in_bytes, hash }
+	}
+}
+
+/// Subtitle tracks can be downloaded as `WebVTT` from `/api/subtitles/{id}`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SubtitlesResponse {
+	pub tracks: Vec<SubtitleTrackResponse>,
+	pub offset_in_milliseconds: Int,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SubtitleTrackResponse {
+	pub id: String,
+	pub label: String,
+	pub language: Option<String>,
+}
+
+impl From<Subtitles> for SubtitlesResponse {
+	fn from(Subtitles { tracks, offset }: Subtitles) -> Self {
+		Self {
+			tracks: tracks
+				.into_iter()
+				.map(
+					|SubtitleTrack {
+					     id, label, language, ..
+					 }| SubtitleTrackResponse { id, label, language },
+				)
+				.collect(),
+			offset_in_milliseconds: Int::new_saturating(offset.num_milliseconds()),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BookmarkResponse {
+	pub id: String,
+	pub label: String,
+	pub position_in_milliseconds: u64,
+}
+
+impl From<Bookmark> for BookmarkResponse {
+	fn from(bookmark: Bookmark) -> Self {
+		Self {
+			id: bookmark.uuid.to_string(),
+			label: bookmark.label,
+			position_in_milliseconds: u64::try_from(bookmark.position.num_milliseconds()).unwrap_or_default(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct MediumReactionResponse {
+	pub kind: ChatKindResponse,
+	pub sender_name: String,
+	pub message: String,
+	pub position_in_milliseconds: UInt,
+}
+
+impl MediumReactionResponse {
+	/// `None` if the message isn't anchored to a medium.
+	pub fn new(chat_message: ChatMessage) -> Option<Self> {
+		let medium_anchor = chat_message.content.medium_anchor?;
+		Some(Self {
+			kind: chat_message.content.kind.into(),
+			sender_name: chat_message.user_name,
+			message: chat_message.content.message,
+			position_in_milliseconds: UInt::try_from(medium_anchor.position.num_milliseconds()).unwrap_or_default(),
+		})
+	}
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatKindResponse {
+	Message,
+	Reaction,
+}
+
+impl From<ChatMessageKind> for ChatKindResponse {
+	fn from(kind: ChatMessageKind) -> Self {
+		match kind {
+			ChatMessageKind::Message => Self::Message,
+			ChatMessageKind::Reaction => Self::Reaction,
+		}
+	}
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "type")]
@@ -73,44 +273,54 @@ impl From<PlaybackState> for PlaybackStateResponse {
 	}
 }
 
-impl From<VersionedMedium> for VersionedMediumResponse {
-	fn from(versioned_medium: VersionedMedium) -> Self {
-		Self {
-			medium: versioned_medium.medium.into(),
-			version: versioned_medium.version,
-		}
-	}
-}
-
 impl From<Medium> for MediumResponse {
 	fn from(medium: Medium) -> Self {
 		match medium {
 			Medium::FixedLength(fixed_length) => MediumResponse::FixedLength {
 				name: fixed_length.name,
 				length_in_milliseconds: u64::try_from(fixed_length.length.num_milliseconds()).unwrap(),
+				fingerprint: fixed_length.fingerprint.map(Into::into),
+				hosted_id: fixed_length.hosted_id,
+				subtitles: fixed_length.subtitles.into(),
 				playback_state: fixed_length.playback.into(),
 			},
+			Medium::Url(url_medium) => MediumResponse::Url {
+				url: url_medium.url.into(),
+				mime_type: url_medium.mime_type.map(|mime_type| mime_type.to_string()),
+				length_in_milliseconds: url_medium
+					.length
+					.map(|length| u64::try_from(length.num_milliseconds()).unwrap()),
+				subtitles: url_medium.subtitles.into(),
+				playback_state: url_medium.playback.into(),
+			},
+			Medium::Live(live_medium) => MediumResponse::Live {
+				name: live_medium.name,
+				subtitles: live_medium.subtitles.into(),
+				playback_state: live_medium.playback.into(),
+			},
 			Medium::Empty => MediumResponse::Empty,
 		}
 	}
 }
 
 #[cfg(test)]
+#[allow(clippy::non_ascii_literal)]
 mod test {
 	use super::*;
 	use chrono::Duration;
-	use js_int::uint;
+	use js_int::{int, uint};
 
 	#[test]
 	fn hello_response_without_medium_should_serialize_and_deserialize() {
 		let hello_response = SuccessMessage::Hello {
 			id: 42.into(),
+			session_token: "c5b3b1c9-6a35-4e8f-9a54-1d0f4e2f3c7a".to_string(),
 			clients: vec![],
-			current_medium: VersionedMedium::default().into(),
+			current_medium: VersionedMediumResponse::new(VersionedMedium::default(), Vec::new()),
 		};
 		let json = serde_json::to_string(&hello_response).expect("Failed to serialize Hello response to JSON");
 		assert_eq!(
-			r#"{"type":"hello","id":42,"clients":[],"current_medium":{"version":0,"type":"empty"}}"#,
+			r#"{"type":"hello","id":42,"session_token":"c5b3b1c9-6a35-4e8f-9a54-1d0f4e2f3c7a","clients":[],"current_medium":{"version":0,"type":"empty","bookmarks":[]}}"#,
 			json
 		);
 
@@ -123,19 +333,36 @@ mod test {
 	fn hello_response_with_medium_should_serialize_and_deserialize() {
 		let hello_response = SuccessMessage::Hello {
 			id: 42.into(),
+			session_token: "c5b3b1c9-6a35-4e8f-9a54-1d0f4e2f3c7a".to_string(),
 			clients: vec![ClientResponse {
 				id: SessionId::from(8080),
 				name: "IMSAI".to_string(),
+				drift_in_milliseconds: Some(int!(-42)),
+				accepts_private_messages: true,
+				profile: ProfileResponse {
+					presence: Presence::Away,
+					color: None,
+					avatar: Some(Avatar::Robot),
+					pronouns: Some("it/its".to_string()),
+				},
 			}],
 			current_medium: VersionedMediumResponse {
 				medium: MediumResponse::FixedLength {
 					name: "WarGames".to_string(),
 					length_in_milliseconds: u64::try_from(Duration::minutes(114).num_milliseconds()).unwrap(),
+					fingerprint: None,
+					hosted_id: None,
+					subtitles: SubtitlesResponse::default(),
 					playback_state: PlaybackStateResponse::Paused {
 						position_in_milliseconds: uint!(0),
 					},
 				},
 				version: uint!(0),
+				bookmarks: vec![BookmarkResponse {
+					id: "9ee7f1c8-5d58-4b0e-9a3d-2c4a3c0f8a51".to_string(),
+					label: "Shall we play a game?".to_string(),
+					position_in_milliseconds: 3_180_000,
+				}],
 			},
 		};
 		let json = serde_json::to_string_pretty(&hello_response).expect("Failed to serialize Hello response to JSON");
@@ -143,10 +370,17 @@ mod test {
 			r#"{
   "type": "hello",
   "id": 42,
+  "session_token": "c5b3b1c9-6a35-4e8f-9a54-1d0f4e2f3c7a",
   "clients": [
     {
       "id": 8080,
-      "name": "IMSAI"
+      "name": "IMSAI",
+      "drift_in_milliseconds": -42,
+      "accepts_private_messages": true,
+      "presence": "away",
+      "color": null,
+      "avatar": "robot",
+      "pronouns": "it/its"
     }
   ],
   "current_medium": {
@@ -154,10 +388,23 @@ mod test {
     "type": "fixed_length",
     "name": "WarGames",
     "length_in_milliseconds": 6840000,
+    "fingerprint": null,
+    "hosted_id": null,
+    "subtitles": {
+      "tracks": [],
+      "offset_in_milliseconds": 0
+    },
     "playback_state": {
       "type": "paused",
       "position_in_milliseconds": 0
-    }
+    },
+    "bookmarks": [
+      {
+        "id": "9ee7f1c8-5d58-4b0e-9a3d-2c4a3c0f8a51",
+        "label": "Shall we play a game?",
+        "position_in_milliseconds": 3180000
+      }
+    ]
   }
 }"#,
 			json
@@ -178,4 +425,118 @@ mod test {
 			serde_json::from_str(&json).expect("Failed to deserialize Success response from JSON");
 		assert_eq!(success_response, deserialized_success_response);
 	}
+
+	#[test]
+	fn command_reply_response_should_serialize_and_deserialize() {
+		let command_reply_response = SuccessMessage::CommandReply {
+			message: "Kicked Mallory.".to_string(),
+		};
+		let json =
+			serde_json::to_string(&command_reply_response).expect("Failed to serialize CommandReply response to JSON");
+		assert_eq!(r#"{"type":"command_reply","message":"Kicked Mallory."}"#, json);
+
+		let deserialized_command_reply_response: SuccessMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize CommandReply response from JSON");
+		assert_eq!(command_reply_response, deserialized_command_reply_response);
+	}
+
+	#[test]
+	fn bookmarks_response_should_serialize_and_deserialize() {
+		let bookmarks_response = SuccessMessage::Bookmarks {
+			medium_version: uint!(2),
+			bookmarks: vec![BookmarkResponse {
+				id: "9ee7f1c8-5d58-4b0e-9a3d-2c4a3c0f8a51".to_string(),
+				label: "Tower of Babel".to_string(),
+				position_in_milliseconds: 60_000,
+			}],
+		};
+		let json = serde_json::to_string(&bookmarks_response).expect("Failed to serialize Bookmarks response to JSON");
+		assert_eq!(
+			r#"{"type":"bookmarks","medium_version":2,"bookmarks":[{"id":"9ee7f1c8-5d58-4b0e-9a3d-2c4a3c0f8a51","label":"Tower of Babel","position_in_milliseconds":60000}]}"#,
+			json
+		);
+
+		let deserialized_bookmarks_response: SuccessMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize Bookmarks response from JSON");
+		assert_eq!(bookmarks_response, deserialized_bookmarks_response);
+	}
+
+	#[test]
+	fn reactions_for_medium_response_should_serialize_and_deserialize() {
+		let reactions_response = SuccessMessage::ReactionsForMedium {
+			medium_version: uint!(2),
+			reactions: vec![
+				MediumReactionResponse {
+					kind: ChatKindResponse::Reaction,
+					sender_name: "Alice".to_string(),
+					message: "😱".to_string(),
+					position_in_milliseconds: uint!(60_000),
+				},
+				MediumReactionResponse {
+					kind: ChatKindResponse::Message,
+					sender_name: "Bob".to_string(),
+					message: "Didn't see that coming".to_string(),
+					position_in_milliseconds: uint!(61_500),
+				},
+			],
+		};
+		let json = serde_json::to_string(&reactions_response)
+			.expect("Failed to serialize ReactionsForMedium response to JSON");
+		assert_eq!(
+			r#"{"type":"reactions_for_medium","medium_version":2,"reactions":[{"kind":"reaction","sender_name":"Alice","message":"😱","position_in_milliseconds":60000},{"kind":"message","sender_name":"Bob","message":"Didn't see that coming","position_in_milliseconds":61500}]}"#,
+			json
+		);
+
+		let deserialized_reactions_response: SuccessMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize ReactionsForMedium response from JSON");
+		assert_eq!(reactions_response, deserialized_reactions_response);
+	}
+
+	#[test]
+	fn resync_response_should_serialize_and_deserialize() {
+		let resync_response = SuccessMessage::Resync {
+			drift_in_milliseconds: int!(-1337),
+			current_medium: VersionedMediumResponse {
+				medium: MediumResponse::FixedLength {
+					name: "Metropolis".to_string(),
+					length_in_milliseconds: 9_180_000,
+					fingerprint: None,
+					hosted_id: None,
+					subtitles: SubtitlesResponse::default(),
+					playback_state: PlaybackStateResponse::Playing {
+						start_time_in_milliseconds: int!(-42),
+					},
+				},
+				version: uint!(3),
+				bookmarks: vec![],
+			},
+		};
+		let json = serde_json::to_string(&resync_response).expect("Failed to serialize Resync response to JSON");
+		assert_eq!(
+			r#"{"type":"resync","drift_in_milliseconds":-1337,"current_medium":{"version":3,"type":"fixed_length","name":"Metropolis","length_in_milliseconds":9180000,"fingerprint":null,"hosted_id":null,"subtitles":{"tracks":[],"offset_in_milliseconds":0},"playback_state":{"type":"playing","start_time_in_milliseconds":-42},"bookmarks":[]}}"#,
+			json
+		);
+
+		let deserialized_resync_response: SuccessMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize Resync response from JSON");
+		assert_eq!(resync_response, deserialized_resync_response);
+	}
+
+	#[test]
+	fn time_sync_response_should_serialize_and_deserialize() {
+		let time_sync_response = SuccessMessage::TimeSync(TimeSyncResponse {
+			originate_timestamp_in_milliseconds: Some(uint!(1)),
+			receive_timestamp_in_milliseconds: uint!(1337),
+			transmit_timestamp_in_milliseconds: uint!(1338),
+		});
+		let json = serde_json::to_string(&time_sync_response).expect("Failed to serialize TimeSync response to JSON");
+		assert_eq!(
+			r#"{"type":"time_sync","originate_timestamp_in_milliseconds":1,"receive_timestamp_in_milliseconds":1337,"transmit_timestamp_in_milliseconds":1338}"#,
+			json
+		);
+
+		let deserialized_time_sync_response: SuccessMessage =
+			serde_json::from_str(&json).expect("Failed to deserialize TimeSync response from JSON");
+		assert_eq!(time_sync_response, deserialized_time_sync_response);
+	}
 }
@@ -0,0 +1,143 @@
+use crate::types::date_time::DateTime;
+use crate::types::uuid::Uuid;
+use anyhow::anyhow;
+use js_int::UInt;
+
+/// Index of the "Yes" option of polls that vote on the medium.
+pub const YES_OPTION: u32 = 0;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Poll {
+	pub uuid: Uuid,
+	pub room_uuid: Uuid,
+	pub kind: PollKind,
+	pub question: String,
+	pub created_by_user_uuid: Option<Uuid>,
+	pub created_by_name: String,
+	/// The medium version a skip or pause poll applies to, `None` for custom polls.
+	pub medium_version: Option<UInt>,
+	pub deadline: DateTime,
+	/// `None` while the poll is open.
+	pub closed_at: Option<DateTime>,
+	pub created_at: DateTime,
+}
+
+impl Poll {
+	pub fn is_closed(&self) -> bool {
+		self.closed_at.is_some()
+	}
+}
+
+/// What a poll is about, the medium polls do something with the medium once enough clients voted "Yes".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollKind {
+	Custom,
+	/// Eject the medium.
+	Skip,
+	/// Pause the medium.
+	Pause,
+}
+
+impl PollKind {
+	pub fn as_str(self) -> &'static str {
+		match self {
+			PollKind::Custom => "custom",
+			PollKind::Skip => "skip",
+			PollKind::Pause => "pause",
+		}
+	}
+}
+
+impl TryFrom<&str> for PollKind {
+	type Error = anyhow::Error;
+
+	fn try_from(kind: &str) -> Result<Self, Self::Error> {
+		match kind {
+			"custom" => Ok(PollKind::Custom),
+			"skip" => Ok(PollKind::Skip),
+			"pause" => Ok(PollKind::Pause),
+			_ => Err(anyhow!("Unknown poll kind '{kind}'")),
+		}
+	}
+}
+
+/// Everything needed to create a poll.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewPoll {
+	pub kind: PollKind,
+	pub question: String,
+	pub options: Vec<String>,
+	pub medium_version: Option<UInt>,
+	pub deadline: DateTime,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PollOption {
+	pub label: String,
+	pub votes: u32,
+}
+
+/// A poll together with its options and how many votes each of them got, ordered by option index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PollResults {
+	pub poll: Poll,
+	pub options: Vec<PollOption>,
+}
+
+impl PollResults {
+	pub fn votes_for(&self, option_index: u32) -> u32 {
+		usize::try_from(option_index)
+			.ok()
+			.and_then(|index| self.options.get(index))
+			.map_or(0, |option| option.votes)
+	}
+}
+
+impl TryFrom<libsql::Row> for Poll {
+	type Error = anyhow::Error;
+
+	fn try_from(row: libsql::Row) -> Result<Self, Self::Error> {
+		let uuid = row.get_value(0)?;
+		let room_uuid = row.get_value(1)?;
+		let kind = row.get::<String>(2)?;
+		let question = row.get(3)?;
+		let created_by_user_uuid = row.get_value(4)?;
+		let created_by_name = row.get(5)?;
+		let medium_version = row.get::<Option<i64>>(6)?;
+		let deadline = row.get_value(7)?;
+		let closed_at = row.get_value(8)?;
+		let created_at = row.get_value(9)?;
+
+		Ok(Self {
+			uuid: uuid.try_into()?,
+			room_uuid: room_uuid.try_into()?,
+			kind: kind.as_str().try_into()?,
+			question,
+			created_by_user_uuid: if created_by_user_uuid.is_null() {
+				None
+			} else {
+				Some(created_by_user_uuid.try_into()?)
+			},
+			created_by_name,
+			medium_version: medium_version.map(UInt::try_from).transpose()?,
+			deadline: deadline.try_into()?,
+			closed_at: if closed_at.is_null() {
+				None
+			} else {
+				Some(closed_at.try_into()?)
+			},
+			created_at: created_at.try_into()?,
+		})
+	}
+}
+
+impl TryFrom<libsql::Row> for PollOption {
+	type Error = anyhow::Error;
+
+	fn try_from(row: libsql::Row) -> Result<Self, Self::Error> {
+		let label = row.get(0)?;
+		let votes = row.get::<u32>(1)?;
+
+		Ok(Self { label, votes })
+	}
+}
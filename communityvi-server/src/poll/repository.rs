@@ -0,0 +1,55 @@
+use crate::database::Connection;
+use crate::database::error::DatabaseError;
+use crate::poll::model;
+use crate::types::date_time::DateTime;
+use crate::types::uuid::Uuid;
+use async_trait::async_trait;
+use static_assertions::assert_obj_safe;
+
+#[cfg(test)]
+mod tests;
+
+#[async_trait]
+pub trait PollRepository: Send + Sync + 'static {
+	async fn create(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		user_uuid: Uuid,
+		user_name: String,
+		new_poll: model::NewPoll,
+		created_at: DateTime,
+	) -> Result<model::PollResults, DatabaseError>;
+	/// Returns `None` if the room has no poll with `poll_uuid`.
+	async fn get(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		poll_uuid: Uuid,
+	) -> Result<Option<model::PollResults>, DatabaseError>;
+	/// Returns the polls of the room that haven't been closed yet, oldest first.
+	async fn get_open(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+	) -> Result<Vec<model::Poll>, DatabaseError>;
+	/// Casts the vote of `voter_user_uuid`, replacing any vote they had cast before.
+	async fn vote(
+		&self,
+		connection: &mut dyn Connection,
+		poll_uuid: Uuid,
+		voter_user_uuid: Uuid,
+		option_index: u32,
+		voted_at: DateTime,
+	) -> Result<(), DatabaseError>;
+	/// Returns `false` if there was no such poll in the room or it had already been closed.
+	async fn close(
+		&self,
+		connection: &mut dyn Connection,
+		room_uuid: Uuid,
+		poll_uuid: Uuid,
+		closed_at: DateTime,
+	) -> Result<bool, DatabaseError>;
+}
+
+assert_obj_safe!(PollRepository);
@@ -0,0 +1,245 @@
+#[generic_tests::define(attrs(tokio::test))]
+mod poll_tests {
+	use crate::database::libsql::test_utils::LibSqlTestFactory;
+	use crate::database::test::TestFactory;
+	use crate::database::{Connection, Repository};
+	use crate::poll::model::{NewPoll, Poll, PollKind, PollOption, PollResults};
+	use crate::room::model::Room;
+	use crate::user::model::User;
+	use crate::user::normalize_name;
+	use chrono::{Duration, Utc};
+	use js_int::uint;
+
+	#[tokio::test]
+	async fn creates_poll<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let user = user(repository.as_ref(), &mut *connection, "Alice").await;
+		let room = room(repository.as_ref(), &mut *connection, "cinema").await;
+		let created_at = Utc::now().into();
+		let deadline = (Utc::now() + Duration::minutes(1)).into();
+
+		let PollResults {
+			poll:
+				Poll {
+					uuid,
+					room_uuid,
+					kind,
+					question,
+					created_by_user_uuid,
+					created_by_name,
+					medium_version,
+					deadline: poll_deadline,
+					closed_at,
+					created_at: poll_created_at,
+				},
+			options,
+		} = repository
+			.poll()
+			.create(
+				&mut *connection,
+				room.uuid,
+				user.uuid,
+				user.name.clone(),
+				NewPoll {
+					kind: PollKind::Skip,
+					question: "Skip the current medium?".to_string(),
+					options: vec!["Yes".to_string(), "No".to_string()],
+					medium_version: Some(uint!(3)),
+					deadline,
+				},
+				created_at,
+			)
+			.await
+			.expect("Failed to create poll");
+
+		assert_eq!(4, uuid.get_version_num());
+		assert_eq!(room.uuid, room_uuid);
+		assert_eq!(PollKind::Skip, kind);
+		assert_eq!("Skip the current medium?", question);
+		assert_eq!(Some(user.uuid), created_by_user_uuid);
+		assert_eq!(user.name, created_by_name);
+		assert_eq!(Some(uint!(3)), medium_version);
+		assert_eq!(deadline.timestamp(), poll_deadline.timestamp());
+		assert_eq!(None, closed_at);
+		assert_eq!(created_at.timestamp(), poll_created_at.timestamp());
+		assert_eq!(
+			vec![
+				PollOption {
+					label: "Yes".to_string(),
+					votes: 0
+				},
+				PollOption {
+					label: "No".to_string(),
+					votes: 0
+				},
+			],
+			options
+		);
+	}
+
+	#[tokio::test]
+	async fn counts_votes_and_replaces_changed_votes<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let alice = user(repository.as_ref(), &mut *connection, "Alice").await;
+		let bob = user(repository.as_ref(), &mut *connection, "Bob").await;
+		let room = room(repository.as_ref(), &mut *connection, "cinema").await;
+		let poll = create_poll(repository.as_ref(), &mut *connection, &room, &alice).await;
+
+		for (voter, option_index) in [(&alice, 0), (&bob, 0), (&bob, 2)] {
+			repository
+				.poll()
+				.vote(
+					&mut *connection,
+					poll.poll.uuid,
+					voter.uuid,
+					option_index,
+					Utc::now().into(),
+				)
+				.await
+				.expect("Failed to vote");
+		}
+
+		let votes = repository
+			.poll()
+			.get(&mut *connection, room.uuid, poll.poll.uuid)
+			.await
+			.expect("Failed to get poll")
+			.expect("Poll not found")
+			.options
+			.into_iter()
+			.map(|option| option.votes)
+			.collect::<Vec<_>>();
+		assert_eq!(vec![1, 0, 1], votes);
+	}
+
+	#[tokio::test]
+	async fn gets_poll_only_in_its_room<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let user = user(repository.as_ref(), &mut *connection, "Alice").await;
+		let other_room = room(repository.as_ref(), &mut *connection, "lobby").await;
+		let room = room(repository.as_ref(), &mut *connection, "cinema").await;
+		let poll = create_poll(repository.as_ref(), &mut *connection, &room, &user).await;
+
+		let from_other_room = repository
+			.poll()
+			.get(&mut *connection, other_room.uuid, poll.poll.uuid)
+			.await
+			.expect("Failed to get poll");
+		let from_room = repository
+			.poll()
+			.get(&mut *connection, room.uuid, poll.poll.uuid)
+			.await
+			.expect("Failed to get poll");
+
+		assert_eq!(None, from_other_room);
+		assert_eq!(Some(poll), from_room);
+	}
+
+	#[tokio::test]
+	async fn closes_poll_only_once<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let user = user(repository.as_ref(), &mut *connection, "Alice").await;
+		let room = room(repository.as_ref(), &mut *connection, "cinema").await;
+		let poll = create_poll(repository.as_ref(), &mut *connection, &room, &user).await;
+
+		let first_close = repository
+			.poll()
+			.close(&mut *connection, room.uuid, poll.poll.uuid, Utc::now().into())
+			.await
+			.expect("Failed to close poll");
+		let second_close = repository
+			.poll()
+			.close(&mut *connection, room.uuid, poll.poll.uuid, Utc::now().into())
+			.await
+			.expect("Failed to close poll");
+
+		assert!(first_close);
+		assert!(!second_close);
+		let closed_poll = repository
+			.poll()
+			.get(&mut *connection, room.uuid, poll.poll.uuid)
+			.await
+			.expect("Failed to get poll")
+			.expect("Poll not found");
+		assert!(closed_poll.poll.is_closed());
+	}
+
+	#[tokio::test]
+	async fn gets_only_open_polls_of_the_room<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let user = user(repository.as_ref(), &mut *connection, "Alice").await;
+		let other_room = room(repository.as_ref(), &mut *connection, "lobby").await;
+		let room = room(repository.as_ref(), &mut *connection, "cinema").await;
+		let open_poll = create_poll(repository.as_ref(), &mut *connection, &room, &user).await;
+		let closed_poll = create_poll(repository.as_ref(), &mut *connection, &room, &user).await;
+		create_poll(repository.as_ref(), &mut *connection, &other_room, &user).await;
+		repository
+			.poll()
+			.close(&mut *connection, room.uuid, closed_poll.poll.uuid, Utc::now().into())
+			.await
+			.expect("Failed to close poll");
+
+		let open_polls = repository
+			.poll()
+			.get_open(&mut *connection, room.uuid)
+			.await
+			.expect("Failed to get open polls");
+
+		assert_eq!(vec![open_poll.poll], open_polls);
+	}
+
+	async fn create_poll(
+		repository: &dyn Repository,
+		connection: &mut dyn Connection,
+		room: &Room,
+		user: &User,
+	) -> PollResults {
+		repository
+			.poll()
+			.create(
+				connection,
+				room.uuid,
+				user.uuid,
+				user.name.clone(),
+				NewPoll {
+					kind: PollKind::Custom,
+					question: "What should we watch next?".to_string(),
+					options: vec!["Metropolis".to_string(), "Nosferatu".to_string(), "The Kid".to_string()],
+					medium_version: None,
+					deadline: (Utc::now() + Duration::minutes(5)).into(),
+				},
+				Utc::now().into(),
+			)
+			.await
+			.expect("Failed to create poll")
+	}
+
+	async fn user(repository: &dyn Repository, connection: &mut dyn Connection, name: &str) -> User {
+		repository
+			.user()
+			.create(connection, name, &normalize_name(name))
+			.await
+			.expect("Failed to create user")
+	}
+
+	async fn room(repository: &dyn Repository, connection: &mut dyn Connection, name: &str) -> Room {
+		repository
+			.room()
+			.create(connection, name)
+			.await
+			.expect("Failed to create room")
+	}
+
+	#[instantiate_tests(<LibSqlTestFactory>)]
+	mod libsql {}
+}
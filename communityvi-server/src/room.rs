@@ -1,17 +1,37 @@
+use crate::bookmark::model::Bookmark;
+use crate::chat::mention::find_mentions;
+use crate::chat::model::{ChatContent, ChatMessage, ChatMessageKind, MediumAnchor};
+use crate::configuration::Configuration;
 use crate::connection::sender::MessageSender;
-use crate::database::{Database, Repository};
-use crate::message::outgoing::broadcast_message::{BroadcastMessage, ChatBroadcast};
+use crate::connection::shared_broadcast::SharedBroadcast;
+use crate::database::error::DatabaseError;
+use crate::database::{Connection, Database, Repository};
+use crate::message::outgoing::broadcast_message::{
+	BroadcastMessage, ChatBroadcast, ChatMention, ClientProfileChangedBroadcast, ClientRenamedBroadcast,
+	MediumStateChangedBroadcast, PlaybackScheduledBroadcast, ReactionBroadcast,
+};
+use crate::message::outgoing::moderator_notice::ModeratorNotice;
+use crate::message::outgoing::private_message::PrivateMessage;
+use crate::poll::model::{NewPoll, Poll, PollKind, PollResults};
 use crate::reference_time::ReferenceTimer;
-use crate::room::client::Client;
-use crate::room::error::RoomError;
-use crate::room::medium::{Medium, VersionedMedium};
+use crate::room::client::{Client, Readiness};
+use crate::room::error::{ChatError, ChatModificationError, PollError, PrivateMessageError, RoomError, SeekError};
+use crate::room::medium::fixed_length::FixedLengthMedium;
+use crate::room::medium::playback_state::PlaybackState;
+use crate::room::medium::subtitles::{SubtitleTrack, SubtitleUpload, Subtitles};
+use crate::room::medium::url::UrlMedium;
+use crate::room::medium::{Medium, SubtitleUpdateError, VersionedMedium};
+use crate::room::scheduled_play::ScheduledPlay;
 use crate::room::session_id::SessionId;
 use crate::room::session_repository::SessionRepository;
+use crate::types::date_time::DateTime;
 use crate::types::uuid::Uuid;
-use crate::user::UserService;
+use crate::user::model::Profile;
+use crate::user::{ProfileError, UserService, normalize_name};
 use chrono::Duration;
 use js_int::UInt;
 use parking_lot::Mutex;
+use std::collections::{BTreeSet, VecDeque};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
@@ -20,22 +40,34 @@ pub mod error;
 pub mod medium;
 pub mod model;
 pub mod repository;
+pub mod scheduled_play;
 pub mod session_id;
 mod session_id_sequence;
 pub mod session_repository;
 
+/// Uploads beyond this are dropped, oldest first, so uploading can't exhaust memory.
+const SUBTITLE_UPLOAD_LIMIT: usize = 16;
+
 #[derive(Clone)]
 pub struct Room {
 	inner: Arc<Inner>,
 }
 
-#[expect(dead_code)]
 struct Inner {
 	uuid: Uuid,
 	user_service: UserService,
+	/// Normalized names that only moderators may use.
+	moderator_names: BTreeSet<String>,
 	// FIXME: Get rid of this tokio mutex
 	session_repository: tokio::sync::RwLock<SessionRepository>,
+	/// Only kept in memory, so it is lost on restart. The `medium` table of the initial schema isn't used yet.
 	medium: Mutex<VersionedMedium>,
+	pause_while_buffering: bool,
+	/// Version of the medium that was paused because a client is buffering.
+	buffering_pause: Mutex<Option<UInt>>,
+	scheduled_play: Mutex<Option<ScheduledPlay>>,
+	/// The most recent uploads, oldest first.
+	subtitle_uploads: Mutex<VecDeque<SubtitleUpload>>,
 	reference_timer: ReferenceTimer,
 	message_counters: MessageCounters,
 	database: Arc<dyn Database>,
@@ -70,7 +102,7 @@ impl Room {
 	pub fn new(
 		room_uuid: Uuid,
 		reference_timer: ReferenceTimer,
-		room_size_limit: usize,
+		configuration: &Configuration,
 		database: Arc<dyn Database>,
 		user_service: UserService,
 		repository: Arc<dyn Repository>,
@@ -78,8 +110,16 @@ impl Room {
 		let inner = Inner {
 			uuid: room_uuid,
 			user_service,
-			session_repository: tokio::sync::RwLock::new(SessionRepository::with_limit(room_size_limit)),
+			moderator_names: configuration.moderator_names().collect(),
+			session_repository: tokio::sync::RwLock::new(SessionRepository::new(
+				configuration.room_size_limit,
+				configuration.into(),
+			)),
 			medium: Mutex::default(),
+			pause_while_buffering: configuration.pause_while_buffering,
+			buffering_pause: Mutex::default(),
+			scheduled_play: Mutex::default(),
+			subtitle_uploads: Mutex::default(),
 			reference_timer,
 			message_counters: Default::default(),
 			database,
@@ -93,15 +133,89 @@ impl Room {
 	pub async fn add_client_and_return_existing(
 		&self,
 		name: &str,
+		is_moderator: bool,
 		message_sender: MessageSender,
 	) -> Result<(Client, Vec<Client>), RoomError> {
+		if !is_moderator && self.is_moderator_name(name) {
+			return Err(RoomError::ClientNameReserved);
+		}
+
 		let mut connection = self.inner.database.connection().await?;
 		let user = self.inner.user_service.create_user(name, connection.as_mut()).await?;
 		self.inner
 			.session_repository
 			.write()
 			.await
-			.add_and_return_existing(user, message_sender)
+			.add_and_return_existing(user, is_moderator, message_sender)
+	}
+
+	fn is_moderator_name(&self, name: &str) -> bool {
+		self.inner.moderator_names.contains(&normalize_name(name))
+	}
+
+	pub async fn client_by_session_token(&self, session_token: Uuid) -> Option<Client> {
+		self.inner
+			.session_repository
+			.read()
+			.await
+			.iter_clients()
+			.find(|client| client.session_token() == session_token)
+			.cloned()
+	}
+
+	/// Finds a client by comparing names the same way as [`normalize_name`].
+	pub async fn client_by_name(&self, name: &str) -> Option<Client> {
+		let normalized_name = normalize_name(name);
+		self.inner
+			.session_repository
+			.read()
+			.await
+			.iter_clients()
+			.find(|client| client.user().normalized_name == normalized_name)
+			.cloned()
+	}
+
+	/// Returns the broadcast that lets everyone know about the new name.
+	/// Only allows respelling a moderator name the client already has.
+	pub async fn rename_client(&self, client: &Client, name: &str) -> Result<ClientRenamedBroadcast, RoomError> {
+		if self.is_moderator_name(name) && normalize_name(name) != client.user().normalized_name {
+			return Err(RoomError::ClientNameReserved);
+		}
+
+		let mut connection = self.inner.database.connection().await?;
+		let user = self
+			.inner
+			.user_service
+			.rename_user(client.user().uuid, name, connection.as_mut())
+			.await?;
+		let new_name = user.name.clone();
+		let old_name = client.update_user(user).name;
+		Ok(ClientRenamedBroadcast {
+			id: client.id(),
+			old_name,
+			new_name,
+		})
+	}
+
+	/// Returns the broadcast that lets everyone know about the new profile.
+	pub async fn update_profile(
+		&self,
+		client: &Client,
+		profile: Profile,
+	) -> Result<ClientProfileChangedBroadcast, ProfileError> {
+		let mut connection = self.inner.database.connection().await?;
+		let user = self
+			.inner
+			.user_service
+			.update_profile(client.user().uuid, &profile, connection.as_mut())
+			.await?;
+		let name = user.name.clone();
+		client.update_user(user);
+		Ok(ClientProfileChangedBroadcast {
+			id: client.id(),
+			name,
+			profile: profile.into(),
+		})
 	}
 
 	pub async fn remove_client(&self, session_id: SessionId) -> Result<(), RoomError> {
@@ -122,19 +236,347 @@ impl Room {
 		Ok(())
 	}
 
-	pub async fn send_chat_message(&self, sender: &Client, message: String) -> Result<(), RoomError> {
+	/// Clients that are mentioned with `@name` get the broadcast with `mentions_you` set, so frontends don't have
+	/// to match names themselves.
+	pub async fn send_chat_message(
+		&self,
+		sender: &Client,
+		message: String,
+		reply_to: Option<Uuid>,
+	) -> Result<Uuid, ChatError> {
+		if let Some(reply_to) = reply_to {
+			let mut connection = self.inner.database.connection().await?;
+			self.inner
+				.repository
+				.chat()
+				.get(connection.as_mut(), self.inner.uuid, reply_to)
+				.await?
+				.filter(|chat_message| chat_message.content.kind == ChatMessageKind::Message)
+				.ok_or(ChatError::UnknownReplyTarget)?;
+		}
+
+		let chat_message = self
+			.persist_chat(sender, ChatMessageKind::Message, message, reply_to)
+			.await?;
 		let chat_counter = self.inner.message_counters.fetch_and_increment_chat_counter()?;
-		let chat_message = ChatBroadcast {
+		let count = self.inner.message_counters.fetch_and_increment_broadcast_counter()?;
+		let session_repository = self.inner.session_repository.read().await;
+		let normalized_names = session_repository
+			.iter_clients()
+			.map(|client| client.user().normalized_name.clone())
+			.collect();
+		let mentioned_names = find_mentions(&chat_message.content.message, &normalized_names);
+		let mentioned_clients = session_repository
+			.iter_clients()
+			.filter(|client| mentioned_names.contains(client.user().normalized_name.as_str()))
+			.collect::<Vec<_>>();
+
+		let chat_broadcast = ChatBroadcast {
+			id: chat_message.uuid.to_string(),
 			sender_id: sender.id(),
-			sender_name: sender.name().to_string(),
-			message,
+			sender_name: sender.name(),
+			message: chat_message.content.message,
 			counter: chat_counter,
+			medium_position_in_milliseconds: medium_position_in_milliseconds(
+				chat_message.content.medium_anchor.as_ref(),
+			),
+			reply_to: chat_message.content.reply_to.map(|uuid| uuid.to_string()),
+			mentions: mentioned_clients
+				.iter()
+				.map(|client| ChatMention {
+					id: client.id(),
+					name: client.name(),
+				})
+				.collect(),
+			mentions_you: false,
+		};
+		let mentioning_broadcast = SharedBroadcast::new(
+			ChatBroadcast {
+				mentions_you: true,
+				..chat_broadcast.clone()
+			}
+			.into(),
+		);
+		let broadcast = SharedBroadcast::new(chat_broadcast.into());
+		for client in session_repository.iter_clients() {
+			let is_mentioned = mentioned_clients
+				.iter()
+				.any(|mentioned_client| mentioned_client.id() == client.id());
+			let message = if is_mentioned {
+				&mentioning_broadcast
+			} else {
+				&broadcast
+			};
+			client.enqueue_broadcast(message.clone(), count);
+		}
+
+		Ok(chat_message.uuid)
+	}
+
+	/// Delivers `message` only to the sender and the recipient, if the sender is within their private message quota.
+	pub async fn send_private_message(
+		&self,
+		sender: &Client,
+		recipient_id: SessionId,
+		message: String,
+		persist: bool,
+	) -> Result<(), PrivateMessageError> {
+		if recipient_id == sender.id() {
+			return Err(PrivateMessageError::RecipientIsSender);
+		}
+		let recipient = self
+			.inner
+			.session_repository
+			.read()
+			.await
+			.get(recipient_id)
+			.cloned()
+			.ok_or(PrivateMessageError::UnknownRecipient)?;
+		if !recipient.accepts_private_messages() {
+			return Err(PrivateMessageError::NotAccepted);
+		}
+		if !sender.check_private_message_quota() {
+			return Err(PrivateMessageError::RateLimited);
+		}
+
+		if persist {
+			let mut connection = self.inner.database.connection().await?;
+			self.inner
+				.repository
+				.chat()
+				.create_private_message(
+					connection.as_mut(),
+					self.inner.uuid,
+					&sender.user(),
+					&recipient.user(),
+					message.clone(),
+					chrono::Utc::now().into(),
+				)
+				.await?;
+		}
+
+		let private_message = PrivateMessage {
+			sender_id: sender.id(),
+			sender_name: sender.name(),
+			recipient_id,
+			recipient_name: recipient.name(),
+			message,
+		};
+		recipient.send_private_message(private_message.clone()).await;
+		sender.send_private_message(private_message).await;
+		Ok(())
+	}
+
+	pub async fn send_moderator_notice(&self, notice: ModeratorNotice) {
+		let moderators = self
+			.inner
+			.session_repository
+			.read()
+			.await
+			.iter_clients()
+			.filter(|client| client.is_moderator())
+			.cloned()
+			.collect::<Vec<_>>();
+		for moderator in moderators {
+			moderator.send_moderator_notice(notice.clone()).await;
+		}
+	}
+
+	/// Only the author can change their messages unless `is_moderator` is set. Reactions can't be edited.
+	pub async fn edit_chat_message(
+		&self,
+		editor: &Client,
+		is_moderator: bool,
+		chat_message_uuid: Uuid,
+		message: String,
+	) -> Result<ChatMessage, ChatModificationError> {
+		let mut connection = self.inner.database.connection().await?;
+		self.modifiable_chat_message(connection.as_mut(), editor, is_moderator, chat_message_uuid)
+			.await?;
+		let chat_message = self
+			.inner
+			.repository
+			.chat()
+			.update_message(
+				connection.as_mut(),
+				self.inner.uuid,
+				chat_message_uuid,
+				message,
+				chrono::Utc::now().into(),
+			)
+			.await?;
+		Ok(chat_message)
+	}
+
+	/// Only the author can delete their messages unless `is_moderator` is set. Reactions can't be deleted.
+	pub async fn delete_chat_message(
+		&self,
+		deleter: &Client,
+		is_moderator: bool,
+		chat_message_uuid: Uuid,
+	) -> Result<(), ChatModificationError> {
+		let mut connection = self.inner.database.connection().await?;
+		self.modifiable_chat_message(connection.as_mut(), deleter, is_moderator, chat_message_uuid)
+			.await?;
+		if !self
+			.inner
+			.repository
+			.chat()
+			.remove(connection.as_mut(), self.inner.uuid, chat_message_uuid)
+			.await?
+		{
+			return Err(ChatModificationError::NotFound);
+		}
+		Ok(())
+	}
+
+	async fn modifiable_chat_message(
+		&self,
+		connection: &mut dyn Connection,
+		client: &Client,
+		is_moderator: bool,
+		chat_message_uuid: Uuid,
+	) -> Result<ChatMessage, ChatModificationError> {
+		let chat_message = self
+			.inner
+			.repository
+			.chat()
+			.get(connection, self.inner.uuid, chat_message_uuid)
+			.await?
+			.filter(|chat_message| chat_message.content.kind == ChatMessageKind::Message)
+			.ok_or(ChatModificationError::NotFound)?;
+
+		if !is_moderator && chat_message.user_uuid != Some(client.user().uuid) {
+			return Err(ChatModificationError::NotAllowed);
+		}
+		Ok(chat_message)
+	}
+
+	pub async fn send_reaction(&self, sender: &Client, emoji: String) -> Result<(), RoomError> {
+		let chat_message = self
+			.persist_chat(sender, ChatMessageKind::Reaction, emoji, None)
+			.await?;
+		let reaction = ReactionBroadcast {
+			sender_id: sender.id(),
+			sender_name: sender.name(),
+			emoji: chat_message.content.message,
+			medium_position_in_milliseconds: medium_position_in_milliseconds(
+				chat_message.content.medium_anchor.as_ref(),
+			),
+		};
+		self.broadcast(reaction).await
+	}
+
+	/// Chat messages and reactions that were sent while `medium` was in the room, ordered by their position in it.
+	pub async fn chat_anchored_to(&self, medium: &Medium) -> Result<Vec<ChatMessage>, RoomError> {
+		let Some(medium_id) = medium.id() else {
+			return Ok(Vec::new());
+		};
+
+		let mut connection = self.inner.database.connection().await?;
+		let messages = self
+			.inner
+			.repository
+			.chat()
+			.get_all_anchored_to_medium(connection.as_mut(), self.inner.uuid, &medium_id)
+			.await?;
+		Ok(messages)
+	}
+
+	async fn persist_chat(
+		&self,
+		sender: &Client,
+		kind: ChatMessageKind,
+		message: String,
+		reply_to: Option<Uuid>,
+	) -> Result<ChatMessage, DatabaseError> {
+		let content = ChatContent {
+			kind,
+			message,
+			medium_anchor: self.medium_anchor(),
+			reply_to,
 		};
-		self.broadcast(chat_message).await
+		let mut connection = self.inner.database.connection().await?;
+		let chat_message = self
+			.inner
+			.repository
+			.chat()
+			.create(
+				connection.as_mut(),
+				self.inner.uuid,
+				sender.user().uuid,
+				sender.name(),
+				content,
+				chrono::Utc::now().into(),
+			)
+			.await?;
+		Ok(chat_message)
+	}
+
+	/// Current position of the medium as computed by the server, `None` if there is no medium.
+	fn medium_anchor(&self) -> Option<MediumAnchor> {
+		let reference_now = self.reference_now();
+		let versioned_medium = self.inner.medium.lock();
+		Some(MediumAnchor {
+			medium_id: versioned_medium.medium.id()?,
+			position: versioned_medium.medium.position_at(reference_now)?,
+		})
 	}
 
+	/// Clients that reported a fingerprint for `versioned_medium` that doesn't match the medium's fingerprint.
+	pub async fn clients_with_mismatching_fingerprint(&self, versioned_medium: &VersionedMedium) -> Vec<Client> {
+		let Medium::FixedLength(FixedLengthMedium {
+			fingerprint: Some(expected_fingerprint),
+			..
+		}) = &versioned_medium.medium
+		else {
+			return Vec::new();
+		};
+
+		self.inner
+			.session_repository
+			.read()
+			.await
+			.iter_clients()
+			.filter(|client| {
+				client
+					.fingerprint(versioned_medium.version)
+					.is_some_and(|fingerprint| &fingerprint != expected_fingerprint)
+			})
+			.cloned()
+			.collect()
+	}
+
+	/// Broadcasting a change of the medium also cancels a scheduled start of the previous version,
+	/// because it can never start anymore.
 	pub async fn broadcast(&self, response: impl Into<BroadcastMessage> + Clone) -> Result<(), RoomError> {
 		let message = response.into();
+		let cancelled_scheduled_play = match &message {
+			BroadcastMessage::MediumStateChanged(MediumStateChangedBroadcast {
+				changed_by_name,
+				changed_by_id,
+				..
+			}) => self
+				.take_outdated_scheduled_play()
+				.map(|scheduled_play| PlaybackScheduledBroadcast {
+					changed_by_name: changed_by_name.clone(),
+					changed_by_id: *changed_by_id,
+					medium_version: scheduled_play.medium_version,
+					scheduled_time_in_milliseconds: None,
+				}),
+			_ => None,
+		};
+
+		self.enqueue_broadcast(message).await?;
+		if let Some(cancelled_scheduled_play) = cancelled_scheduled_play {
+			self.enqueue_broadcast(cancelled_scheduled_play.into()).await?;
+		}
+
+		Ok(())
+	}
+
+	async fn enqueue_broadcast(&self, message: BroadcastMessage) -> Result<(), RoomError> {
+		let message = SharedBroadcast::new(message);
 		let count = self.inner.message_counters.fetch_and_increment_broadcast_counter()?;
 		let session_repository = self.inner.session_repository.read().await;
 		for client in session_repository.iter_clients() {
@@ -160,8 +602,7 @@ impl Room {
 
 	#[must_use = "returns a `VersionedMedium` with new version that must be propagated"]
 	pub fn play_medium(&self, start_time: Duration, previous_version: UInt) -> Option<VersionedMedium> {
-		let reference_now = Duration::from_std(self.inner.reference_timer.reference_time())
-			.expect("This won't happen unless you run the server for more than 9_223_372_036_854_775_807 seconds :)");
+		let reference_now = self.reference_now();
 		self.inner
 			.medium
 			.lock()
@@ -173,15 +614,400 @@ impl Room {
 		self.inner.medium.lock().pause(at_position, previous_version)
 	}
 
+	pub fn add_subtitle_track(
+		&self,
+		track: SubtitleTrack,
+		medium_version: UInt,
+	) -> Result<Subtitles, SubtitleUpdateError> {
+		self.inner.medium.lock().add_subtitle_track(track, medium_version)
+	}
+
+	pub fn set_subtitle_offset(
+		&self,
+		offset: Duration,
+		medium_version: UInt,
+	) -> Result<Subtitles, SubtitleUpdateError> {
+		self.inner.medium.lock().set_subtitle_offset(offset, medium_version)
+	}
+
+	/// Keep `upload` around until a client adds it to the medium, dropping the oldest upload if there are too many.
+	pub fn upload_subtitles(&self, upload: SubtitleUpload) {
+		let mut subtitle_uploads = self.inner.subtitle_uploads.lock();
+		subtitle_uploads.retain(|existing| existing.id != upload.id);
+		if subtitle_uploads.len() >= SUBTITLE_UPLOAD_LIMIT {
+			subtitle_uploads.pop_front();
+		}
+		subtitle_uploads.push_back(upload);
+	}
+
+	pub fn subtitle_upload(&self, id: &str) -> Option<SubtitleUpload> {
+		self.inner
+			.subtitle_uploads
+			.lock()
+			.iter()
+			.find(|upload| upload.id == id)
+			.cloned()
+	}
+
+	/// Look up a subtitle track of the current medium.
+	pub fn subtitle_track(&self, id: &str) -> Option<SubtitleTrack> {
+		self.inner
+			.medium
+			.lock()
+			.medium
+			.subtitles()?
+			.tracks
+			.iter()
+			.find(|track| track.id == id)
+			.cloned()
+	}
+
+	/// Move the current medium to `position`, keeping it playing or paused.
+	/// If `previous_version` is incorrect or there is no medium, `None` is returned.
+	#[must_use = "returns a `VersionedMedium` with new version that must be propagated"]
+	pub fn jump_medium_to(&self, position: Duration, previous_version: UInt) -> Option<VersionedMedium> {
+		let reference_now = self.reference_now();
+		let mut versioned_medium = self.inner.medium.lock();
+		Self::jump(&mut versioned_medium, position, reference_now, previous_version)
+	}
+
+	/// Like [`Room::jump_medium_to`], but for whatever medium is current, since chat commands don't know its
+	/// version. The medium is checked and moved under the same lock, so the position can't end up in another one.
+	pub fn seek_medium(&self, position: Duration) -> Result<VersionedMedium, SeekError> {
+		let reference_now = self.reference_now();
+		let mut versioned_medium = self.inner.medium.lock();
+		match &versioned_medium.medium {
+			Medium::Empty => return Err(SeekError::NoMedium),
+			Medium::Live(_) => return Err(SeekError::LiveMedium),
+			Medium::FixedLength(FixedLengthMedium { length, .. })
+			| Medium::Url(UrlMedium {
+				length: Some(length), ..
+			}) if position > *length => {
+				return Err(SeekError::BeyondEnd);
+			}
+			Medium::FixedLength(_) | Medium::Url(_) => {}
+		}
+
+		let current_version = versioned_medium.version;
+		Self::jump(&mut versioned_medium, position, reference_now, current_version).ok_or(SeekError::NoMedium)
+	}
+
+	fn jump(
+		versioned_medium: &mut VersionedMedium,
+		position: Duration,
+		reference_now: Duration,
+		previous_version: UInt,
+	) -> Option<VersionedMedium> {
+		match versioned_medium.medium.playback()? {
+			PlaybackState::Playing { .. } => {
+				versioned_medium.play(reference_now - position, reference_now, previous_version)
+			}
+			PlaybackState::Paused { .. } => versioned_medium.pause(position, previous_version),
+		}
+	}
+
+	pub async fn add_bookmark(&self, medium_id: &str, position: Duration, label: &str) -> Result<Bookmark, RoomError> {
+		let mut connection = self.inner.database.connection().await?;
+		let bookmark = self
+			.inner
+			.repository
+			.bookmark()
+			.create(
+				connection.as_mut(),
+				self.inner.uuid,
+				medium_id,
+				position,
+				label,
+				chrono::Utc::now().into(),
+			)
+			.await?;
+		Ok(bookmark)
+	}
+
+	/// Returns `false` if the room has no bookmark with `bookmark_uuid`.
+	pub async fn remove_bookmark(&self, bookmark_uuid: Uuid) -> Result<bool, RoomError> {
+		let mut connection = self.inner.database.connection().await?;
+		let removed = self
+			.inner
+			.repository
+			.bookmark()
+			.remove(connection.as_mut(), self.inner.uuid, bookmark_uuid)
+			.await?;
+		Ok(removed)
+	}
+
+	/// Bookmarks of `medium` ordered by position, empty if there is no medium.
+	pub async fn bookmarks(&self, medium: &Medium) -> Result<Vec<Bookmark>, RoomError> {
+		let Some(medium_id) = medium.id() else {
+			return Ok(Vec::new());
+		};
+
+		let mut connection = self.inner.database.connection().await?;
+		let bookmarks = self
+			.inner
+			.repository
+			.bookmark()
+			.get_all_for_medium(connection.as_mut(), self.inner.uuid, &medium_id)
+			.await?;
+		Ok(bookmarks)
+	}
+
+	pub async fn create_poll(&self, creator: &Client, new_poll: NewPoll) -> Result<PollResults, RoomError> {
+		let mut connection = self.inner.database.connection().await?;
+		let user = creator.user();
+		let poll = self
+			.inner
+			.repository
+			.poll()
+			.create(
+				connection.as_mut(),
+				self.inner.uuid,
+				user.uuid,
+				user.name.clone(),
+				new_poll,
+				chrono::Utc::now().into(),
+			)
+			.await?;
+		Ok(poll)
+	}
+
+	/// Polls that haven't been closed yet, e.g. to close them at their deadline after a restart.
+	pub async fn open_polls(&self) -> Result<Vec<Poll>, DatabaseError> {
+		let mut connection = self.inner.database.connection().await?;
+		self.inner
+			.repository
+			.poll()
+			.get_open(connection.as_mut(), self.inner.uuid)
+			.await
+	}
+
+	/// Casts the vote of `voter` or changes it if they had already voted.
+	pub async fn vote(&self, voter: &Client, poll_uuid: Uuid, option_index: u32) -> Result<PollResults, PollError> {
+		let mut connection = self.inner.database.connection().await?;
+		let poll = self.open_poll(connection.as_mut(), poll_uuid).await?;
+		if usize::try_from(option_index).map_or(true, |index| index >= poll.options.len()) {
+			return Err(PollError::UnknownOption);
+		}
+
+		let repository = self.inner.repository.poll();
+		repository
+			.vote(
+				connection.as_mut(),
+				poll_uuid,
+				voter.user().uuid,
+				option_index,
+				chrono::Utc::now().into(),
+			)
+			.await?;
+		repository
+			.get(connection.as_mut(), self.inner.uuid, poll_uuid)
+			.await?
+			.ok_or(PollError::NotFound)
+	}
+
+	/// Only the creator can close their polls unless `is_moderator` is set.
+	pub async fn close_poll(
+		&self,
+		closer: &Client,
+		is_moderator: bool,
+		poll_uuid: Uuid,
+	) -> Result<PollResults, PollError> {
+		let mut connection = self.inner.database.connection().await?;
+		let poll = self.open_poll(connection.as_mut(), poll_uuid).await?;
+		if !is_moderator && poll.poll.created_by_user_uuid != Some(closer.user().uuid) {
+			return Err(PollError::NotAllowed);
+		}
+
+		self.close_open_poll(connection.as_mut(), poll_uuid)
+			.await?
+			.ok_or(PollError::Closed)
+	}
+
+	/// Closes a poll regardless of who created it, e.g. once its deadline has passed.
+	/// Returns `None` if it had already been closed.
+	pub async fn close_poll_by_server(&self, poll_uuid: Uuid) -> Result<Option<PollResults>, PollError> {
+		let mut connection = self.inner.database.connection().await?;
+		self.close_open_poll(connection.as_mut(), poll_uuid).await
+	}
+
+	async fn close_open_poll(
+		&self,
+		connection: &mut dyn Connection,
+		poll_uuid: Uuid,
+	) -> Result<Option<PollResults>, PollError> {
+		let repository = self.inner.repository.poll();
+		if !repository
+			.close(connection, self.inner.uuid, poll_uuid, chrono::Utc::now().into())
+			.await?
+		{
+			return Ok(None);
+		}
+
+		let poll = repository
+			.get(connection, self.inner.uuid, poll_uuid)
+			.await?
+			.ok_or(PollError::NotFound)?;
+		Ok(Some(poll))
+	}
+
+	async fn open_poll(&self, connection: &mut dyn Connection, poll_uuid: Uuid) -> Result<PollResults, PollError> {
+		let poll = self
+			.inner
+			.repository
+			.poll()
+			.get(connection, self.inner.uuid, poll_uuid)
+			.await?
+			.ok_or(PollError::NotFound)?;
+		if poll.poll.is_closed() {
+			return Err(PollError::Closed);
+		}
+		Ok(poll)
+	}
+
+	/// Whether more than half of the clients in the room voted for `option_index`.
+	pub async fn has_majority(&self, poll: &PollResults, option_index: u32) -> bool {
+		let client_count = self.inner.session_repository.read().await.iter_clients().count();
+		usize::try_from(poll.votes_for(option_index)).is_ok_and(|votes| votes * 2 > client_count)
+	}
+
+	/// Ejects or pauses the medium as decided by a skip or pause poll. If the medium has changed since the poll was
+	/// created or it is a custom poll, nothing happens and `None` is returned.
+	#[must_use = "returns a `VersionedMedium` with new version that must be propagated"]
+	pub fn apply_medium_poll(&self, poll: &Poll) -> Option<VersionedMedium> {
+		let medium_version = poll.medium_version?;
+		match poll.kind {
+			PollKind::Custom => None,
+			PollKind::Skip => self.insert_medium(Medium::Empty, medium_version),
+			PollKind::Pause => {
+				let reference_now = self.reference_now();
+				let mut versioned_medium = self.inner.medium.lock();
+				let position = versioned_medium.medium.position_at(reference_now)?;
+				versioned_medium.pause(position, medium_version)
+			}
+		}
+	}
+
+	/// Schedule the medium to start playing at the reference time `start_time`, replacing any previously scheduled
+	/// start. If `previous_version` is incorrect, nothing happens and `None` is returned.
+	#[must_use]
+	pub fn schedule_play(&self, start_time: Duration, previous_version: UInt) -> Option<ScheduledPlay> {
+		let versioned_medium = self.inner.medium.lock();
+		if previous_version != versioned_medium.version {
+			return None;
+		}
+
+		let scheduled_play = ScheduledPlay {
+			medium_version: previous_version,
+			start_time,
+		};
+		*self.inner.scheduled_play.lock() = Some(scheduled_play);
+		Some(scheduled_play)
+	}
+
+	/// Returns the scheduled start that was cancelled, if any.
+	pub fn cancel_scheduled_play(&self) -> Option<ScheduledPlay> {
+		self.inner.scheduled_play.lock().take()
+	}
+
+	/// Removes a scheduled start of a previous version of the medium.
+	fn take_outdated_scheduled_play(&self) -> Option<ScheduledPlay> {
+		let versioned_medium = self.inner.medium.lock();
+		let mut scheduled_play = self.inner.scheduled_play.lock();
+		if scheduled_play.is_some_and(|scheduled_play| scheduled_play.medium_version != versioned_medium.version) {
+			scheduled_play.take()
+		} else {
+			None
+		}
+	}
+
+	/// Turn `scheduled_play` into regular playback, unless it has been cancelled or replaced in the meantime
+	/// or the medium has changed since it was scheduled.
+	#[must_use = "returns a `VersionedMedium` with new version that must be propagated"]
+	pub fn start_scheduled_play(&self, scheduled_play: ScheduledPlay) -> Option<VersionedMedium> {
+		let reference_now = self.reference_now();
+		let mut versioned_medium = self.inner.medium.lock();
+		let mut pending_scheduled_play = self.inner.scheduled_play.lock();
+		if *pending_scheduled_play != Some(scheduled_play) {
+			return None;
+		}
+		*pending_scheduled_play = None;
+
+		let position = versioned_medium.medium.position_at(reference_now)?;
+		versioned_medium.play(
+			scheduled_play.start_time - position,
+			reference_now,
+			scheduled_play.medium_version,
+		)
+	}
+
+	/// Pauses the medium while any client is buffering and resumes it once everyone is ready again.
+	/// Only a medium that was paused by this and hasn't been changed by a client since is resumed.
+	#[must_use = "returns a `VersionedMedium` with new version that must be propagated"]
+	pub async fn update_buffering_pause(&self) -> Option<VersionedMedium> {
+		if !self.inner.pause_while_buffering {
+			return None;
+		}
+
+		let anyone_buffering = self
+			.inner
+			.session_repository
+			.read()
+			.await
+			.iter_clients()
+			.any(|client| client.readiness() == Readiness::Buffering);
+		let reference_now = self.reference_now();
+
+		let mut versioned_medium = self.inner.medium.lock();
+		let mut buffering_pause = self.inner.buffering_pause.lock();
+		if buffering_pause.is_some_and(|paused_version| paused_version != versioned_medium.version) {
+			// A client has changed the medium in the meantime, so it isn't ours to resume anymore.
+			*buffering_pause = None;
+		}
+
+		let position = versioned_medium.medium.position_at(reference_now)?;
+		let is_playing = matches!(versioned_medium.medium.playback(), Some(PlaybackState::Playing { .. }));
+		match *buffering_pause {
+			None if anyone_buffering && is_playing => {
+				let version = versioned_medium.version;
+				let paused_medium = versioned_medium.pause(position, version)?;
+				*buffering_pause = Some(paused_medium.version);
+				Some(paused_medium)
+			}
+			Some(paused_version) if !anyone_buffering => {
+				*buffering_pause = None;
+				versioned_medium.play(reference_now - position, reference_now, paused_version)
+			}
+			_ => None,
+		}
+	}
+
 	fn eject_medium(&self) {
 		self.inner.medium.lock().update(Medium::Empty);
 	}
 
+	fn reference_now(&self) -> Duration {
+		Duration::from_std(self.inner.reference_timer.reference_time())
+			.expect("This won't happen unless you run the server for more than 9_223_372_036_854_775_807 seconds :)")
+	}
+
+	/// The reference time counts from the UNIX epoch, so it can be used for points in time like poll deadlines.
+	pub fn reference_date_time(&self) -> DateTime {
+		(chrono::DateTime::UNIX_EPOCH + self.reference_now()).into()
+	}
+
+	pub fn reference_time_milliseconds(&self) -> UInt {
+		self.inner.reference_timer.reference_time_milliseconds()
+	}
+
 	pub fn medium(&self) -> VersionedMedium {
 		self.inner.medium.lock().clone()
 	}
 }
 
+fn medium_position_in_milliseconds(medium_anchor: Option<&MediumAnchor>) -> Option<UInt> {
+	medium_anchor.map(|anchor| UInt::try_from(anchor.position.num_milliseconds()).unwrap_or_default())
+}
+
 #[cfg(test)]
 #[allow(clippy::non_ascii_literal)]
 mod test {
@@ -199,7 +1025,7 @@ mod test {
 			let message_sender = MessageSender::from(FakeMessageSender::default());
 
 			if let Err(error) = room
-				.add_client_and_return_existing(&format!("{count}"), message_sender.clone())
+				.add_client_and_return_existing(&format!("{count}"), false, message_sender.clone())
 				.await
 			{
 				panic!("Failed to add client {count}: {error}");
@@ -207,7 +1033,9 @@ mod test {
 		}
 
 		let message_sender = MessageSender::from(FakeMessageSender::default());
-		let result = room.add_client_and_return_existing("elephant", message_sender).await;
+		let result = room
+			.add_client_and_return_existing("elephant", false, message_sender)
+			.await;
 		assert!(matches!(result, Err(RoomError::RoomFull)));
 	}
 
@@ -218,7 +1046,7 @@ mod test {
 
 		let message_sender = MessageSender::from(FakeMessageSender::default());
 		let (makise_kurisu, _) = room
-			.add_client_and_return_existing(name, message_sender)
+			.add_client_and_return_existing(name, false, message_sender)
 			.await
 			.expect("Failed to add client with same name after first is gone");
 		let medium = FixedLengthMedium::new("愛のむきだし".to_string(), Duration::minutes(237));
@@ -261,19 +1089,57 @@ mod test {
 		assert_eq!(room.medium().version, uint!(0));
 	}
 
+	#[tokio::test]
+	async fn should_not_start_cancelled_scheduled_play() {
+		let room = room(1).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+		let scheduled_play = room
+			.schedule_play(Duration::hours(20), uint!(1))
+			.expect("Failed to schedule play");
+
+		assert_eq!(Some(scheduled_play), room.cancel_scheduled_play());
+		assert_eq!(None, room.start_scheduled_play(scheduled_play));
+		assert_eq!(uint!(1), room.medium().version);
+	}
+
+	#[tokio::test]
+	async fn should_not_start_scheduled_play_if_medium_has_changed() {
+		let room = room(1).await;
+		let medium = FixedLengthMedium::new("Metropolis".to_string(), Duration::minutes(153));
+		room.insert_medium(medium, uint!(0)).expect("Failed to insert medium");
+		let scheduled_play = room
+			.schedule_play(Duration::hours(20), uint!(1))
+			.expect("Failed to schedule play");
+		let paused_medium = room
+			.pause_medium(Duration::seconds(42), uint!(1))
+			.expect("Failed to pause medium");
+
+		assert_eq!(None, room.start_scheduled_play(scheduled_play));
+		assert_eq!(paused_medium, room.medium());
+	}
+
+	#[tokio::test]
+	async fn should_not_schedule_play_with_incorrect_version() {
+		let room = room(1).await;
+
+		assert_eq!(None, room.schedule_play(Duration::hours(20), uint!(1)));
+		assert_eq!(None, room.cancel_scheduled_play());
+	}
+
 	#[tokio::test]
 	async fn add_client_should_return_list_of_existing_clients() {
 		let room = room(10).await;
 		let jake_sender = FakeMessageSender::default();
 		let (jake, existing_clients) = room
-			.add_client_and_return_existing("Jake", jake_sender.into())
+			.add_client_and_return_existing("Jake", false, jake_sender.into())
 			.await
 			.unwrap();
 		assert!(existing_clients.is_empty());
 
 		let elwood_sender = FakeMessageSender::default();
 		let (_, existing_clients) = room
-			.add_client_and_return_existing("Elwood", elwood_sender.into())
+			.add_client_and_return_existing("Elwood", false, elwood_sender.into())
 			.await
 			.unwrap();
 		assert_eq!(existing_clients.len(), 1);
@@ -288,7 +1154,10 @@ mod test {
 		Room::new(
 			Uuid::new_v4(),
 			ReferenceTimer::default(),
-			room_size_limit,
+			&Configuration {
+				room_size_limit,
+				..Configuration::test()
+			},
 			DefaultTestFactory::database().await,
 			user_service,
 			repository,
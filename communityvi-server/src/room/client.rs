@@ -1,13 +1,26 @@
+use crate::chat::filter::{ChatFilterConfiguration, ChatFilterKind};
+use crate::configuration::Configuration;
 use crate::connection::Connection;
 use crate::connection::broadcast_buffer::BroadcastBuffer;
 use crate::connection::sender::MessageSender;
-use crate::message::outgoing::broadcast_message::BroadcastMessage;
+use crate::connection::shared_broadcast::SharedBroadcast;
 use crate::message::outgoing::error_message::ErrorMessage;
+use crate::message::outgoing::moderator_notice::ModeratorNotice;
+use crate::message::outgoing::private_message::PrivateMessage;
 use crate::message::outgoing::success_message::SuccessMessage;
+use crate::room::medium::fingerprint::Fingerprint;
 use crate::room::session_id::SessionId;
+use crate::types::uuid::Uuid;
 use crate::user::model::User;
+use chrono::Duration;
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
 use js_int::UInt;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU32;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
 use tracing::info;
 
 #[derive(Clone)]
@@ -17,15 +30,97 @@ pub struct Client {
 
 struct Inner {
 	id: SessionId,
-	user: User,
+	/// Secret that authenticates REST requests of the client, e.g. subtitle uploads.
+	session_token: Uuid,
+	user: Mutex<User>,
+	/// Decided when registering, see `moderators` in the configuration.
+	is_moderator: bool,
 	connection: Connection,
+	drift: Mutex<Option<Drift>>,
+	readiness: Mutex<Readiness>,
+	fingerprint: Mutex<Option<(UInt, Fingerprint)>>,
+	accepts_private_messages: AtomicBool,
+	private_message_rate_limiter: DefaultDirectRateLimiter,
+	chat_message_rate_limiter: Option<DefaultDirectRateLimiter>,
+	/// The last chat message and how often it was sent in a row.
+	last_chat_message: Mutex<Option<(String, u32)>>,
+	kick_notification: Notify,
+	typing: AtomicBool,
+	typing_notification: Notify,
+}
+
+/// How many messages each client may send, see the configuration.
+#[derive(Clone, Copy, Debug)]
+pub struct MessageQuotas {
+	pub private_messages_per_minute: NonZeroU32,
+	/// Only limited by the first flood filter in the chat filters, if there is one.
+	pub chat_messages_per_minute: Option<NonZeroU32>,
+}
+
+impl From<&Configuration> for MessageQuotas {
+	fn from(configuration: &Configuration) -> Self {
+		Self {
+			private_messages_per_minute: configuration.private_messages_per_minute,
+			chat_messages_per_minute: configuration.chat_filters.iter().find_map(
+				|ChatFilterConfiguration { filter, .. }| match filter {
+					ChatFilterKind::Flood {
+						messages_per_minute, ..
+					} => Some(*messages_per_minute),
+					_ => None,
+				},
+			),
+		}
+	}
+}
+
+/// Whether a client is able to play the medium or still buffering it.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Readiness {
+	#[default]
+	Ready,
+	Buffering,
+}
+
+/// Drift from the expected playback position according to the last position report of a client.
+#[derive(Clone, Copy, Debug)]
+struct Drift {
+	medium_version: UInt,
+	drift: Duration,
 }
 
 impl Client {
-	pub fn new(id: SessionId, user: User, broadcast_buffer: BroadcastBuffer, sender: MessageSender) -> Self {
+	pub fn new(
+		id: SessionId,
+		user: User,
+		is_moderator: bool,
+		message_quotas: MessageQuotas,
+		broadcast_buffer: BroadcastBuffer,
+		sender: MessageSender,
+	) -> Self {
 		let connection = Connection::new(sender, broadcast_buffer);
 		Self {
-			inner: Arc::new(Inner { id, user, connection }),
+			inner: Arc::new(Inner {
+				id,
+				session_token: Uuid::new_v4(),
+				user: Mutex::new(user),
+				is_moderator,
+				connection,
+				drift: Mutex::default(),
+				readiness: Mutex::default(),
+				fingerprint: Mutex::default(),
+				accepts_private_messages: AtomicBool::new(true),
+				private_message_rate_limiter: RateLimiter::direct(Quota::per_minute(
+					message_quotas.private_messages_per_minute,
+				)),
+				chat_message_rate_limiter: message_quotas
+					.chat_messages_per_minute
+					.map(|limit_per_minute| RateLimiter::direct(Quota::per_minute(limit_per_minute))),
+				last_chat_message: Mutex::default(),
+				kick_notification: Notify::new(),
+				typing: AtomicBool::new(false),
+				typing_notification: Notify::new(),
+			}),
 		}
 	}
 
@@ -33,12 +128,123 @@ impl Client {
 		self.inner.id
 	}
 
-	pub fn name(&self) -> &str {
-		&self.inner.user.name
+	pub fn name(&self) -> String {
+		self.inner.user.lock().name.clone()
+	}
+
+	pub fn user(&self) -> User {
+		self.inner.user.lock().clone()
+	}
+
+	pub fn session_token(&self) -> Uuid {
+		self.inner.session_token
+	}
+
+	pub fn is_moderator(&self) -> bool {
+		self.inner.is_moderator
+	}
+
+	/// Returns the previous user, e.g. to get the old name after a client was renamed.
+	pub fn update_user(&self, user: User) -> User {
+		std::mem::replace(&mut self.inner.user.lock(), user)
+	}
+
+	pub fn update_drift(&self, medium_version: UInt, drift: Duration) {
+		*self.inner.drift.lock() = Some(Drift { medium_version, drift });
+	}
+
+	/// Drift of the last position report, but only if it was reported for `medium_version`.
+	pub fn drift(&self, medium_version: UInt) -> Option<Duration> {
+		self.inner
+			.drift
+			.lock()
+			.filter(|drift| drift.medium_version == medium_version)
+			.map(|drift| drift.drift)
+	}
+
+	pub fn update_fingerprint(&self, medium_version: UInt, fingerprint: Fingerprint) {
+		*self.inner.fingerprint.lock() = Some((medium_version, fingerprint));
+	}
+
+	/// Fingerprint of the file the client has loaded, but only if it was reported for `medium_version`.
+	pub fn fingerprint(&self, medium_version: UInt) -> Option<Fingerprint> {
+		self.inner
+			.fingerprint
+			.lock()
+			.as_ref()
+			.filter(|(version, _)| *version == medium_version)
+			.map(|(_, fingerprint)| fingerprint.clone())
+	}
+
+	pub fn readiness(&self) -> Readiness {
+		*self.inner.readiness.lock()
+	}
+
+	/// Returns the previous readiness.
+	pub fn update_readiness(&self, readiness: Readiness) -> Readiness {
+		std::mem::replace(&mut self.inner.readiness.lock(), readiness)
+	}
+
+	pub fn accepts_private_messages(&self) -> bool {
+		self.inner.accepts_private_messages.load(Ordering::Relaxed)
 	}
 
-	pub fn user(&self) -> &User {
-		&self.inner.user
+	pub fn set_accepts_private_messages(&self, accepts_private_messages: bool) {
+		self.inner
+			.accepts_private_messages
+			.store(accepts_private_messages, Ordering::Relaxed);
+	}
+
+	/// Whether the client may send another private message.
+	pub fn check_private_message_quota(&self) -> bool {
+		self.inner.private_message_rate_limiter.check().is_ok()
+	}
+
+	/// Whether the client may send another chat message.
+	pub fn check_chat_message_quota(&self) -> bool {
+		self.inner
+			.chat_message_rate_limiter
+			.as_ref()
+			.is_none_or(|limiter| limiter.check().is_ok())
+	}
+
+	/// Remembers `message` and returns how often it was sent in a row, including this time.
+	pub fn count_chat_message_repetitions(&self, message: &str) -> u32 {
+		let mut last_chat_message = self.inner.last_chat_message.lock();
+		match last_chat_message.as_mut() {
+			Some((last_message, repetitions)) if last_message == message => {
+				*repetitions = repetitions.saturating_add(1);
+				*repetitions
+			}
+			_ => {
+				*last_chat_message = Some((message.to_string(), 1));
+				1
+			}
+		}
+	}
+
+	/// Returns the previous typing state. Reporting that the client is typing also resets its expiry,
+	/// see [`Client::typing_reported`].
+	pub fn update_typing(&self, typing: bool) -> bool {
+		if typing {
+			self.inner.typing_notification.notify_one();
+		}
+		self.inner.typing.swap(typing, Ordering::Relaxed)
+	}
+
+	/// Resolves once the client reported that it is typing.
+	pub async fn typing_reported(&self) {
+		self.inner.typing_notification.notified().await;
+	}
+
+	/// Makes the client leave the room, see [`Client::kicked`].
+	pub fn kick(&self) {
+		self.inner.kick_notification.notify_one();
+	}
+
+	/// Resolves once the client was kicked.
+	pub async fn kicked(&self) {
+		self.inner.kick_notification.notified().await;
 	}
 
 	pub async fn send_success_message(&self, message: SuccessMessage, request_id: UInt) -> bool {
@@ -63,8 +269,30 @@ impl Client {
 		success
 	}
 
-	pub async fn send_broadcast_message(&self, message: impl Into<BroadcastMessage> + Unpin) -> bool {
-		let success = self.inner.connection.send_broadcast_message(message.into()).await;
+	pub async fn send_private_message(&self, message: PrivateMessage) -> bool {
+		let success = self.inner.connection.send_private_message(message).await;
+		if !success {
+			info!(
+				"Failed to send private message to client with id {} because it went away.",
+				self.inner.id
+			);
+		}
+		success
+	}
+
+	pub async fn send_moderator_notice(&self, message: ModeratorNotice) -> bool {
+		let success = self.inner.connection.send_moderator_notice(message).await;
+		if !success {
+			info!(
+				"Failed to send moderator notice to client with id {} because it went away.",
+				self.inner.id
+			);
+		}
+		success
+	}
+
+	pub async fn send_broadcast_message(&self, message: &SharedBroadcast) -> bool {
+		let success = self.inner.connection.send_broadcast_message(message).await;
 		if !success {
 			info!(
 				"Failed to send broadcast to client with id {} because it went away.",
@@ -74,11 +302,11 @@ impl Client {
 		success
 	}
 
-	pub fn enqueue_broadcast(&self, message: impl Into<BroadcastMessage> + Unpin, count: usize) {
-		self.inner.connection.enqueue_broadcast(message.into(), count);
+	pub fn enqueue_broadcast(&self, message: SharedBroadcast, count: usize) {
+		self.inner.connection.enqueue_broadcast(message, count);
 	}
 
-	pub async fn wait_for_broadcast(&self) -> BroadcastMessage {
+	pub async fn wait_for_broadcast(&self) -> SharedBroadcast {
 		self.inner.connection.wait_for_broadcast().await
 	}
 
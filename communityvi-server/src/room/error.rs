@@ -11,6 +11,8 @@ pub enum RoomError {
 	ClientNameAlreadyInUse,
 	#[error("Client name is too long. (>256 bytes UTF-8)")]
 	ClientNameTooLong,
+	#[error("Client name is reserved for a moderator.")]
+	ClientNameReserved,
 	#[error("Can't join, room is already full.")]
 	RoomFull,
 	#[error("Database error: {0}")]
@@ -31,3 +33,61 @@ impl From<UserCreationError> for RoomError {
 		}
 	}
 }
+
+#[derive(Error, Debug)]
+pub enum ChatError {
+	#[error("There is no chat message with this id to reply to.")]
+	UnknownReplyTarget,
+	#[error("Database error: {0}")]
+	Database(#[from] DatabaseError),
+	#[error("{0}")]
+	Overflow(#[from] OverflowError),
+}
+
+#[derive(Error, Debug)]
+pub enum PrivateMessageError {
+	#[error("There is no client with this id in the room.")]
+	UnknownRecipient,
+	#[error("Private messages can't be sent to yourself.")]
+	RecipientIsSender,
+	#[error("The recipient doesn't accept private messages.")]
+	NotAccepted,
+	#[error("Too many private messages, try again later.")]
+	RateLimited,
+	#[error("Database error: {0}")]
+	Database(#[from] DatabaseError),
+}
+
+#[derive(Error, Debug)]
+pub enum ChatModificationError {
+	#[error("There is no chat message with this id.")]
+	NotFound,
+	#[error("Only the author or a moderator can change a chat message.")]
+	NotAllowed,
+	#[error("Database error: {0}")]
+	Database(#[from] DatabaseError),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SeekError {
+	#[error("There is no medium to seek in.")]
+	NoMedium,
+	#[error("Live media can't be seeked.")]
+	LiveMedium,
+	#[error("The position is beyond the end of the medium.")]
+	BeyondEnd,
+}
+
+#[derive(Error, Debug)]
+pub enum PollError {
+	#[error("There is no poll with this id.")]
+	NotFound,
+	#[error("The poll has already been closed.")]
+	Closed,
+	#[error("The poll has no option with this index.")]
+	UnknownOption,
+	#[error("Only the creator or a moderator can close a poll.")]
+	NotAllowed,
+	#[error("Database error: {0}")]
+	Database(#[from] DatabaseError),
+}
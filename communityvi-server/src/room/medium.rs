@@ -1,9 +1,17 @@
 use crate::room::medium::fixed_length::FixedLengthMedium;
+use crate::room::medium::live::LiveMedium;
+use crate::room::medium::playback_state::PlaybackState;
+use crate::room::medium::subtitles::{SubtitleTrack, Subtitles};
+use crate::room::medium::url::UrlMedium;
 use chrono::Duration;
 use js_int::{UInt, uint};
 
+pub mod fingerprint;
 pub mod fixed_length;
+pub mod live;
 pub mod playback_state;
+pub mod subtitles;
+pub mod url;
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct VersionedMedium {
@@ -23,6 +31,69 @@ pub enum Medium {
 	#[default]
 	Empty,
 	FixedLength(FixedLengthMedium),
+	Url(UrlMedium),
+	Live(LiveMedium),
+}
+
+impl Medium {
+	/// Expected playback position at `reference_time`, `None` if there is no medium.
+	pub fn position_at(&self, reference_time: Duration) -> Option<Duration> {
+		match self {
+			Medium::Empty => None,
+			Medium::FixedLength(medium) => Some(medium.position_at(reference_time)),
+			Medium::Url(medium) => Some(medium.position_at(reference_time)),
+			Medium::Live(medium) => Some(medium.position_at(reference_time)),
+		}
+	}
+
+	pub fn playback(&self) -> Option<PlaybackState> {
+		match self {
+			Medium::Empty => None,
+			Medium::FixedLength(medium) => Some(medium.playback),
+			Medium::Url(medium) => Some(medium.playback),
+			Medium::Live(medium) => Some(medium.playback),
+		}
+	}
+
+	/// Identifies the content independent of its playback state and version, so bookmarks
+	/// still apply when the same medium is inserted again. `None` if there is no medium.
+	pub fn id(&self) -> Option<String> {
+		match self {
+			Medium::Empty => None,
+			Medium::FixedLength(medium) => Some(match (&medium.hosted_id, &medium.fingerprint) {
+				(Some(hosted_id), _) => format!("hosted:{hosted_id}"),
+				(None, Some(fingerprint)) => format!("fingerprint:{}", fingerprint.hash),
+				(None, None) => format!("fixed_length:{}:{}", medium.length.num_milliseconds(), medium.name),
+			}),
+			Medium::Url(medium) => Some(format!("url:{}", medium.url)),
+			Medium::Live(medium) => Some(format!("live:{}", medium.name)),
+		}
+	}
+
+	pub fn subtitles(&self) -> Option<&Subtitles> {
+		match self {
+			Medium::Empty => None,
+			Medium::FixedLength(medium) => Some(&medium.subtitles),
+			Medium::Url(medium) => Some(&medium.subtitles),
+			Medium::Live(medium) => Some(&medium.subtitles),
+		}
+	}
+
+	fn subtitles_mut(&mut self) -> Option<&mut Subtitles> {
+		match self {
+			Medium::Empty => None,
+			Medium::FixedLength(medium) => Some(&mut medium.subtitles),
+			Medium::Url(medium) => Some(&mut medium.subtitles),
+			Medium::Live(medium) => Some(&mut medium.subtitles),
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SubtitleUpdateError {
+	IncorrectMediumVersion,
+	NoMedium,
+	TrackLimitReached,
 }
 
 impl VersionedMedium {
@@ -41,6 +112,12 @@ impl VersionedMedium {
 			Medium::FixedLength(medium) => {
 				medium.play(start_time, reference_now);
 			}
+			Medium::Url(medium) => {
+				medium.play(start_time, reference_now);
+			}
+			Medium::Live(medium) => {
+				medium.play(start_time);
+			}
 		}
 		self.version += uint!(1);
 		Some(self.clone())
@@ -57,18 +134,73 @@ impl VersionedMedium {
 			Medium::FixedLength(medium) => {
 				medium.pause(at_position);
 			}
+			Medium::Url(medium) => {
+				medium.pause(at_position);
+			}
+			Medium::Live(medium) => {
+				medium.pause(at_position);
+			}
 		}
 		self.version += uint!(1);
 		Some(self.clone())
 	}
 }
 
+/// Subtitles belong to the medium but not to its playback, so changing them doesn't bump the version.
+/// Otherwise they would invalidate pending playback requests, scheduled plays and polls.
+impl VersionedMedium {
+	/// Attach `track` to the medium, replacing a track with identical content.
+	pub(super) fn add_subtitle_track(
+		&mut self,
+		track: SubtitleTrack,
+		medium_version: UInt,
+	) -> Result<Subtitles, SubtitleUpdateError> {
+		let subtitles = self.subtitles_for_update(medium_version)?;
+		let track_limit_reached = subtitles.tracks.len() >= Subtitles::TRACK_LIMIT;
+		match subtitles.tracks.iter_mut().find(|existing| existing.id == track.id) {
+			Some(existing) => *existing = track,
+			None if track_limit_reached => return Err(SubtitleUpdateError::TrackLimitReached),
+			None => subtitles.tracks.push(track),
+		}
+		Ok(subtitles.clone())
+	}
+
+	pub(super) fn set_subtitle_offset(
+		&mut self,
+		offset: Duration,
+		medium_version: UInt,
+	) -> Result<Subtitles, SubtitleUpdateError> {
+		let subtitles = self.subtitles_for_update(medium_version)?;
+		subtitles.offset = offset;
+		Ok(subtitles.clone())
+	}
+
+	fn subtitles_for_update(&mut self, medium_version: UInt) -> Result<&mut Subtitles, SubtitleUpdateError> {
+		if self.version != medium_version {
+			return Err(SubtitleUpdateError::IncorrectMediumVersion);
+		}
+		self.medium.subtitles_mut().ok_or(SubtitleUpdateError::NoMedium)
+	}
+}
+
 impl From<FixedLengthMedium> for Medium {
 	fn from(fixed_length_medium: FixedLengthMedium) -> Self {
 		Medium::FixedLength(fixed_length_medium)
 	}
 }
 
+impl From<UrlMedium> for Medium {
+	fn from(url_medium: UrlMedium) -> Self {
+		Medium::Url(url_medium)
+	}
+}
+
+impl From<LiveMedium> for Medium {
+	fn from(live_medium: LiveMedium) -> Self {
+		Medium::Live(live_medium)
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use crate::room::medium::VersionedMedium;
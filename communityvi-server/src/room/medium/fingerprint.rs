@@ -0,0 +1,7 @@
+/// Identifies the content of a local file, e.g. by hashing its first and last few MiB together with its size.
+/// The server never sees the file itself, so the hash is only ever compared, not verified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fingerprint {
+	pub size_in_bytes: u64,
+	pub hash: String,
+}
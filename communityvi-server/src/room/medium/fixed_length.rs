@@ -1,4 +1,6 @@
+use crate::room::medium::fingerprint::Fingerprint;
 use crate::room::medium::playback_state::PlaybackState;
+use crate::room::medium::subtitles::Subtitles;
 use chrono::Duration;
 
 /// A medium with a fixed length. e.g. Video file or online video.
@@ -6,6 +8,10 @@ use chrono::Duration;
 pub struct FixedLengthMedium {
 	pub length: Duration,
 	pub name: String,
+	pub fingerprint: Option<Fingerprint>,
+	/// Id in the server's media directory if the server hosts the medium itself.
+	pub hosted_id: Option<String>,
+	pub subtitles: Subtitles,
 	pub playback: PlaybackState,
 }
 
@@ -14,10 +20,29 @@ impl FixedLengthMedium {
 		Self {
 			length,
 			name,
+			fingerprint: None,
+			hosted_id: None,
+			subtitles: Subtitles::default(),
 			playback: PlaybackState::default(),
 		}
 	}
 
+	#[must_use]
+	pub fn with_fingerprint(self, fingerprint: Fingerprint) -> Self {
+		Self {
+			fingerprint: Some(fingerprint),
+			..self
+		}
+	}
+
+	#[must_use]
+	pub fn with_hosted_id(self, hosted_id: String) -> Self {
+		Self {
+			hosted_id: Some(hosted_id),
+			..self
+		}
+	}
+
 	pub(super) fn play(&mut self, start_time: Duration, reference_now: Duration) {
 		let medium_has_ended = (start_time + self.length) < reference_now;
 
@@ -30,6 +55,14 @@ impl FixedLengthMedium {
 		};
 	}
 
+	/// Position in the medium at `reference_time`, clamped to its length.
+	pub fn position_at(&self, reference_time: Duration) -> Duration {
+		match self.playback {
+			PlaybackState::Playing { start_time } => (reference_time - start_time).clamp(Duration::zero(), self.length),
+			PlaybackState::Paused { at_position } => at_position,
+		}
+	}
+
 	pub(super) fn pause(&mut self, at_position: Duration) {
 		// Don't pause before 0 or after the end.
 		let new_position = at_position.clamp(Duration::zero(), self.length);
@@ -165,4 +198,24 @@ mod test {
 			}
 		);
 	}
+
+	#[test]
+	fn should_calculate_position_while_playing() {
+		let mut medium = test_medium();
+		let now = 1000;
+		medium.play(Duration::seconds(now - 10), Duration::seconds(now));
+
+		assert_eq!(Duration::seconds(10), medium.position_at(Duration::seconds(now)));
+		assert_eq!(Duration::zero(), medium.position_at(Duration::seconds(now - 11)));
+		assert_eq!(medium.length, medium.position_at(Duration::seconds(now + 100)));
+	}
+
+	#[test]
+	fn should_calculate_position_while_paused() {
+		let mut medium = test_medium();
+
+		medium.pause(Duration::seconds(13));
+
+		assert_eq!(Duration::seconds(13), medium.position_at(Duration::seconds(1000)));
+	}
 }
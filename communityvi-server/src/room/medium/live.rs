@@ -0,0 +1,90 @@
+use crate::room::medium::playback_state::PlaybackState;
+use crate::room::medium::subtitles::Subtitles;
+use chrono::Duration;
+
+/// A medium without an end, e.g. a live stream.
+/// Playing means following the stream, pausing means no longer following it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LiveMedium {
+	pub name: String,
+	pub subtitles: Subtitles,
+	pub playback: PlaybackState,
+}
+
+impl LiveMedium {
+	pub fn new(name: String) -> Self {
+		Self {
+			name,
+			subtitles: Subtitles::default(),
+			playback: PlaybackState::default(),
+		}
+	}
+
+	pub(super) fn play(&mut self, start_time: Duration) {
+		self.playback = PlaybackState::Playing { start_time };
+	}
+
+	/// Position in the medium at `reference_time`, there is no end to clamp it to.
+	pub fn position_at(&self, reference_time: Duration) -> Duration {
+		match self.playback {
+			PlaybackState::Playing { start_time } => (reference_time - start_time).max(Duration::zero()),
+			PlaybackState::Paused { at_position } => at_position,
+		}
+	}
+
+	pub(super) fn pause(&mut self, at_position: Duration) {
+		self.playback = PlaybackState::Paused {
+			at_position: at_position.max(Duration::zero()),
+		};
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn test_medium() -> LiveMedium {
+		LiveMedium::new("Rocket Launch".to_string())
+	}
+
+	#[test]
+	fn should_keep_playing_indefinitely() {
+		let mut medium = test_medium();
+		let one_year = Duration::days(365);
+
+		medium.play(Duration::zero());
+
+		assert_eq!(
+			PlaybackState::Playing {
+				start_time: Duration::zero()
+			},
+			medium.playback
+		);
+		assert_eq!(one_year, medium.position_at(one_year));
+	}
+
+	#[test]
+	fn should_stop_following_when_paused() {
+		let mut medium = test_medium();
+		medium.play(Duration::zero());
+
+		medium.pause(Duration::days(365));
+
+		assert_eq!(
+			PlaybackState::Paused {
+				at_position: Duration::days(365)
+			},
+			medium.playback
+		);
+		assert_eq!(Duration::days(365), medium.position_at(Duration::days(1000)));
+	}
+
+	#[test]
+	fn should_not_pause_before_start() {
+		let mut medium = test_medium();
+
+		medium.pause(Duration::seconds(-1));
+
+		assert_eq!(Duration::zero(), medium.position_at(Duration::zero()));
+	}
+}
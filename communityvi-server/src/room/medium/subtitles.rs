@@ -0,0 +1,236 @@
+use chrono::Duration;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Subtitle tracks attached to a medium, shown shifted by `offset` in all clients.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Subtitles {
+	pub tracks: Vec<SubtitleTrack>,
+	pub offset: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubtitleTrack {
+	/// Derived from the content, so identical uploads share an id.
+	pub id: String,
+	pub label: String,
+	pub language: Option<String>,
+	pub webvtt: Arc<str>,
+}
+
+/// Subtitles uploaded via `/api/subtitles` that haven't necessarily been added to a medium yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubtitleUpload {
+	/// Derived from the content, so identical uploads share an id.
+	pub id: String,
+	pub webvtt: Arc<str>,
+}
+
+impl Subtitles {
+	pub const TRACK_LIMIT: usize = 16;
+}
+
+impl SubtitleUpload {
+	/// Parse SRT or `WebVTT` subtitles, converting them to `WebVTT`.
+	pub fn parse(content: &str) -> Result<Self, SubtitleError> {
+		let webvtt = to_webvtt(content)?;
+		let hash = Sha256::digest(webvtt.as_bytes());
+
+		Ok(Self {
+			id: hex::encode(&hash[..16]),
+			webvtt: webvtt.into(),
+		})
+	}
+
+	pub fn into_track(self, label: String, language: Option<String>) -> SubtitleTrack {
+		SubtitleTrack {
+			id: self.id,
+			label,
+			language,
+			webvtt: self.webvtt,
+		}
+	}
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SubtitleError {
+	#[error("Subtitles don't contain any cues.")]
+	NoCues,
+	#[error("Invalid cue timing in line {line}.")]
+	InvalidTiming { line: usize },
+}
+
+const WEBVTT_HEADER: &str = "WEBVTT";
+
+fn to_webvtt(content: &str) -> Result<String, SubtitleError> {
+	let content = content
+		.strip_prefix('\u{feff}')
+		.unwrap_or(content)
+		.replace("\r\n", "\n");
+	let is_webvtt = content
+		.strip_prefix(WEBVTT_HEADER)
+		.is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t', '\n']));
+
+	if is_webvtt {
+		validate_webvtt(&content)?;
+		Ok(content)
+	} else {
+		convert_srt(&content)
+	}
+}
+
+fn validate_webvtt(content: &str) -> Result<(), SubtitleError> {
+	let mut cue_count = 0;
+	for (index, line) in content.lines().enumerate() {
+		if line.contains("-->") {
+			parse_timing(line, '.').ok_or(SubtitleError::InvalidTiming { line: index + 1 })?;
+			cue_count += 1;
+		}
+	}
+
+	if cue_count == 0 {
+		return Err(SubtitleError::NoCues);
+	}
+	Ok(())
+}
+
+fn convert_srt(content: &str) -> Result<String, SubtitleError> {
+	let mut webvtt = format!("{WEBVTT_HEADER}\n");
+	let mut cue_count = 0;
+	let mut lines = content.lines().enumerate().peekable();
+	while let Some((index, line)) = lines.next() {
+		if line.trim().is_empty() {
+			continue;
+		}
+
+		// The numeric cue identifier is optional in practice.
+		let (index, line) = if line.contains("-->") {
+			(index, line)
+		} else {
+			lines.next().ok_or(SubtitleError::InvalidTiming { line: index + 2 })?
+		};
+		let (start, end) = parse_timing(line, ',').ok_or(SubtitleError::InvalidTiming { line: index + 1 })?;
+		cue_count += 1;
+
+		let _ = write!(
+			webvtt,
+			"\n{cue_count}\n{} --> {}\n",
+			format_timestamp(start),
+			format_timestamp(end)
+		);
+		while let Some((_, text)) = lines.next_if(|(_, line)| !line.trim().is_empty()) {
+			webvtt.push_str(text);
+			webvtt.push('\n');
+		}
+	}
+
+	if cue_count == 0 {
+		return Err(SubtitleError::NoCues);
+	}
+	Ok(webvtt)
+}
+
+/// Parse a timing line like `00:00:01,000 --> 00:00:02,500`, ignoring any cue settings after the end time.
+fn parse_timing(line: &str, fraction_separator: char) -> Option<(Duration, Duration)> {
+	let (start, rest) = line.split_once("-->")?;
+	let end = rest.split_whitespace().next()?;
+	let start = parse_timestamp(start.trim(), fraction_separator)?;
+	let end = parse_timestamp(end, fraction_separator)?;
+
+	(start <= end).then_some((start, end))
+}
+
+/// Parse `hh:mm:ss,ttt`, hours are optional.
+fn parse_timestamp(timestamp: &str, fraction_separator: char) -> Option<Duration> {
+	let (time, milliseconds) = timestamp.split_once(fraction_separator)?;
+	let mut components = time.rsplit(':');
+	let seconds = parse_component(components.next()?, 59)?;
+	let minutes = parse_component(components.next()?, 59)?;
+	let hours = components.next().map_or(Some(0), |hours| hours.parse::<i64>().ok())?;
+	if components.next().is_some() || milliseconds.len() != 3 {
+		return None;
+	}
+	let milliseconds = milliseconds.parse::<i64>().ok()?;
+
+	Some(
+		Duration::hours(hours)
+			+ Duration::minutes(minutes)
+			+ Duration::seconds(seconds)
+			+ Duration::milliseconds(milliseconds),
+	)
+}
+
+fn parse_component(component: &str, maximum: i64) -> Option<i64> {
+	if component.len() != 2 {
+		return None;
+	}
+	component.parse().ok().filter(|value| (0..=maximum).contains(value))
+}
+
+fn format_timestamp(timestamp: Duration) -> String {
+	format!(
+		"{:02}:{:02}:{:02}.{:03}",
+		timestamp.num_hours(),
+		timestamp.num_minutes() % 60,
+		timestamp.num_seconds() % 60,
+		timestamp.num_milliseconds() % 1000
+	)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const SRT: &str = "1\r\n00:00:01,000 --> 00:00:02,500\r\nHello there!\r\n\r\n2\r\n00:01:02,003 --> 01:00:00,000\r\nGeneral Kenobi!\r\nYou are a bold one.\r\n";
+
+	#[test]
+	fn should_convert_srt_to_webvtt() {
+		let upload = SubtitleUpload::parse(SRT).expect("Failed to parse SRT subtitles");
+
+		assert_eq!(
+			"WEBVTT\n\n1\n00:00:01.000 --> 00:00:02.500\nHello there!\n\n2\n00:01:02.003 --> 01:00:00.000\nGeneral Kenobi!\nYou are a bold one.\n",
+			&*upload.webvtt
+		);
+	}
+
+	#[test]
+	fn should_keep_webvtt() {
+		const WEBVTT: &str = "WEBVTT - Star Wars\n\n00:01.000 --> 00:02.500 align:start\nHello there!\n";
+
+		let upload = SubtitleUpload::parse(WEBVTT).expect("Failed to parse WebVTT subtitles");
+
+		assert_eq!(WEBVTT, &*upload.webvtt);
+	}
+
+	#[test]
+	fn should_give_identical_subtitles_the_same_id() {
+		let first = SubtitleUpload::parse(SRT).unwrap();
+		let second = SubtitleUpload::parse(&SRT.replace("\r\n", "\n")).unwrap();
+
+		assert_eq!(first.id, second.id);
+	}
+
+	#[test]
+	fn should_reject_invalid_timings() {
+		assert_eq!(
+			Err(SubtitleError::InvalidTiming { line: 2 }),
+			to_webvtt("1\n00:00:01.000 --> 00:00:02,500\nHello there!\n")
+		);
+		assert_eq!(
+			Err(SubtitleError::InvalidTiming { line: 3 }),
+			to_webvtt("WEBVTT\n\n00:02.000 --> 00:01.000\nHello there!\n")
+		);
+		assert_eq!(
+			Err(SubtitleError::InvalidTiming { line: 1 }),
+			to_webvtt("00:00:61,000 --> 00:01:00,000\nHello there!\n")
+		);
+	}
+
+	#[test]
+	fn should_reject_subtitles_without_cues() {
+		assert_eq!(Err(SubtitleError::NoCues), to_webvtt("WEBVTT\n"));
+		assert_eq!(Err(SubtitleError::NoCues), to_webvtt(""));
+	}
+}
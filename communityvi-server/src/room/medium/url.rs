@@ -0,0 +1,109 @@
+use crate::room::medium::playback_state::PlaybackState;
+use crate::room::medium::subtitles::Subtitles;
+use chrono::Duration;
+use mime::Mime;
+use url::Url;
+
+/// A medium that every client loads from the same URL, e.g. a web-hosted video.
+/// The length is only known if the client inserting it was able to determine it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UrlMedium {
+	pub url: Url,
+	pub mime_type: Option<Mime>,
+	pub length: Option<Duration>,
+	pub subtitles: Subtitles,
+	pub playback: PlaybackState,
+}
+
+impl UrlMedium {
+	pub fn new(url: Url, mime_type: Option<Mime>, length: Option<Duration>) -> Self {
+		Self {
+			url,
+			mime_type,
+			length,
+			subtitles: Subtitles::default(),
+			playback: PlaybackState::default(),
+		}
+	}
+
+	pub(super) fn play(&mut self, start_time: Duration, reference_now: Duration) {
+		let medium_has_ended = self.length.is_some_and(|length| (start_time + length) < reference_now);
+
+		self.playback = match self.length {
+			Some(length) if medium_has_ended => PlaybackState::Paused { at_position: length },
+			_ => PlaybackState::Playing { start_time },
+		};
+	}
+
+	/// Position in the medium at `reference_time`, clamped to its length if it is known.
+	pub fn position_at(&self, reference_time: Duration) -> Duration {
+		match self.playback {
+			PlaybackState::Playing { start_time } => self.clamp_position(reference_time - start_time),
+			PlaybackState::Paused { at_position } => at_position,
+		}
+	}
+
+	pub(super) fn pause(&mut self, at_position: Duration) {
+		self.playback = PlaybackState::Paused {
+			at_position: self.clamp_position(at_position),
+		};
+	}
+
+	fn clamp_position(&self, position: Duration) -> Duration {
+		let position = position.max(Duration::zero());
+		match self.length {
+			Some(length) => position.min(length),
+			None => position,
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn test_medium(length: Option<Duration>) -> UrlMedium {
+		UrlMedium::new(
+			Url::parse("https://example.com/universe.webm").unwrap(),
+			Some("video/webm".parse().unwrap()),
+			length,
+		)
+	}
+
+	#[test]
+	fn should_not_play_past_the_end_if_the_length_is_known() {
+		let mut medium = test_medium(Some(Duration::seconds(42)));
+
+		medium.play(Duration::seconds(0), Duration::seconds(100));
+
+		assert_eq!(
+			PlaybackState::Paused {
+				at_position: Duration::seconds(42)
+			},
+			medium.playback
+		);
+	}
+
+	#[test]
+	fn should_keep_playing_if_the_length_is_unknown() {
+		let mut medium = test_medium(None);
+
+		medium.play(Duration::seconds(0), Duration::seconds(100));
+
+		assert_eq!(Duration::seconds(100), medium.position_at(Duration::seconds(100)));
+	}
+
+	#[test]
+	fn should_only_clamp_pause_position_to_known_length() {
+		let mut known_length = test_medium(Some(Duration::seconds(42)));
+		let mut unknown_length = test_medium(None);
+
+		known_length.pause(Duration::seconds(1337));
+		unknown_length.pause(Duration::seconds(1337));
+		assert_eq!(Duration::seconds(42), known_length.position_at(Duration::zero()));
+		assert_eq!(Duration::seconds(1337), unknown_length.position_at(Duration::zero()));
+
+		unknown_length.pause(Duration::seconds(-1));
+		assert_eq!(Duration::zero(), unknown_length.position_at(Duration::zero()));
+	}
+}
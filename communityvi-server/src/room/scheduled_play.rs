@@ -0,0 +1,10 @@
+use chrono::Duration;
+use js_int::UInt;
+
+/// Pending start of playback for a specific version of the medium.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScheduledPlay {
+	pub medium_version: UInt,
+	/// Reference time at which the medium starts playing.
+	pub start_time: Duration,
+}
@@ -1,6 +1,6 @@
 use crate::connection::broadcast_buffer::BroadcastBuffer;
 use crate::connection::sender::MessageSender;
-use crate::room::client::Client;
+use crate::room::client::{Client, MessageQuotas};
 use crate::room::error::RoomError;
 use crate::room::session_id::SessionId;
 use crate::room::session_id_sequence::SessionIdSequence;
@@ -9,14 +9,16 @@ use std::collections::HashMap;
 
 pub struct SessionRepository {
 	maximum_size: usize,
+	message_quotas: MessageQuotas,
 	id_sequence: SessionIdSequence,
 	clients_by_id: HashMap<SessionId, Client>,
 }
 
 impl SessionRepository {
-	pub fn with_limit(limit: usize) -> SessionRepository {
+	pub fn new(limit: usize, message_quotas: MessageQuotas) -> SessionRepository {
 		Self {
 			maximum_size: limit,
+			message_quotas,
 			id_sequence: Default::default(),
 			clients_by_id: Default::default(),
 		}
@@ -27,6 +29,7 @@ impl SessionRepository {
 	pub fn add_and_return_existing(
 		&mut self,
 		user: User,
+		is_moderator: bool,
 		message_sender: MessageSender,
 	) -> Result<(Client, Vec<Client>), RoomError> {
 		if self.clients_by_id.len() >= self.maximum_size {
@@ -35,7 +38,14 @@ impl SessionRepository {
 
 		let id = self.id_sequence.next();
 		let broadcast_buffer = BroadcastBuffer::new(self.maximum_size);
-		let client = Client::new(id, user, broadcast_buffer, message_sender);
+		let client = Client::new(
+			id,
+			user,
+			is_moderator,
+			self.message_quotas,
+			broadcast_buffer,
+			message_sender,
+		);
 
 		let existing_clients = self.clients_by_id.values().cloned().collect();
 		if self.clients_by_id.insert(id, client.clone()).is_some() {
@@ -49,6 +59,10 @@ impl SessionRepository {
 		self.clients_by_id.remove(&session_id)
 	}
 
+	pub fn get(&self, session_id: SessionId) -> Option<&Client> {
+		self.clients_by_id.get(&session_id)
+	}
+
 	pub fn is_empty(&self) -> bool {
 		self.clients_by_id.is_empty()
 	}
@@ -62,31 +76,56 @@ impl SessionRepository {
 #[allow(clippy::non_ascii_literal)]
 mod test {
 	use super::*;
+	use crate::configuration::Configuration;
 	use crate::database::test::{DefaultTestFactory, TestFactory};
 	use crate::user::UserService;
 	use crate::utils::fake_message_sender::FakeMessageSender;
+	use std::num::NonZeroU32;
 
 	#[tokio::test]
 	async fn add_should_return_empty_list_when_adding_to_an_empty_list() {
 		let user_repository = user_repository();
 		let mut connection = DefaultTestFactory::connection().await;
-		let mut session_repository = SessionRepository::with_limit(10);
+		let mut session_repository = SessionRepository::new(10, MessageQuotas::from(&Configuration::test()));
 		let jake = user_repository
 			.create_user("Jake", connection.as_mut())
 			.await
 			.expect("Could not create user");
 		let jake_sender = FakeMessageSender::default();
 		let (_, existing_clients) = session_repository
-			.add_and_return_existing(jake, jake_sender.into())
+			.add_and_return_existing(jake, false, jake_sender.into())
 			.unwrap();
 		assert!(existing_clients.is_empty());
 	}
 
+	#[tokio::test]
+	async fn added_clients_should_have_the_configured_message_quotas() {
+		let user_repository = user_repository();
+		let mut connection = DefaultTestFactory::connection().await;
+		let message_quotas = MessageQuotas {
+			private_messages_per_minute: NonZeroU32::new(1).unwrap(),
+			chat_messages_per_minute: Some(NonZeroU32::new(1).unwrap()),
+		};
+		let mut session_repository = SessionRepository::new(10, message_quotas);
+		let jake = user_repository
+			.create_user("Jake", connection.as_mut())
+			.await
+			.expect("Could not create user");
+		let (jake, _) = session_repository
+			.add_and_return_existing(jake, false, FakeMessageSender::default().into())
+			.unwrap();
+
+		assert!(jake.check_private_message_quota());
+		assert!(!jake.check_private_message_quota());
+		assert!(jake.check_chat_message_quota());
+		assert!(!jake.check_chat_message_quota());
+	}
+
 	#[tokio::test]
 	async fn add_should_return_list_of_existing_clients() {
 		let user_repository = user_repository();
 		let mut connection = DefaultTestFactory::connection().await;
-		let mut session_repository = SessionRepository::with_limit(10);
+		let mut session_repository = SessionRepository::new(10, MessageQuotas::from(&Configuration::test()));
 		let jake = user_repository
 			.create_user("Jake", connection.as_mut())
 			.await
@@ -97,13 +136,13 @@ mod test {
 			.expect("Could not create user");
 		let jake_sender = FakeMessageSender::default();
 		let (jake, existing_clients) = session_repository
-			.add_and_return_existing(jake, jake_sender.into())
+			.add_and_return_existing(jake, false, jake_sender.into())
 			.unwrap();
 		assert!(existing_clients.is_empty());
 
 		let elwood_sender = FakeMessageSender::default();
 		let (_, existing_clients) = session_repository
-			.add_and_return_existing(elwood, elwood_sender.into())
+			.add_and_return_existing(elwood, false, elwood_sender.into())
 			.unwrap();
 		assert_eq!(existing_clients.len(), 1);
 		let existing_jake = &existing_clients[0];
@@ -115,7 +154,7 @@ mod test {
 	async fn should_track_if_there_are_any_clients_left() {
 		let user_repository = user_repository();
 		let mut connection = DefaultTestFactory::connection().await;
-		let mut session_repository = SessionRepository::with_limit(2);
+		let mut session_repository = SessionRepository::new(2, MessageQuotas::from(&Configuration::test()));
 		let ferris = user_repository
 			.create_user("Ferris", connection.as_mut())
 			.await
@@ -127,11 +166,11 @@ mod test {
 
 		let ferris_connection = MessageSender::from(FakeMessageSender::default());
 		let (ferris_client, _) = session_repository
-			.add_and_return_existing(ferris, ferris_connection)
+			.add_and_return_existing(ferris, false, ferris_connection)
 			.expect("Could not add Ferris!");
 		let spidey_connection = MessageSender::from(FakeMessageSender::default());
 		let (spidey_client, _) = session_repository
-			.add_and_return_existing(spidey, spidey_connection)
+			.add_and_return_existing(spidey, false, spidey_connection)
 			.expect("Could not add Spidey!");
 
 		session_repository.remove(ferris_client.id());
@@ -147,7 +186,7 @@ mod test {
 			.expect("Could not create Crab!");
 		let crab_connection = MessageSender::from(FakeMessageSender::default());
 		session_repository
-			.add_and_return_existing(crab, crab_connection)
+			.add_and_return_existing(crab, false, crab_connection)
 			.expect("Could not add client!");
 	}
 
@@ -155,7 +194,7 @@ mod test {
 	async fn should_allow_adding_clients_up_to_limit() {
 		let user_repository = user_repository();
 		let mut connection = DefaultTestFactory::connection().await;
-		let mut session_repository = SessionRepository::with_limit(2);
+		let mut session_repository = SessionRepository::new(2, MessageQuotas::from(&Configuration::test()));
 		for count in 1..=2 {
 			let user = user_repository
 				.create_user(&format!("{count}"), connection.as_mut())
@@ -163,7 +202,7 @@ mod test {
 				.expect("Could not create user!");
 			let message_sender = MessageSender::from(FakeMessageSender::default());
 
-			if let Err(error) = session_repository.add_and_return_existing(user, message_sender.clone()) {
+			if let Err(error) = session_repository.add_and_return_existing(user, false, message_sender.clone()) {
 				panic!("Failed to add client {count}: {error}");
 			}
 		}
@@ -173,7 +212,7 @@ mod test {
 	async fn should_not_allow_adding_more_clients_than_limit() {
 		let user_repository = user_repository();
 		let mut connection = DefaultTestFactory::connection().await;
-		let mut session_repository = SessionRepository::with_limit(2);
+		let mut session_repository = SessionRepository::new(2, MessageQuotas::from(&Configuration::test()));
 		for count in 1..=2 {
 			let user = user_repository
 				.create_user(&format!("{count}"), connection.as_mut())
@@ -181,7 +220,7 @@ mod test {
 				.expect("Could not create user!");
 			let message_sender = MessageSender::from(FakeMessageSender::default());
 
-			if let Err(error) = session_repository.add_and_return_existing(user, message_sender.clone()) {
+			if let Err(error) = session_repository.add_and_return_existing(user, false, message_sender.clone()) {
 				panic!("Failed to add client {count}: {error}");
 			}
 		}
@@ -191,7 +230,7 @@ mod test {
 			.await
 			.expect("Could not create user!");
 		let message_sender = MessageSender::from(FakeMessageSender::default());
-		let result = session_repository.add_and_return_existing(elephant, message_sender);
+		let result = session_repository.add_and_return_existing(elephant, false, message_sender);
 		assert!(matches!(result, Err(RoomError::RoomFull)));
 	}
 
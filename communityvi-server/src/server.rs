@@ -3,9 +3,12 @@ use crate::connection::receiver::MessageReceiver;
 use crate::connection::sender::MessageSender;
 use crate::context::ApplicationContext;
 use crate::error::CommunityviError;
-use crate::lifecycle::run_client;
+use crate::lifecycle::{close_open_polls_at_their_deadline, run_client};
+use crate::message::compression::MessageCompression;
+use crate::message::format::MessageFormat;
 use crate::room::Room;
 use crate::server::rest_api::{finish_openapi_specification, rest_api};
+use crate::server::sse::SseSessions;
 use crate::utils::websocket_message_conversion::{
 	axum_websocket_message_to_tungstenite_message, tungstenite_message_to_axum_websocket_message,
 };
@@ -21,7 +24,9 @@ use std::future::ready;
 use std::sync::Arc;
 
 mod file_bundle;
+mod hosted_media;
 mod rest_api;
+mod sse;
 
 pub async fn run_server(application_context: ApplicationContext) -> Result<(), CommunityviError> {
 	let default_room = application_context
@@ -32,11 +37,12 @@ pub async fn run_server(application_context: ApplicationContext) -> Result<(), C
 	let room = Room::new(
 		default_room.uuid,
 		application_context.reference_timer.clone(),
-		application_context.configuration.room_size_limit,
+		&application_context.configuration,
 		application_context.database.clone(),
 		application_context.user_service.clone(),
 		application_context.repository.clone(),
 	);
+	close_open_polls_at_their_deadline(&application_context.time_source, &room).await?;
 	let address = application_context.configuration.address;
 
 	axum_server::Server::bind(address)
@@ -59,10 +65,13 @@ pub fn create_router(application_context: ApplicationContext, room: Room) -> Rou
 				operation.summary("Start a websocket client session")
 			}),
 		)
+		.route("/sse", axum::routing::get(sse::sse_handler))
+		.route("/sse/{session_token}", axum::routing::post(sse::request_handler))
 		.nest_api_service("/api", rest_api().with_state(application_context.clone()))
 		.finish_api_with(&mut api_specification, finish_openapi_specification)
 		.with_state(application_context)
 		.layer(Extension(room))
+		.layer(Extension(SseSessions::default()))
 		.layer(Extension(
 			OpenApiJson::try_from(api_specification).expect("Failed to serialize generated OpenAPI specification"),
 		));
@@ -111,17 +120,41 @@ async fn websocket_handler(
 	Extension(room): Extension<Room>,
 	State(application_context): State<ApplicationContext>,
 ) -> impl IntoApiResponse {
+	let websocket = websocket.protocols(MessageCompression::websocket_protocols());
+	let (message_format, compressed) = websocket
+		.selected_protocol()
+		.and_then(|protocol| protocol.to_str().ok())
+		.and_then(MessageCompression::parse_websocket_protocol)
+		.unwrap_or_default();
+	let message_compression = compressed.then(|| application_context.configuration.message_compression());
+
 	websocket
-		.max_message_size(10 * 1024)
-		.max_frame_size(10 * 1024)
-		.on_upgrade(move |websocket| run_websocket_connection(websocket, room, application_context))
+		.max_message_size(application_context.configuration.websocket_message_size_limit)
+		.max_frame_size(application_context.configuration.websocket_frame_size_limit)
+		.on_upgrade(move |websocket| {
+			run_websocket_connection(
+				websocket,
+				message_format,
+				message_compression,
+				room,
+				application_context,
+			)
+		})
 }
 
-async fn run_websocket_connection(websocket: WebSocket, room: Room, application_context: ApplicationContext) {
+async fn run_websocket_connection(
+	websocket: WebSocket,
+	message_format: MessageFormat,
+	message_compression: Option<MessageCompression>,
+	room: Room,
+	application_context: ApplicationContext,
+) {
 	let (sink, stream) = websocket.split();
 
 	let message_sender =
-		MessageSender::from(sink.with(|message| ready(tungstenite_message_to_axum_websocket_message(message))));
+		MessageSender::from(sink.with(|message| ready(tungstenite_message_to_axum_websocket_message(message))))
+			.with_format(message_format)
+			.with_compression(message_compression);
 	let message_receiver = MessageReceiver::new(
 		stream
 			.map_ok(axum_websocket_message_to_tungstenite_message)
@@ -169,14 +169,18 @@ impl BundledFile {
 	}
 
 	fn is_cached(&self, request_headers: &HeaderMap) -> bool {
-		match request_headers.get(IF_NONE_MATCH) {
-			Some(if_none_match) => &self.etag() == if_none_match,
-			None => false,
-		}
+		is_cached(&self.etag(), request_headers)
+	}
+}
+
+pub(super) fn is_cached(etag: &str, request_headers: &HeaderMap) -> bool {
+	match request_headers.get(IF_NONE_MATCH) {
+		Some(if_none_match) => etag == if_none_match,
+		None => false,
 	}
 }
 
-fn last_modified_header_value(date_time: DateTime<Utc>) -> String {
+pub(super) fn last_modified_header_value(date_time: DateTime<Utc>) -> String {
 	// https://httpwg.org/specs/rfc7231.html#http.date
 	date_time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
 }
@@ -185,7 +189,7 @@ fn normalize_path(path: &str) -> &str {
 	path.trim_matches('/')
 }
 
-fn not_found() -> Response<Body> {
+pub(super) fn not_found() -> Response<Body> {
 	const STATUS: StatusCode = StatusCode::NOT_FOUND;
 	Response::builder()
 		.status(STATUS)
@@ -194,7 +198,7 @@ fn not_found() -> Response<Body> {
 		.unwrap()
 }
 
-fn not_modified() -> Response<Body> {
+pub(super) fn not_modified() -> Response<Body> {
 	const STATUS: StatusCode = StatusCode::NOT_MODIFIED;
 	Response::builder()
 		.status(STATUS)
@@ -0,0 +1,200 @@
+use crate::media_directory::{HostedMedium, MediaDirectory};
+use crate::server::file_bundle::{is_cached, last_modified_header_value, not_found, not_modified};
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::header::{
+	ACCEPT_RANGES, CACHE_CONTROL, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE,
+};
+use axum::http::{HeaderMap, Response, StatusCode};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::io::SeekFrom;
+use std::ops::RangeInclusive;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+use tracing::error;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct HostedMediumPath {
+	/// As listed by `/api/media`.
+	id: String,
+}
+
+/// Stream a file from the media directory, supporting single byte ranges so clients can seek.
+pub async fn hosted_medium(
+	State(media_directory): State<MediaDirectory>,
+	Path(HostedMediumPath { id }): Path<HostedMediumPath>,
+	request_headers: HeaderMap,
+) -> Response<Body> {
+	let Some(medium) = media_directory.medium(&id) else {
+		return not_found();
+	};
+
+	let etag = medium.etag();
+	if is_cached(&etag, &request_headers) {
+		return not_modified();
+	}
+
+	let range = if range_is_still_valid(medium, &request_headers) {
+		requested_range(
+			request_headers.get(RANGE).and_then(|range| range.to_str().ok()),
+			medium.size_in_bytes,
+		)
+	} else {
+		ByteRange::Full
+	};
+
+	match respond_with_range(medium, &etag, range).await {
+		Ok(response) => response,
+		Err(error) => {
+			error!("Failed to read hosted medium '{}': {error}", medium.path.display());
+			Response::builder()
+				.status(StatusCode::INTERNAL_SERVER_ERROR)
+				.body(Body::empty())
+				.unwrap()
+		}
+	}
+}
+
+async fn respond_with_range(medium: &HostedMedium, etag: &str, range: ByteRange) -> std::io::Result<Response<Body>> {
+	let builder = Response::builder()
+		.header(ACCEPT_RANGES, "bytes")
+		.header(CACHE_CONTROL, "must-revalidate")
+		.header(ETAG, etag);
+	let builder = if let Some(last_modified) = medium.last_modified.map(last_modified_header_value) {
+		builder.header(LAST_MODIFIED, last_modified)
+	} else {
+		builder
+	};
+
+	let mut file = File::open(&medium.path).await?;
+	let response = match range {
+		ByteRange::Full => builder
+			.status(StatusCode::OK)
+			.header(CONTENT_TYPE, medium.mime_type.as_ref())
+			.header(CONTENT_LENGTH, medium.size_in_bytes)
+			.body(Body::from_stream(ReaderStream::new(file))),
+		ByteRange::Partial(range) => {
+			let length = range.end() - range.start() + 1;
+			file.seek(SeekFrom::Start(*range.start())).await?;
+			builder
+				.status(StatusCode::PARTIAL_CONTENT)
+				.header(CONTENT_TYPE, medium.mime_type.as_ref())
+				.header(CONTENT_LENGTH, length)
+				.header(
+					CONTENT_RANGE,
+					format!("bytes {}-{}/{}", range.start(), range.end(), medium.size_in_bytes),
+				)
+				.body(Body::from_stream(ReaderStream::new(file.take(length))))
+		}
+		ByteRange::Unsatisfiable => builder
+			.status(StatusCode::RANGE_NOT_SATISFIABLE)
+			.header(CONTENT_RANGE, format!("bytes */{}", medium.size_in_bytes))
+			.body(Body::empty()),
+	};
+
+	Ok(response.unwrap())
+}
+
+/// A range request with `If-Range` only applies if the file hasn't changed since, otherwise it is served in full.
+fn range_is_still_valid(medium: &HostedMedium, request_headers: &HeaderMap) -> bool {
+	let Some(if_range) = request_headers.get(IF_RANGE) else {
+		return true;
+	};
+
+	if_range == medium.etag().as_str()
+		|| medium
+			.last_modified
+			.map(last_modified_header_value)
+			.is_some_and(|last_modified| if_range == last_modified.as_str())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRange {
+	Full,
+	Partial(RangeInclusive<u64>),
+	Unsatisfiable,
+}
+
+/// Parse a `Range` header with a single byte range, see <https://httpwg.org/specs/rfc9110.html#field.range>.
+/// Invalid and multipart ranges are ignored, which the specification allows.
+fn requested_range(range: Option<&str>, size: u64) -> ByteRange {
+	let Some((start, end)) = range
+		.and_then(|range| range.trim().strip_prefix("bytes="))
+		.filter(|range| !range.contains(','))
+		.and_then(|range| range.split_once('-'))
+		.map(|(start, end)| (start.trim(), end.trim()))
+	else {
+		return ByteRange::Full;
+	};
+
+	if start.is_empty() {
+		// suffix range, e.g. the last 500 bytes
+		return match end.parse::<u64>() {
+			Ok(0) => ByteRange::Unsatisfiable,
+			Ok(_) if size == 0 => ByteRange::Unsatisfiable,
+			Ok(suffix_length) => ByteRange::Partial(size.saturating_sub(suffix_length)..=(size - 1)),
+			Err(_) => ByteRange::Full,
+		};
+	}
+
+	let Ok(start) = start.parse::<u64>() else {
+		return ByteRange::Full;
+	};
+	let end = if end.is_empty() {
+		None
+	} else {
+		match end.parse::<u64>() {
+			Ok(end) if end >= start => Some(end),
+			_ => return ByteRange::Full,
+		}
+	};
+
+	if start >= size {
+		return ByteRange::Unsatisfiable;
+	}
+
+	let last_byte = size - 1;
+	ByteRange::Partial(start..=end.map_or(last_byte, |end| end.min(last_byte)))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn should_serve_everything_without_range() {
+		assert_eq!(ByteRange::Full, requested_range(None, 1000));
+	}
+
+	#[test]
+	fn should_parse_byte_ranges() {
+		assert_eq!(ByteRange::Partial(0..=499), requested_range(Some("bytes=0-499"), 1000));
+		assert_eq!(ByteRange::Partial(500..=999), requested_range(Some("bytes=500-"), 1000));
+		assert_eq!(ByteRange::Partial(900..=999), requested_range(Some("bytes=-100"), 1000));
+	}
+
+	#[test]
+	fn should_clamp_byte_ranges_to_the_size() {
+		assert_eq!(
+			ByteRange::Partial(500..=999),
+			requested_range(Some("bytes=500-5000"), 1000)
+		);
+		assert_eq!(ByteRange::Partial(0..=999), requested_range(Some("bytes=-5000"), 1000));
+	}
+
+	#[test]
+	fn should_not_satisfy_ranges_past_the_end() {
+		assert_eq!(ByteRange::Unsatisfiable, requested_range(Some("bytes=1000-"), 1000));
+		assert_eq!(ByteRange::Unsatisfiable, requested_range(Some("bytes=-0"), 1000));
+	}
+
+	#[test]
+	fn should_ignore_invalid_and_multipart_ranges() {
+		assert_eq!(ByteRange::Full, requested_range(Some("bytes=500-100"), 1000));
+		assert_eq!(ByteRange::Full, requested_range(Some("bytes=a-b"), 1000));
+		assert_eq!(ByteRange::Full, requested_range(Some("lines=1-2"), 1000));
+		assert_eq!(ByteRange::Full, requested_range(Some("bytes=0-1,5-6"), 1000));
+	}
+}
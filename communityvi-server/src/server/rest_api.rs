@@ -1,23 +1,38 @@
 // Pass by reference doesn't seem to be supported by rweb here
-// NOTE: This is regarding `reference_time_milliseconds` below, but rweb throws these attributes away entirely
+// NOTE: This is regarding `reference_time_milliseconds` and `time_sync` below, but rweb throws these attributes away entirely
 //       therefore needs to be global to the module.
 #![allow(clippy::needless_pass_by_value)]
 
 use crate::context::ApplicationContext;
+use crate::media_directory::{HostedMedium, MediaDirectory};
+use crate::message::outgoing::success_message::TimeSyncResponse;
 use crate::reference_time::ReferenceTimer;
-use crate::server::OpenApiJson;
-use aide::axum::routing::get_with;
+use crate::room::Room;
+use crate::room::medium::subtitles::SubtitleUpload;
+use crate::server::file_bundle::{is_cached, not_found, not_modified};
+use crate::server::{OpenApiJson, hosted_media};
+use crate::types::uuid::Uuid;
+use aide::axum::routing::{get_with, post_with};
 use aide::axum::{ApiRouter, IntoApiResponse};
 use aide::transform::TransformOpenApi;
-use axum::extract::State;
+use axum::body::Body;
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
+use axum::http::header::{AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, ETAG};
+use axum::http::{HeaderMap, Response, StatusCode};
 use axum::response::IntoResponse;
 use axum::routing::get;
 use axum::{Extension, Json, Router};
+use js_int::UInt;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use tower_http::cors::CorsLayer;
 
 #[cfg(feature = "api-docs")]
 mod api_docs;
 
+/// Subtitles of a feature film are usually well below 200 KiB.
+const SUBTITLE_SIZE_LIMIT: usize = 1024 * 1024;
+
 pub fn rest_api() -> ApiRouter<ApplicationContext> {
 	ApiRouter::new()
 		.api_route(
@@ -27,6 +42,58 @@ pub fn rest_api() -> ApiRouter<ApplicationContext> {
 				.summary("Return current server reference time in milliseconds")
 				.description("The reference time is the common time that all participants are synchronized on and that all operations refer to.")
 			))
+		.api_route(
+			"/time-sync",
+			get_with(time_sync,
+			|operation| operation
+				.summary("Synchronize with the server reference time")
+				.description("Like in NTP, the client's originate timestamp is returned together with the times the request was received and the response was sent, \
+					so that clients can calculate the round trip time and their offset to the reference time.")
+			))
+		.api_route(
+			"/media",
+			get_with(media, |operation| {
+				operation
+					.summary("List the media hosted by the server")
+					.description("Media can be inserted into a room by their id and streamed from `/api/media/{id}`.")
+			}),
+		)
+		.api_route(
+			"/media/{id}",
+			get_with(hosted_media::hosted_medium, |operation| {
+				operation
+					.summary("Stream a medium hosted by the server")
+					.description("Single byte ranges are supported so that clients can seek.")
+			}),
+		)
+		.api_route(
+			"/subtitles",
+			post_with(upload_subtitles, |operation| {
+				operation
+					.summary("Upload SRT or WebVTT subtitles")
+					.description("The returned id can be used to add the subtitles to the current medium of the room. \
+						Subtitles are uploaded here because they usually exceed the websocket message size limit. \
+						Only registered clients may upload subtitles, authenticated as `Authorization: Bearer <session_token>` \
+						with the session token from the hello response.")
+					.response::<200, Json<SubtitleUploadResponse>>()
+					.response_with::<400, String, _>(|response| response.description("Invalid subtitles"))
+					.response_with::<401, (), _>(|response| {
+						response.description("Missing or unknown session token")
+					})
+					.response_with::<413, (), _>(|response| response.description("Subtitles are too large"))
+			})
+			.layer(DefaultBodyLimit::max(SUBTITLE_SIZE_LIMIT)),
+		)
+		.api_route(
+			"/subtitles/{id}",
+			get_with(subtitle_track, |operation| {
+				operation
+					.summary("Download a subtitle track of the current medium as WebVTT")
+					.response_with::<404, (), _>(|response| {
+						response.description("The track doesn't belong to the current medium")
+					})
+			}),
+		)
 		.route("/openapi.json", get(openapi_specification))
 		.merge(stoplight_elements())
 		.layer(CorsLayer::very_permissive())
@@ -55,7 +122,116 @@ async fn openapi_specification(Extension(specification): Extension<OpenApiJson>)
 	Json(specification)
 }
 
+#[derive(Deserialize, JsonSchema)]
+struct TimeSyncQuery {
+	#[schemars(with = "Option<u64>")]
+	originate_timestamp_in_milliseconds: Option<UInt>,
+}
+
 async fn reference_time_milliseconds(State(reference_timer): State<ReferenceTimer>) -> impl IntoApiResponse {
 	let milliseconds = u64::from(reference_timer.reference_time_milliseconds());
 	Json(milliseconds)
 }
+
+async fn time_sync(
+	State(reference_timer): State<ReferenceTimer>,
+	Query(TimeSyncQuery {
+		originate_timestamp_in_milliseconds,
+	}): Query<TimeSyncQuery>,
+) -> impl IntoApiResponse {
+	let receive_timestamp_in_milliseconds = reference_timer.reference_time_milliseconds();
+	Json(TimeSyncResponse {
+		originate_timestamp_in_milliseconds,
+		receive_timestamp_in_milliseconds,
+		transmit_timestamp_in_milliseconds: reference_timer.reference_time_milliseconds(),
+	})
+}
+
+#[derive(Serialize, JsonSchema)]
+struct HostedMediumResponse {
+	id: String,
+	name: String,
+	length_in_milliseconds: u64,
+	size_in_bytes: u64,
+	mime_type: String,
+}
+
+impl From<&HostedMedium> for HostedMediumResponse {
+	fn from(medium: &HostedMedium) -> Self {
+		Self {
+			id: medium.id.clone(),
+			name: medium.name.clone(),
+			length_in_milliseconds: u64::try_from(medium.length.num_milliseconds()).unwrap_or_default(),
+			size_in_bytes: medium.size_in_bytes,
+			mime_type: medium.mime_type.to_string(),
+		}
+	}
+}
+
+async fn media(State(media_directory): State<MediaDirectory>) -> impl IntoApiResponse {
+	Json(
+		media_directory
+			.media()
+			.map(HostedMediumResponse::from)
+			.collect::<Vec<_>>(),
+	)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct SubtitleUploadResponse {
+	id: String,
+}
+
+async fn upload_subtitles(Extension(room): Extension<Room>, headers: HeaderMap, content: String) -> Response<Body> {
+	let session_token = headers
+		.get(AUTHORIZATION)
+		.and_then(|authorization| authorization.to_str().ok())
+		.and_then(|authorization| authorization.strip_prefix("Bearer "))
+		.and_then(|session_token| session_token.parse::<uuid::Uuid>().ok());
+	let Some(session_token) = session_token else {
+		return StatusCode::UNAUTHORIZED.into_response();
+	};
+	if room.client_by_session_token(Uuid::from(session_token)).await.is_none() {
+		return StatusCode::UNAUTHORIZED.into_response();
+	}
+
+	match SubtitleUpload::parse(&content) {
+		Ok(upload) => {
+			let id = upload.id.clone();
+			room.upload_subtitles(upload);
+			Json(SubtitleUploadResponse { id }).into_response()
+		}
+		Err(error) => (StatusCode::BAD_REQUEST, format!("Invalid subtitles: {error}")).into_response(),
+	}
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct SubtitleTrackPath {
+	/// As listed in the subtitles of the medium.
+	id: String,
+}
+
+/// Subtitle tracks of the current medium as `WebVTT`.
+async fn subtitle_track(
+	Extension(room): Extension<Room>,
+	Path(SubtitleTrackPath { id }): Path<SubtitleTrackPath>,
+	request_headers: HeaderMap,
+) -> Response<Body> {
+	let Some(track) = room.subtitle_track(&id) else {
+		return not_found();
+	};
+
+	// The id is derived from the content
+	let etag = format!(r#""{}""#, track.id);
+	if is_cached(&etag, &request_headers) {
+		return not_modified();
+	}
+
+	Response::builder()
+		.status(StatusCode::OK)
+		.header(CONTENT_TYPE, "text/vtt; charset=utf-8")
+		.header(CACHE_CONTROL, "must-revalidate")
+		.header(ETAG, etag)
+		.body(Body::from(track.webvtt.to_string()))
+		.unwrap()
+}
@@ -0,0 +1,218 @@
+//! Fallback transport for clients that can't use websockets, e.g. because a proxy breaks them.
+//!
+//! Outgoing messages are sent as server-sent events on `GET /sse`. The first event is a `session` event
+//! with the session token as data. Requests are sent as JSON `ClientRequestWithId` in the body of
+//! `POST /sse/{session_token}`. Heartbeat pings are sent as SSE comments and are answered on behalf of the
+//! client as soon as they have been handed to the HTTP connection.
+use crate::configuration::Configuration;
+use crate::connection::receiver::MessageReceiver;
+use crate::connection::sender::MessageSender;
+use crate::context::ApplicationContext;
+use crate::lifecycle::run_client;
+use crate::message::WebSocketMessage;
+use crate::room::Room;
+use crate::types::uuid::Uuid;
+use axum::extract::{Extension, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::sse::{Event, Sse};
+use futures_channel::mpsc;
+use futures_util::{SinkExt, Stream, StreamExt, stream};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::future::ready;
+use std::sync::Arc;
+use tracing::error;
+
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Request channels of all currently connected SSE clients by their session token.
+#[derive(Clone, Default)]
+pub struct SseSessions {
+	request_senders: Arc<Mutex<BTreeMap<Uuid, mpsc::Sender<WebSocketMessage>>>>,
+}
+
+impl SseSessions {
+	fn insert(&self, session_token: Uuid, request_sender: mpsc::Sender<WebSocketMessage>) {
+		self.request_senders.lock().insert(session_token, request_sender);
+	}
+
+	fn get(&self, session_token: Uuid) -> Option<mpsc::Sender<WebSocketMessage>> {
+		self.request_senders.lock().get(&session_token).cloned()
+	}
+
+	fn remove(&self, session_token: Uuid) {
+		self.request_senders.lock().remove(&session_token);
+	}
+}
+
+/// Removes the session when it is dropped together with its events. This also covers clients that disconnect
+/// before registering, because removing the session drops the last request sender, which ends the registration.
+struct SseSession {
+	sse_sessions: SseSessions,
+	session_token: Uuid,
+}
+
+impl Drop for SseSession {
+	fn drop(&mut self) {
+		self.sse_sessions.remove(self.session_token);
+	}
+}
+
+pub async fn sse_handler(
+	Extension(room): Extension<Room>,
+	Extension(sse_sessions): Extension<SseSessions>,
+	State(application_context): State<ApplicationContext>,
+) -> impl IntoResponse {
+	let session_token = Uuid::new_v4();
+	let (request_sender, request_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+	let (outgoing_sender, outgoing_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+	let events = session_events(sse_sessions, session_token, request_sender, outgoing_receiver);
+
+	let message_sender = MessageSender::from(outgoing_sender.sink_map_err(Into::into));
+	let message_receiver = MessageReceiver::new(request_receiver.map(Ok), message_sender.clone());
+	tokio::spawn(run_client(application_context, room, message_sender, message_receiver));
+
+	Sse::new(events.map(Ok::<_, Infallible>))
+}
+
+/// Adds the session and returns its events, starting with the session token.
+/// The session is removed as soon as the events are dropped, i.e. when the client disconnects.
+fn session_events(
+	sse_sessions: SseSessions,
+	session_token: Uuid,
+	request_sender: mpsc::Sender<WebSocketMessage>,
+	outgoing_receiver: impl Stream<Item = WebSocketMessage>,
+) -> impl Stream<Item = Event> {
+	sse_sessions.insert(session_token, request_sender.clone());
+	let session = SseSession {
+		sse_sessions,
+		session_token,
+	};
+
+	let session_event = Event::default().event("session").data(session_token.to_string());
+	stream::once(ready(session_event))
+		.chain(outgoing_events(outgoing_receiver, request_sender))
+		.map(move |event| {
+			let _session = &session;
+			event
+		})
+}
+
+/// Converts outgoing websocket messages to events until the connection is closed.
+fn outgoing_events(
+	outgoing_receiver: impl Stream<Item = WebSocketMessage>,
+	mut pong_sender: mpsc::Sender<WebSocketMessage>,
+) -> impl Stream<Item = Event> {
+	outgoing_receiver
+		.take_while(|message| ready(!message.is_close()))
+		.filter_map(move |message| {
+			let event = match message {
+				WebSocketMessage::Text(text) => Some(Event::default().data(text.as_str())),
+				WebSocketMessage::Ping(payload) => {
+					// The client has no way to respond to the heartbeat, so a ping counts as answered
+					// as soon as it is passed on to the HTTP connection.
+					if pong_sender.try_send(WebSocketMessage::Pong(payload)).is_err() {
+						error!("Failed to answer heartbeat of SSE client.");
+					}
+					Some(Event::default().comment("heartbeat"))
+				}
+				message => {
+					error!("Unsupported message for SSE: {message:?}");
+					None
+				}
+			};
+			ready(event)
+		})
+}
+
+pub async fn request_handler(
+	Path(session_token): Path<String>,
+	Extension(sse_sessions): Extension<SseSessions>,
+	State(configuration): State<Configuration>,
+	request: String,
+) -> StatusCode {
+	if request.len() > configuration.websocket_message_size_limit {
+		return StatusCode::PAYLOAD_TOO_LARGE;
+	}
+
+	let Some(mut request_sender) = session_token
+		.parse::<uuid::Uuid>()
+		.ok()
+		.and_then(|session_token| sse_sessions.get(session_token.into()))
+	else {
+		return StatusCode::NOT_FOUND;
+	};
+
+	match request_sender.send(WebSocketMessage::text(request)).await {
+		Ok(()) => StatusCode::ACCEPTED,
+		// The client has disconnected in the meantime
+		Err(_) => StatusCode::NOT_FOUND,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[tokio::test]
+	async fn should_send_heartbeats_as_comments_and_answer_them() {
+		let (mut outgoing_sender, outgoing_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+		let (pong_sender, mut pong_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+		let mut events = Box::pin(outgoing_events(outgoing_receiver, pong_sender));
+
+		outgoing_sender
+			.send(WebSocketMessage::Ping(vec![4, 2].into()))
+			.await
+			.unwrap();
+		let event = events.next().await.expect("No heartbeat event");
+
+		assert_eq!(
+			format!("{:?}", Event::default().comment("heartbeat")),
+			format!("{event:?}")
+		);
+		assert_eq!(
+			WebSocketMessage::Pong(vec![4, 2].into()),
+			pong_receiver.next().await.expect("Heartbeat was not answered")
+		);
+	}
+
+	#[tokio::test]
+	async fn should_remove_the_session_when_the_client_disconnects_before_registering() {
+		let sse_sessions = SseSessions::default();
+		let session_token = Uuid::new_v4();
+		let (request_sender, mut request_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+		let (_outgoing_sender, outgoing_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+		let mut events = Box::pin(session_events(
+			sse_sessions.clone(),
+			session_token,
+			request_sender,
+			outgoing_receiver,
+		));
+
+		assert!(events.next().await.is_some(), "No session event");
+		assert!(sse_sessions.get(session_token).is_some());
+
+		drop(events);
+
+		assert!(sse_sessions.get(session_token).is_none());
+		assert!(
+			request_receiver.next().await.is_none(),
+			"Registration didn't end after the client disconnected"
+		);
+	}
+
+	#[tokio::test]
+	async fn should_end_events_when_connection_is_closed() {
+		let (mut outgoing_sender, outgoing_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+		let (pong_sender, _pong_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+		let mut events = Box::pin(outgoing_events(outgoing_receiver, pong_sender));
+
+		outgoing_sender.send(WebSocketMessage::text("{}")).await.unwrap();
+		outgoing_sender.send(WebSocketMessage::Close(None)).await.unwrap();
+
+		assert!(events.next().await.is_some());
+		assert!(events.next().await.is_none());
+	}
+}
@@ -1,10 +1,16 @@
 use crate::configuration::Configuration;
 use crate::context::ApplicationContext;
-use crate::message::client_request::{ChatRequest, RegisterRequest};
+use crate::message::client_request::{
+	AddSubtitleTrackRequest, ChangeNameRequest, ChatRequest, InsertMediumRequest, MediumRequest, RegisterRequest,
+};
+use crate::message::compression::MessageCompression;
+use crate::message::format::MessageFormat;
+use crate::message::outgoing::OutgoingMessage;
 use crate::message::outgoing::broadcast_message::{
-	BroadcastMessage, ChatBroadcast, ClientJoinedBroadcast, ClientLeftBroadcast, LeftReason,
+	BroadcastMessage, ChatBroadcast, ClientJoinedBroadcast, ClientLeftBroadcast, LeftReason, SubtitlesChangedBroadcast,
 };
 use crate::message::outgoing::error_message::{ErrorMessage, ErrorMessageType};
+use crate::message::outgoing::success_message::ProfileResponse;
 use crate::message::outgoing::success_message::SuccessMessage;
 use crate::room::Room;
 use crate::room::session_id::SessionId;
@@ -12,13 +18,19 @@ use crate::server::create_router;
 use crate::utils::test_client::WebsocketTestClient;
 use crate::utils::time_source::TimeSource;
 use axum::http::StatusCode;
-use axum::http::header::{CONNECTION, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION, UPGRADE};
+use axum::http::header::{
+	CONNECTION, CONTENT_TYPE, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_PROTOCOL, SEC_WEBSOCKET_VERSION, UPGRADE,
+};
 use js_int::uint;
+use reqwest::{RequestBuilder, Response};
+use serde::Deserialize;
 use tokio_tungstenite::tungstenite::protocol::Role;
 use tokio_tungstenite::{WebSocketStream, tungstenite};
 
+mod sse_client;
 mod test_client;
 
+use sse_client::SseClient;
 use test_client::TestClient;
 
 mod rest_api;
@@ -72,6 +84,7 @@ async fn should_broadcast_messages() {
 	let message = r"Hello everyone \o/";
 	let request = ChatRequest {
 		message: message.to_string(),
+		reply_to: None,
 	};
 	let (alice_session_id, mut alice_test_client) = registered_websocket_test_client("Alice", &http_client).await;
 	assert_eq!(SessionId::from(0), alice_session_id);
@@ -81,31 +94,352 @@ async fn should_broadcast_messages() {
 	let expected_bob_joined_broadcast = BroadcastMessage::ClientJoined(ClientJoinedBroadcast {
 		id: bob_session_id,
 		name: "Bob".to_string(),
+		accepts_private_messages: true,
+		profile: ProfileResponse::default(),
 	});
 	let bob_joined_broadcast = alice_test_client.receive_broadcast_message().await;
 	assert_eq!(expected_bob_joined_broadcast, bob_joined_broadcast);
 
+	let request_id = alice_test_client.send_request(request).await;
+	assert_eq!(
+		SuccessMessage::Success,
+		alice_test_client.receive_success_message(request_id).await
+	);
+
+	let alice_chat_broadcast = alice_test_client.receive_broadcast_message().await;
 	let expected_chat_broadcast = BroadcastMessage::Chat(ChatBroadcast {
+		id: chat_id(&alice_chat_broadcast),
 		sender_id: alice_session_id,
 		sender_name: "Alice".to_string(),
 		message: message.to_string(),
 		counter: uint!(0),
+		medium_position_in_milliseconds: None,
+		reply_to: None,
+		mentions: Vec::new(),
+		mentions_you: false,
 	});
+	assert_eq!(expected_chat_broadcast, alice_chat_broadcast);
+	assert_eq!(
+		expected_chat_broadcast,
+		bob_test_client.receive_broadcast_message().await
+	);
+}
 
-	let request_id = alice_test_client.send_request(request).await;
+#[tokio::test]
+async fn should_reply_with_error_for_too_long_chat_messages_instead_of_closing_the_connection() {
+	let http_client = start_test_server().await;
+	let (_session_id, mut websocket_client) = registered_websocket_test_client("Ferris", &http_client).await;
+
+	let request_id = websocket_client
+		.send_request(ChatRequest {
+			message: "🦀".repeat(1000),
+			reply_to: None,
+		})
+		.await;
+	let response = websocket_client.receive_error_message(Some(request_id)).await;
+	assert_eq!(ErrorMessageType::ChatMessageTooLong, response.error);
+
+	let request_id = websocket_client
+		.send_request(ChatRequest {
+			message: "🦀".to_string(),
+			reply_to: None,
+		})
+		.await;
+	assert_eq!(
+		SuccessMessage::Success,
+		websocket_client.receive_success_message(request_id).await
+	);
+}
+
+#[tokio::test]
+async fn should_exchange_message_pack_messages_if_negotiated() {
+	let http_client = start_test_server().await;
+	let mut alice_test_client = websocket_test_client_with_format(&http_client, MessageFormat::MessagePack).await;
+
+	let request_id = alice_test_client
+		.send_request(RegisterRequest {
+			name: "Alice".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
+		})
+		.await;
+	let websocket_message = alice_test_client.receive_raw().await;
+	assert!(websocket_message.is_binary());
+	let hello_message = MessageFormat::MessagePack
+		.decode::<OutgoingMessage>(&websocket_message)
+		.expect("Failed to decode MessagePack response");
+	let OutgoingMessage::Success {
+		request_id: hello_request_id,
+		message: SuccessMessage::Hello {
+			id: alice_session_id, ..
+		},
+	} = hello_message
+	else {
+		panic!("Expected Hello-Response, got '{hello_message:?}'");
+	};
+	assert_eq!(request_id, hello_request_id);
+	let _alice_joined_broadcast = alice_test_client.receive_broadcast_message().await;
+
+	let request_id = alice_test_client
+		.send_request(ChatRequest {
+			message: "Hallo".to_string(),
+			reply_to: None,
+		})
+		.await;
 	assert_eq!(
 		SuccessMessage::Success,
 		alice_test_client.receive_success_message(request_id).await
 	);
+	let chat_broadcast = alice_test_client.receive_broadcast_message().await;
+	assert_eq!(
+		BroadcastMessage::Chat(ChatBroadcast {
+			id: chat_id(&chat_broadcast),
+			sender_id: alice_session_id,
+			sender_name: "Alice".to_string(),
+			message: "Hallo".to_string(),
+			counter: uint!(0),
+			medium_position_in_milliseconds: None,
+			reply_to: None,
+			mentions: Vec::new(),
+			mentions_you: false,
+		}),
+		chat_broadcast
+	);
+}
+
+#[tokio::test]
+async fn should_not_allow_text_messages_after_negotiating_message_pack() {
+	let http_client = start_test_server().await;
+	let mut websocket_client = websocket_test_client_with_format(&http_client, MessageFormat::MessagePack).await;
+	let invalid_message = tungstenite::Message::text(r#"{"request_id":1,"type":"register","name":"Ferris"}"#);
+	websocket_client.send_raw(invalid_message).await;
+
+	let response = websocket_client.receive_error_message(None).await;
+
+	assert_eq!(ErrorMessageType::InvalidFormat, response.error);
+}
 
+#[tokio::test]
+async fn should_compress_large_messages_if_negotiated() {
+	let http_client = start_test_server().await;
+	let mut alice_test_client =
+		websocket_test_client_with_protocol(&http_client, "communityvi-json+deflate", MessageFormat::Json).await;
+
+	let request_id = alice_test_client
+		.send_request(RegisterRequest {
+			name: "Alice".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
+		})
+		.await;
+	let hello_message = alice_test_client.receive_raw().await;
+	assert!(hello_message.is_text(), "Small messages must not be compressed");
+	let hello_message = MessageFormat::Json
+		.decode::<OutgoingMessage>(&hello_message)
+		.expect("Failed to decode JSON response");
+	let OutgoingMessage::Success {
+		request_id: hello_request_id,
+		message: SuccessMessage::Hello {
+			id: alice_session_id, ..
+		},
+	} = hello_message
+	else {
+		panic!("Expected Hello-Response, got '{hello_message:?}'");
+	};
+	assert_eq!(request_id, hello_request_id);
+	let _alice_joined_broadcast = alice_test_client.receive_broadcast_message().await;
+
+	let long_message = "All work and no play makes Jack a dull boy. ".repeat(40);
+	let request_id = alice_test_client
+		.send_request(ChatRequest {
+			message: long_message.clone(),
+			reply_to: None,
+		})
+		.await;
+	let mut compressed_messages = Vec::new();
+	for _ in 0..2 {
+		let websocket_message = alice_test_client.receive_raw().await;
+		if websocket_message.is_binary() {
+			compressed_messages.push(websocket_message);
+		} else {
+			assert_eq!(
+				OutgoingMessage::Success {
+					request_id,
+					message: SuccessMessage::Success
+				},
+				MessageFormat::Json.decode(&websocket_message).unwrap()
+			);
+		}
+	}
+
+	let [compressed_message] = compressed_messages.as_slice() else {
+		panic!("Expected exactly one compressed message, got {compressed_messages:?}");
+	};
+	let tungstenite::Message::Binary(compressed_bytes) = compressed_message else {
+		unreachable!();
+	};
+	assert_eq!(MessageCompression::MARKER, compressed_bytes[0]);
+	assert!(compressed_bytes.len() < long_message.len());
+	let chat_message = MessageFormat::Json
+		.decode::<OutgoingMessage>(&alice_test_client.decompress(compressed_message.clone()))
+		.expect("Failed to decode decompressed JSON broadcast");
+	let OutgoingMessage::Broadcast {
+		message: chat_broadcast,
+	} = &chat_message
+	else {
+		panic!("Expected broadcast, got {chat_message:?}");
+	};
 	assert_eq!(
-		expected_chat_broadcast,
+		OutgoingMessage::Broadcast {
+			message: BroadcastMessage::Chat(ChatBroadcast {
+				id: chat_id(chat_broadcast),
+				sender_id: alice_session_id,
+				sender_name: "Alice".to_string(),
+				message: long_message,
+				counter: uint!(0),
+				medium_position_in_milliseconds: None,
+				reply_to: None,
+				mentions: Vec::new(),
+				mentions_you: false,
+			})
+		},
+		chat_message
+	);
+}
+
+#[tokio::test]
+async fn should_compress_large_message_pack_messages_if_negotiated() {
+	let http_client = start_test_server().await;
+	let (alice_session_id, _alice_test_client) = registered_websocket_test_client("Alice", &http_client).await;
+	let mut bob_test_client = websocket_test_client_with_protocol(
+		&http_client,
+		"communityvi-messagepack+deflate",
+		MessageFormat::MessagePack,
+	)
+	.await;
+	// Enough clients for the Hello message to exceed the compression threshold
+	let mut other_test_clients = Vec::new();
+	for number in 0..7 {
+		let name = format!("{number}: {}", "Client with a rather long name. ".repeat(5));
+		other_test_clients.push(registered_websocket_test_client(&name, &http_client).await);
+	}
+
+	let request_id = bob_test_client
+		.send_request(RegisterRequest {
+			name: "Bob".to_string(),
+			accepts_private_messages: true,
+			moderator_token: None,
+		})
+		.await;
+	let websocket_message = bob_test_client.receive_raw().await;
+	let tungstenite::Message::Binary(compressed_bytes) = &websocket_message else {
+		panic!("Expected binary message, got {websocket_message:?}");
+	};
+	assert_eq!(MessageCompression::MARKER, compressed_bytes[0]);
+
+	let hello_message = MessageFormat::MessagePack
+		.decode::<OutgoingMessage>(&bob_test_client.decompress(websocket_message))
+		.expect("Failed to decode decompressed MessagePack response");
+	let OutgoingMessage::Success {
+		request_id: hello_request_id,
+		message: SuccessMessage::Hello { clients, .. },
+	} = hello_message
+	else {
+		panic!("Expected Hello-Response, got '{hello_message:?}'");
+	};
+	assert_eq!(request_id, hello_request_id);
+	assert_eq!(8, clients.len());
+	assert!(clients.iter().any(|client| client.id == alice_session_id));
+}
+
+#[tokio::test]
+async fn should_exchange_messages_via_server_sent_events() {
+	let http_client = start_test_server().await;
+	let (alice_session_id, mut alice_test_client) = registered_websocket_test_client("Alice", &http_client).await;
+	let mut bob_test_client = WebsocketTestClient::from(
+		SseClient::connect(&http_client)
+			.await
+			.expect("Failed to connect SSE client"),
+	);
+
+	let bob_session_id = register_client("Bob", &mut bob_test_client).await;
+	assert_eq!(
+		BroadcastMessage::ClientJoined(ClientJoinedBroadcast {
+			id: bob_session_id,
+			name: "Bob".to_string(),
+			accepts_private_messages: true,
+			profile: ProfileResponse::default(),
+		}),
 		alice_test_client.receive_broadcast_message().await
 	);
+
+	let request_id = alice_test_client
+		.send_request(ChatRequest {
+			message: "Hi Bob".to_string(),
+			reply_to: None,
+		})
+		.await;
 	assert_eq!(
-		expected_chat_broadcast,
-		bob_test_client.receive_broadcast_message().await
+		SuccessMessage::Success,
+		alice_test_client.receive_success_message(request_id).await
 	);
+	let bob_chat_broadcast = bob_test_client.receive_broadcast_message().await;
+	let alice_chat_broadcast = BroadcastMessage::Chat(ChatBroadcast {
+		id: chat_id(&bob_chat_broadcast),
+		sender_id: alice_session_id,
+		sender_name: "Alice".to_string(),
+		message: "Hi Bob".to_string(),
+		counter: uint!(0),
+		medium_position_in_milliseconds: None,
+		reply_to: None,
+		mentions: Vec::new(),
+		mentions_you: false,
+	});
+	assert_eq!(alice_chat_broadcast, bob_chat_broadcast);
+	assert_eq!(
+		alice_chat_broadcast,
+		alice_test_client.receive_broadcast_message().await
+	);
+
+	let request_id = bob_test_client
+		.send_request(ChatRequest {
+			message: "Hi Alice".to_string(),
+			reply_to: None,
+		})
+		.await;
+	assert_eq!(
+		SuccessMessage::Success,
+		bob_test_client.receive_success_message(request_id).await
+	);
+	let chat_broadcast = alice_test_client.receive_broadcast_message().await;
+	assert_eq!(
+		BroadcastMessage::Chat(ChatBroadcast {
+			id: chat_id(&chat_broadcast),
+			sender_id: bob_session_id,
+			sender_name: "Bob".to_string(),
+			message: "Hi Alice".to_string(),
+			counter: uint!(1),
+			medium_position_in_milliseconds: None,
+			reply_to: None,
+			mentions: Vec::new(),
+			mentions_you: false,
+		}),
+		chat_broadcast
+	);
+}
+
+#[tokio::test]
+async fn should_not_accept_requests_for_unknown_sse_sessions() {
+	let http_client = start_test_server().await;
+
+	let response = http_client
+		.post("/sse/3f5e7c8d-7b2a-4c9e-9f3d-1a2b3c4d5e6f")
+		.body(r#"{"request_id":1,"type":"register","name":"Ferris"}"#)
+		.send()
+		.await
+		.expect("Request failed");
+
+	assert_eq!(StatusCode::NOT_FOUND, response.status());
 }
 
 #[tokio::test]
@@ -126,6 +460,31 @@ async fn should_broadcast_when_client_leaves_the_room() {
 	assert_eq!(expected_leave_message, leave_message);
 }
 
+#[tokio::test]
+async fn should_broadcast_the_current_name_when_a_renamed_client_leaves_the_room() {
+	let http_client = start_test_server().await;
+	let (_alice_session_id, mut alice_client) = registered_websocket_test_client("Alice", &http_client).await;
+	let (bob_session_id, mut bob_client) = registered_websocket_test_client("Bob", &http_client).await;
+	let _bobs_join_message = alice_client.receive_broadcast_message().await;
+
+	let request_id = bob_client
+		.send_request(ChangeNameRequest {
+			name: "Robert".to_string(),
+		})
+		.await;
+	bob_client.receive_success_message(request_id).await;
+	let _bobs_rename_message = alice_client.receive_broadcast_message().await;
+	std::mem::drop(bob_client);
+
+	let expected_leave_message = BroadcastMessage::ClientLeft(ClientLeftBroadcast {
+		id: bob_session_id,
+		name: "Robert".to_string(),
+		reason: LeftReason::Closed,
+	});
+	let leave_message = alice_client.receive_broadcast_message().await;
+	assert_eq!(expected_leave_message, leave_message);
+}
+
 #[tokio::test]
 async fn test_server_should_upgrade_websocket_connection_and_ping_pong() {
 	let http_client = start_test_server().await;
@@ -153,47 +512,223 @@ async fn test_server_should_serve_bundled_frontend() {
 	assert!(content.starts_with(b"<!doctype html>"));
 }
 
-async fn registered_websocket_test_client(
-	name: &'static str,
-	http_client: &TestClient,
-) -> (SessionId, WebsocketTestClient) {
+#[tokio::test]
+async fn should_serve_subtitles_of_the_current_medium() {
+	let http_client = start_test_server().await;
+	let mut websocket_client = websocket_test_client(&http_client).await;
+	let (_session_id, session_token) = register_client_with_session_token("Alice", &mut websocket_client).await;
+	let request_id = websocket_client
+		.send_request(InsertMediumRequest {
+			previous_version: uint!(0),
+			medium: MediumRequest::Live {
+				name: "Rocket Launch".to_string(),
+			},
+		})
+		.await;
+	assert_eq!(
+		SuccessMessage::Success,
+		websocket_client.receive_success_message(request_id).await
+	);
+	websocket_client.receive_broadcast_message().await;
+
+	let upload_response = http_client
+		.post("/api/subtitles")
+		.bearer_auth(session_token)
+		.body("1\n00:00:03,000 --> 00:00:04,000\nLiftoff!\n")
+		.send()
+		.await
+		.expect("Request failed");
+	assert_eq!(StatusCode::OK, upload_response.status());
+	let SubtitleUploadResponse { id } = upload_response.json().await.expect("Failed to parse upload response");
+
+	let request_id = websocket_client
+		.send_request(AddSubtitleTrackRequest {
+			medium_version: uint!(1),
+			track_id: id.clone(),
+			label: "English".to_string(),
+			language: Some("en".to_string()),
+		})
+		.await;
+	assert_eq!(
+		SuccessMessage::Success,
+		websocket_client.receive_success_message(request_id).await
+	);
+	let BroadcastMessage::SubtitlesChanged(SubtitlesChangedBroadcast { subtitles, .. }) =
+		websocket_client.receive_broadcast_message().await
+	else {
+		panic!("Expected subtitles to be broadcast");
+	};
+
+	assert_eq!(id, subtitles.tracks[0].id);
+	let response = http_client
+		.get(&format!("/api/subtitles/{id}"))
+		.send()
+		.await
+		.expect("Request failed");
+
+	assert_eq!(StatusCode::OK, response.status());
+	assert_eq!("text/vtt; charset=utf-8", response.headers()[CONTENT_TYPE]);
+	assert_eq!(
+		"WEBVTT\n\n1\n00:00:03.000 --> 00:00:04.000\nLiftoff!\n",
+		response.text().await.expect("Failed to read subtitles")
+	);
+}
+
+#[tokio::test]
+async fn should_reject_invalid_subtitle_uploads() {
+	let http_client = start_test_server().await;
+	let mut websocket_client = websocket_test_client(&http_client).await;
+	let (_session_id, session_token) = register_client_with_session_token("Alice", &mut websocket_client).await;
+
+	let response = http_client
+		.post("/api/subtitles")
+		.bearer_auth(session_token)
+		.body("WEBVTT\n")
+		.send()
+		.await
+		.expect("Request failed");
+
+	assert_eq!(StatusCode::BAD_REQUEST, response.status());
+	assert_eq!(
+		"Invalid subtitles: Subtitles don't contain any cues.",
+		response.text().await.expect("Failed to read response")
+	);
+}
+
+#[tokio::test]
+async fn should_only_accept_subtitle_uploads_from_registered_clients() {
+	let http_client = start_test_server().await;
+	let subtitles = "1\n00:00:03,000 --> 00:00:04,000\nLiftoff!\n";
+
+	let anonymous_response = http_client
+		.post("/api/subtitles")
+		.body(subtitles)
+		.send()
+		.await
+		.expect("Request failed");
+	let unknown_response = http_client
+		.post("/api/subtitles")
+		.bearer_auth("c5b3b1c9-6a35-4e8f-9a54-1d0f4e2f3c7a")
+		.body(subtitles)
+		.send()
+		.await
+		.expect("Request failed");
+
+	assert_eq!(StatusCode::UNAUTHORIZED, anonymous_response.status());
+	assert_eq!(StatusCode::UNAUTHORIZED, unknown_response.status());
+}
+
+#[tokio::test]
+async fn should_reject_subtitle_uploads_that_are_too_large() {
+	let http_client = start_test_server().await;
+	let mut websocket_client = websocket_test_client(&http_client).await;
+	let (_session_id, session_token) = register_client_with_session_token("Alice", &mut websocket_client).await;
+
+	let response = http_client
+		.post("/api/subtitles")
+		.bearer_auth(session_token)
+		.body(format!("WEBVTT\n\n{}", "a".repeat(1024 * 1024)))
+		.send()
+		.await
+		.expect("Request failed");
+
+	assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status());
+}
+
+#[tokio::test]
+async fn should_not_find_subtitles_that_dont_belong_to_the_current_medium() {
+	let http_client = start_test_server().await;
+
+	let response = http_client
+		.get("/api/subtitles/unknown")
+		.send()
+		.await
+		.expect("Request failed");
+
+	assert_eq!(StatusCode::NOT_FOUND, response.status());
+}
+
+#[derive(Deserialize)]
+struct SubtitleUploadResponse {
+	id: String,
+}
+
+async fn registered_websocket_test_client(name: &str, http_client: &TestClient) -> (SessionId, WebsocketTestClient) {
 	let mut websocket_client = websocket_test_client(http_client).await;
 	let session_id = register_client(name, &mut websocket_client).await;
 	(session_id, websocket_client)
 }
 
 async fn register_client(name: &str, test_client: &mut WebsocketTestClient) -> SessionId {
-	let register_request = RegisterRequest { name: name.to_string() };
+	let (id, _session_token) = register_client_with_session_token(name, test_client).await;
+	id
+}
+
+async fn register_client_with_session_token(name: &str, test_client: &mut WebsocketTestClient) -> (SessionId, String) {
+	let register_request = RegisterRequest {
+		name: name.to_string(),
+		accepts_private_messages: true,
+		moderator_token: None,
+	};
 
 	let request_id = test_client.send_request(register_request).await;
 
 	let response = test_client.receive_success_message(request_id).await;
 
-	let SuccessMessage::Hello { id, .. } = response else {
+	let SuccessMessage::Hello { id, session_token, .. } = response else {
 		panic!("Expected Hello-Response, got '{response:?}'");
 	};
 
 	let joined_response = test_client.receive_broadcast_message().await;
 	assert!(matches!(
 		joined_response,
-		BroadcastMessage::ClientJoined(ClientJoinedBroadcast { id: _, name: _ })
+		BroadcastMessage::ClientJoined(ClientJoinedBroadcast { .. })
 	));
 
-	id
+	(id, session_token)
 }
 
 async fn websocket_test_client(http_client: &TestClient) -> WebsocketTestClient {
-	let response = http_client
+	let response = websocket_upgrade_request(http_client)
+		.send()
+		.await
+		.expect("Websocket request failed.");
+	assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+	assert!(!response.headers().contains_key(SEC_WEBSOCKET_PROTOCOL));
+
+	upgrade_websocket(response).await
+}
+
+async fn websocket_test_client_with_format(http_client: &TestClient, format: MessageFormat) -> WebsocketTestClient {
+	websocket_test_client_with_protocol(http_client, format.websocket_protocol(), format).await
+}
+
+async fn websocket_test_client_with_protocol(
+	http_client: &TestClient,
+	protocol: &str,
+	format: MessageFormat,
+) -> WebsocketTestClient {
+	let response = websocket_upgrade_request(http_client)
+		.header(SEC_WEBSOCKET_PROTOCOL, format!("graphql-ws, {protocol}"))
+		.send()
+		.await
+		.expect("Websocket request failed.");
+	assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+	assert_eq!(response.headers()[SEC_WEBSOCKET_PROTOCOL], protocol);
+
+	upgrade_websocket(response).await.with_format(format)
+}
+
+fn websocket_upgrade_request(http_client: &TestClient) -> RequestBuilder {
+	http_client
 		.get("/ws")
 		.header(CONNECTION, "upgrade")
 		.header(UPGRADE, "websocket")
 		.header(SEC_WEBSOCKET_KEY, "dGhlIHNhbXBsZSBub25jZQ==")
 		.header(SEC_WEBSOCKET_VERSION, "13")
-		.send()
-		.await
-		.expect("Websocket request failed.");
-	assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+}
 
+async fn upgrade_websocket(response: Response) -> WebsocketTestClient {
 	let upgraded = response.upgrade().await.expect("Failed to upgrade client websocket.");
 	WebSocketStream::from_raw_socket(upgraded, Role::Client, None)
 		.await
@@ -201,13 +736,7 @@ async fn websocket_test_client(http_client: &TestClient) -> WebsocketTestClient
 }
 
 async fn start_test_server() -> TestClient {
-	let configuration = Configuration {
-		address: "127.0.0.1:8000".parse().unwrap(),
-		log_filters: String::new(),
-		room_size_limit: 10,
-		heartbeat_interval: std::time::Duration::from_secs(2),
-		missed_heartbeat_limit: 3,
-	};
+	let configuration = Configuration::test();
 	let time_source = TimeSource::test();
 	let application_context = ApplicationContext::new(configuration, time_source)
 		.await
@@ -229,7 +758,10 @@ async fn start_test_server() -> TestClient {
 	let room = Room::new(
 		test_room.uuid,
 		application_context.reference_timer.clone(),
-		10,
+		&Configuration {
+			room_size_limit: 10,
+			..application_context.configuration.clone()
+		},
 		application_context.database.clone(),
 		application_context.user_service.clone(),
 		application_context.repository.clone(),
@@ -238,3 +770,10 @@ async fn start_test_server() -> TestClient {
 		.await
 		.expect("Failed to start test server")
 }
+
+fn chat_id(broadcast: &BroadcastMessage) -> String {
+	let BroadcastMessage::Chat(ChatBroadcast { id, .. }) = broadcast else {
+		panic!("Expected chat broadcast, got {broadcast:?}");
+	};
+	id.clone()
+}
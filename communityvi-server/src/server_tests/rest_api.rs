@@ -1,8 +1,12 @@
+use crate::message::outgoing::success_message::TimeSyncResponse;
 use crate::reference_time::ReferenceTimer;
 use crate::server_tests::start_test_server;
+use crate::server_tests::test_client::TestClient;
 use axum::http::StatusCode;
-use js_int::UInt;
+use axum::http::header::{ACCEPT_RANGES, CONTENT_RANGE, ETAG, IF_NONE_MATCH, IF_RANGE, RANGE};
+use js_int::{UInt, uint};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 
 #[cfg(feature = "api-docs")]
 mod api_docs;
@@ -31,6 +35,49 @@ async fn should_return_reference_time() {
 	assert!(diff <= 2_000, "Was {diff} ms");
 }
 
+#[tokio::test]
+async fn should_synchronize_with_the_reference_time() {
+	let reference_timer = ReferenceTimer::default();
+	let client = start_test_server().await;
+	let response = client
+		.get("/api/time-sync?originate_timestamp_in_milliseconds=42")
+		.send()
+		.await
+		.expect("Request failed");
+
+	let status = response.status();
+	let TimeSyncResponse {
+		originate_timestamp_in_milliseconds,
+		receive_timestamp_in_milliseconds,
+		transmit_timestamp_in_milliseconds,
+	} = response
+		.json::<TimeSyncResponse>()
+		.await
+		.expect("Failed to parse time sync response");
+
+	let reference_time = i64::from(receive_timestamp_in_milliseconds);
+	let expected_reference_time = i64::from(reference_timer.reference_time_milliseconds());
+	let diff = (reference_time - expected_reference_time).abs();
+	assert_eq!(status, StatusCode::OK);
+	assert_eq!(Some(uint!(42)), originate_timestamp_in_milliseconds);
+	assert!(diff >= 0);
+	assert!(diff <= 2_000, "Was {diff} ms");
+	assert!(transmit_timestamp_in_milliseconds >= receive_timestamp_in_milliseconds);
+}
+
+#[tokio::test]
+async fn should_synchronize_with_the_reference_time_without_originate_timestamp() {
+	let client = start_test_server().await;
+	let response = client.get("/api/time-sync").send().await.expect("Request failed");
+
+	assert_eq!(response.status(), StatusCode::OK);
+	let time_sync_response = response
+		.json::<TimeSyncResponse>()
+		.await
+		.expect("Failed to parse time sync response");
+	assert_eq!(None, time_sync_response.originate_timestamp_in_milliseconds);
+}
+
 #[tokio::test]
 async fn should_provide_openapi_json() {
 	let client = start_test_server().await;
@@ -40,6 +87,7 @@ async fn should_provide_openapi_json() {
 	#[derive(Deserialize)]
 	struct OpenApi {
 		openapi: String,
+		paths: BTreeMap<String, serde_json::Value>,
 	}
 
 	let status = response.status();
@@ -50,4 +98,124 @@ async fn should_provide_openapi_json() {
 
 	assert_eq!(status, StatusCode::OK);
 	assert!(specification.openapi.starts_with("3."));
+	assert!(specification.paths.contains_key("/api/reference-time-milliseconds"));
+	assert!(specification.paths.contains_key("/api/time-sync"));
+	assert!(specification.paths.contains_key("/api/media/{id}"));
+	assert!(specification.paths.contains_key("/api/subtitles"));
+	assert!(specification.paths.contains_key("/api/subtitles/{id}"));
+}
+
+#[derive(Deserialize)]
+struct HostedMediumResponse {
+	id: String,
+	name: String,
+	length_in_milliseconds: u64,
+	size_in_bytes: u64,
+	mime_type: String,
+}
+
+async fn hosted_medium(client: &TestClient, name: &str) -> HostedMediumResponse {
+	let response = client.get("/api/media").send().await.expect("Request failed");
+	assert_eq!(response.status(), StatusCode::OK);
+
+	response
+		.json::<Vec<HostedMediumResponse>>()
+		.await
+		.expect("Failed to parse media response")
+		.into_iter()
+		.find(|medium| medium.name == name)
+		.expect("Medium wasn't listed")
+}
+
+#[tokio::test]
+async fn should_list_hosted_media() {
+	let client = start_test_server().await;
+
+	let medium = hosted_medium(&client, "music/half-a-second.wav").await;
+
+	assert_eq!(500, medium.length_in_milliseconds);
+	assert_eq!(4044, medium.size_in_bytes);
+	assert_eq!("audio/wav", medium.mime_type);
+}
+
+#[tokio::test]
+async fn should_stream_hosted_media() {
+	let client = start_test_server().await;
+	let medium = hosted_medium(&client, "silence.wav").await;
+
+	let response = client
+		.get(&format!("/api/media/{}", medium.id))
+		.send()
+		.await
+		.expect("Request failed");
+
+	assert_eq!(response.status(), StatusCode::OK);
+	assert_eq!("bytes", response.headers()[ACCEPT_RANGES]);
+	assert!(response.headers().contains_key(ETAG));
+	let content = response.bytes().await.expect("Failed to read content");
+	assert_eq!(std::fs::read("test/media/silence.wav").unwrap(), content);
+}
+
+#[tokio::test]
+async fn should_stream_ranges_of_hosted_media() {
+	let client = start_test_server().await;
+	let medium = hosted_medium(&client, "silence.wav").await;
+
+	let response = client
+		.get(&format!("/api/media/{}", medium.id))
+		.header(RANGE, "bytes=0-3")
+		.send()
+		.await
+		.expect("Request failed");
+
+	assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+	assert_eq!("bytes 0-3/2044", response.headers()[CONTENT_RANGE]);
+	assert_eq!(
+		b"RIFF".as_slice(),
+		response.bytes().await.expect("Failed to read content")
+	);
+}
+
+#[tokio::test]
+async fn should_ignore_ranges_of_changed_hosted_media() {
+	let client = start_test_server().await;
+	let medium = hosted_medium(&client, "silence.wav").await;
+
+	let response = client
+		.get(&format!("/api/media/{}", medium.id))
+		.header(RANGE, "bytes=0-3")
+		.header(IF_RANGE, r#""outdated""#)
+		.send()
+		.await
+		.expect("Request failed");
+
+	assert_eq!(response.status(), StatusCode::OK);
+	assert_eq!(2044, response.bytes().await.expect("Failed to read content").len());
+}
+
+#[tokio::test]
+async fn should_not_resend_cached_hosted_media() {
+	let client = start_test_server().await;
+	let medium = hosted_medium(&client, "silence.wav").await;
+	let path = format!("/api/media/{}", medium.id);
+	let response = client.get(&path).send().await.expect("Request failed");
+	let etag = response.headers()[ETAG].clone();
+
+	let response = client
+		.get(&path)
+		.header(IF_NONE_MATCH, etag)
+		.send()
+		.await
+		.expect("Request failed");
+
+	assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn should_not_find_unknown_hosted_media() {
+	let client = start_test_server().await;
+
+	let response = client.get("/api/media/unknown").send().await.expect("Request failed");
+
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
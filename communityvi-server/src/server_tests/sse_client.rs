@@ -0,0 +1,109 @@
+use crate::message::WebSocketMessage;
+use crate::server_tests::test_client::TestClient;
+use crate::utils::test_client::WebSocketClient;
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use reqwest::{Response, StatusCode, Url};
+
+/// Client for the SSE transport that can be used in place of a websocket.
+///
+/// Events are received as text messages and heartbeat comments as empty pings.
+pub struct SseClient {
+	client: reqwest::Client,
+	events: EventStream,
+	request_url: Url,
+}
+
+impl SseClient {
+	pub async fn connect(http_client: &TestClient) -> anyhow::Result<Self> {
+		let response = http_client.get("/sse").send().await?;
+		if response.status() != StatusCode::OK {
+			bail!("Failed to connect, got status {}", response.status());
+		}
+
+		let mut events = EventStream {
+			response,
+			buffer: String::new(),
+		};
+		let (event_type, session_token) = events.next_event().await?;
+		if event_type.as_deref() != Some("session") {
+			bail!("Expected session event, got {event_type:?}");
+		}
+		let request_url = http_client
+			.post(&format!("/sse/{session_token}"))
+			.build()?
+			.url()
+			.clone();
+
+		Ok(Self {
+			client: reqwest::Client::new(),
+			events,
+			request_url,
+		})
+	}
+}
+
+struct EventStream {
+	response: Response,
+	buffer: String,
+}
+
+impl EventStream {
+	/// Receives the next event as type and data. Comments have neither.
+	async fn next_event(&mut self) -> anyhow::Result<(Option<String>, String)> {
+		let event = loop {
+			if let Some(end) = self.buffer.find("\n\n") {
+				let event = self.buffer[..end].to_string();
+				self.buffer.drain(..end + 2);
+				break event;
+			}
+
+			let chunk = self
+				.response
+				.chunk()
+				.await?
+				.ok_or_else(|| anyhow!("Event stream has ended"))?;
+			self.buffer.push_str(std::str::from_utf8(&chunk)?);
+		};
+
+		let mut event_type = None;
+		let mut data = String::new();
+		for line in event.lines() {
+			if let Some(value) = line.strip_prefix("event:") {
+				event_type = Some(value.trim().to_string());
+			} else if let Some(value) = line.strip_prefix("data:") {
+				data.push_str(value.strip_prefix(' ').unwrap_or(value));
+			}
+		}
+		Ok((event_type, data))
+	}
+}
+
+#[async_trait]
+impl WebSocketClient for SseClient {
+	async fn send(&mut self, message: WebSocketMessage) -> anyhow::Result<()> {
+		let WebSocketMessage::Text(text) = message else {
+			bail!("Only text messages can be sent via SSE transport, got {message:?}");
+		};
+
+		let response = self
+			.client
+			.post(self.request_url.clone())
+			.body(text.as_str().to_owned())
+			.send()
+			.await?;
+		if response.status() != StatusCode::ACCEPTED {
+			bail!("Request was not accepted, got status {}", response.status());
+		}
+		Ok(())
+	}
+
+	async fn receive(&mut self) -> anyhow::Result<WebSocketMessage> {
+		let message = match self.events.next_event().await? {
+			(None, data) if data.is_empty() => WebSocketMessage::Ping(Default::default()),
+			(None, data) => WebSocketMessage::text(data),
+			(Some(event_type), _) => bail!("Unexpected event type: {event_type}"),
+		};
+		Ok(message)
+	}
+}
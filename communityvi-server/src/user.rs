@@ -1,7 +1,7 @@
 use crate::database::Connection;
 use crate::database::error::DatabaseError;
 use crate::types::uuid::Uuid;
-use crate::user::model::User;
+use crate::user::model::{Profile, User};
 use crate::user::repository::UserRepository;
 use std::sync::Arc;
 use thiserror::Error;
@@ -21,23 +21,44 @@ impl UserService {
 	}
 
 	pub async fn create_user(&self, name: &str, connection: &mut dyn Connection) -> Result<User, UserCreationError> {
-		if name.trim().is_empty() {
-			return Err(UserCreationError::NameEmpty);
-		}
-
-		const MAX_NAME_LENGTH: usize = 256;
-		if name.len() > MAX_NAME_LENGTH {
-			return Err(UserCreationError::NameTooLong);
-		}
+		validate_name(name)?;
 
 		let user = self
 			.repository
 			.create(connection, name, &normalize_name(name))
 			.await
-			.map_err(|error| match error {
-				DatabaseError::UniqueViolation(_) => UserCreationError::NameAlreadyInUse,
-				other => other.into(),
-			})?;
+			.map_err(name_error)?;
+
+		Ok(user)
+	}
+
+	/// Validates the new name the same way as [`UserService::create_user`].
+	pub async fn rename_user(
+		&self,
+		user_uuid: Uuid,
+		name: &str,
+		connection: &mut dyn Connection,
+	) -> Result<User, UserCreationError> {
+		validate_name(name)?;
+
+		let user = self
+			.repository
+			.update_name(connection, user_uuid, name, &normalize_name(name))
+			.await
+			.map_err(name_error)?;
+
+		Ok(user)
+	}
+
+	pub async fn update_profile(
+		&self,
+		user_uuid: Uuid,
+		profile: &Profile,
+		connection: &mut dyn Connection,
+	) -> Result<User, ProfileError> {
+		validate_profile(profile)?;
+
+		let user = self.repository.update_profile(connection, user_uuid, profile).await?;
 
 		Ok(user)
 	}
@@ -47,6 +68,50 @@ impl UserService {
 	}
 }
 
+fn validate_name(name: &str) -> Result<(), UserCreationError> {
+	if name.trim().is_empty() {
+		return Err(UserCreationError::NameEmpty);
+	}
+
+	const MAX_NAME_LENGTH: usize = 256;
+	if name.len() > MAX_NAME_LENGTH {
+		return Err(UserCreationError::NameTooLong);
+	}
+
+	Ok(())
+}
+
+fn validate_profile(Profile { color, pronouns, .. }: &Profile) -> Result<(), ProfileError> {
+	if let Some(color) = color {
+		let is_hex_color = color
+			.strip_prefix('#')
+			.is_some_and(|hex| hex.len() == 6 && hex.chars().all(|character| character.is_ascii_hexdigit()));
+		if !is_hex_color {
+			return Err(ProfileError::InvalidColor);
+		}
+	}
+
+	if let Some(pronouns) = pronouns {
+		if pronouns.trim().is_empty() {
+			return Err(ProfileError::PronounsEmpty);
+		}
+
+		const MAX_PRONOUNS_LENGTH: usize = 32;
+		if pronouns.len() > MAX_PRONOUNS_LENGTH {
+			return Err(ProfileError::PronounsTooLong);
+		}
+	}
+
+	Ok(())
+}
+
+fn name_error(error: DatabaseError) -> UserCreationError {
+	match error {
+		DatabaseError::UniqueViolation(_) => UserCreationError::NameAlreadyInUse,
+		other => other.into(),
+	}
+}
+
 /// Ensure that unicode characters get correctly decomposed,
 /// normalized and some homograph attacks are hindered, disregarding whitespace.
 pub fn normalize_name(name: &str) -> String {
@@ -68,6 +133,18 @@ pub enum UserCreationError {
 	Database(#[from] DatabaseError),
 }
 
+#[derive(Error, Debug)]
+pub enum ProfileError {
+	#[error("Color must be a hex color like '#1a2b3c'.")]
+	InvalidColor,
+	#[error("Pronouns were empty or whitespace-only, leave them out instead.")]
+	PronounsEmpty,
+	#[error("Pronouns are too long. (>32 bytes UTF-8)")]
+	PronounsTooLong,
+	#[error("Database error: {0}")]
+	Database(#[from] DatabaseError),
+}
+
 #[cfg(test)]
 #[allow(clippy::non_ascii_literal)]
 mod test {
@@ -233,6 +310,99 @@ mod test {
 		);
 	}
 
+	#[tokio::test]
+	async fn should_rename_user() {
+		let user_service = user_service();
+		let mut connection = DefaultTestFactory::connection().await;
+		let user = user_service
+			.create_user("Thomas", connection.as_mut())
+			.await
+			.expect("Failed to create user");
+
+		let renamed_user = user_service
+			.rename_user(user.uuid, "Tom", connection.as_mut())
+			.await
+			.expect("Failed to rename user");
+
+		assert_eq!(user.uuid, renamed_user.uuid);
+		assert_eq!("Tom", renamed_user.name);
+		user_service
+			.create_user("Thomas", connection.as_mut())
+			.await
+			.expect("Failed to create user with the name from before the rename");
+	}
+
+	#[tokio::test]
+	async fn should_not_rename_user_to_invalid_or_homograph_name() {
+		let user_service = user_service();
+		let mut connection = DefaultTestFactory::connection().await;
+		user_service
+			.create_user("Thomas", connection.as_mut())
+			.await
+			.expect("Failed to create user");
+		let user = user_service
+			.create_user("Anorak", connection.as_mut())
+			.await
+			.expect("Failed to create user");
+
+		let empty_result = user_service.rename_user(user.uuid, "  ", connection.as_mut()).await;
+		let too_long_result = user_service
+			.rename_user(user.uuid, &"A".repeat(257), connection.as_mut())
+			.await;
+		let homograph_result = user_service
+			.rename_user(user.uuid, &normalize_name("Thomas"), connection.as_mut())
+			.await;
+
+		assert!(matches!(empty_result, Err(UserCreationError::NameEmpty)));
+		assert!(matches!(too_long_result, Err(UserCreationError::NameTooLong)));
+		assert!(matches!(homograph_result, Err(UserCreationError::NameAlreadyInUse)));
+	}
+
+	#[tokio::test]
+	async fn should_not_update_profile_with_invalid_attributes() {
+		let user_service = user_service();
+		let mut connection = DefaultTestFactory::connection().await;
+		let user = user_service
+			.create_user("Anorak", connection.as_mut())
+			.await
+			.expect("Failed to create user");
+
+		let invalid_color_result = user_service
+			.update_profile(
+				user.uuid,
+				&Profile {
+					color: Some("red".to_string()),
+					..Profile::default()
+				},
+				connection.as_mut(),
+			)
+			.await;
+		let empty_pronouns_result = user_service
+			.update_profile(
+				user.uuid,
+				&Profile {
+					pronouns: Some(" ".to_string()),
+					..Profile::default()
+				},
+				connection.as_mut(),
+			)
+			.await;
+		let too_long_pronouns_result = user_service
+			.update_profile(
+				user.uuid,
+				&Profile {
+					pronouns: Some("a".repeat(33)),
+					..Profile::default()
+				},
+				connection.as_mut(),
+			)
+			.await;
+
+		assert!(matches!(invalid_color_result, Err(ProfileError::InvalidColor)));
+		assert!(matches!(empty_pronouns_result, Err(ProfileError::PronounsEmpty)));
+		assert!(matches!(too_long_pronouns_result, Err(ProfileError::PronounsTooLong)));
+	}
+
 	fn user_service() -> UserService {
 		let repository = DefaultTestFactory::repository();
 		UserService::new(repository)
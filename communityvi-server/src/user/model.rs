@@ -1,9 +1,136 @@
 use crate::types::uuid::Uuid;
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct User {
 	pub uuid: Uuid,
 	pub name: String,
 	pub normalized_name: String,
+	pub profile: Profile,
+}
+
+/// What a user shows about themselves to the others in the room.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+	pub presence: Presence,
+	/// Hex color like `#1a2b3c`.
+	pub color: Option<String>,
+	pub avatar: Option<Avatar>,
+	pub pronouns: Option<String>,
+}
+
+/// Serialized with the same names that are stored in the database, see [`Presence::as_str`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(into = "&'static str", try_from = "String")]
+pub enum Presence {
+	#[default]
+	Active,
+	Away,
+	DoNotDisturb,
+}
+
+impl Presence {
+	const ALL: [Presence; 3] = [Presence::Active, Presence::Away, Presence::DoNotDisturb];
+
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Presence::Active => "active",
+			Presence::Away => "away",
+			Presence::DoNotDisturb => "do_not_disturb",
+		}
+	}
+}
+
+impl From<Presence> for &'static str {
+	fn from(presence: Presence) -> Self {
+		presence.as_str()
+	}
+}
+
+impl TryFrom<&str> for Presence {
+	type Error = anyhow::Error;
+
+	fn try_from(presence: &str) -> Result<Self, Self::Error> {
+		Self::ALL
+			.into_iter()
+			.find(|known_presence| known_presence.as_str() == presence)
+			.ok_or_else(|| anyhow!("Unknown presence '{presence}'"))
+	}
+}
+
+impl TryFrom<String> for Presence {
+	type Error = anyhow::Error;
+
+	fn try_from(presence: String) -> Result<Self, Self::Error> {
+		presence.as_str().try_into()
+	}
+}
+
+/// The fixed set of avatars users can choose from, the pictures themselves are up to the client.
+/// Serialized with the same names that are stored in the database, see [`Avatar::as_str`].
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(into = "&'static str", try_from = "String")]
+pub enum Avatar {
+	Cat,
+	Dog,
+	Fox,
+	Owl,
+	Panda,
+	Penguin,
+	Rabbit,
+	Robot,
+}
+
+impl Avatar {
+	const ALL: [Avatar; 8] = [
+		Avatar::Cat,
+		Avatar::Dog,
+		Avatar::Fox,
+		Avatar::Owl,
+		Avatar::Panda,
+		Avatar::Penguin,
+		Avatar::Rabbit,
+		Avatar::Robot,
+	];
+
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Avatar::Cat => "cat",
+			Avatar::Dog => "dog",
+			Avatar::Fox => "fox",
+			Avatar::Owl => "owl",
+			Avatar::Panda => "panda",
+			Avatar::Penguin => "penguin",
+			Avatar::Rabbit => "rabbit",
+			Avatar::Robot => "robot",
+		}
+	}
+}
+
+impl From<Avatar> for &'static str {
+	fn from(avatar: Avatar) -> Self {
+		avatar.as_str()
+	}
+}
+
+impl TryFrom<&str> for Avatar {
+	type Error = anyhow::Error;
+
+	fn try_from(avatar: &str) -> Result<Self, Self::Error> {
+		Self::ALL
+			.into_iter()
+			.find(|known_avatar| known_avatar.as_str() == avatar)
+			.ok_or_else(|| anyhow!("Unknown avatar '{avatar}'"))
+	}
+}
+
+impl TryFrom<String> for Avatar {
+	type Error = anyhow::Error;
+
+	fn try_from(avatar: String) -> Result<Self, Self::Error> {
+		avatar.as_str().try_into()
+	}
 }
 
 impl TryFrom<libsql::Row> for User {
@@ -13,11 +140,21 @@ impl TryFrom<libsql::Row> for User {
 		let uuid = row.get_value(0)?;
 		let name = row.get(1)?;
 		let normalized_name = row.get(2)?;
+		let presence = row.get::<String>(3)?;
+		let color = row.get(4)?;
+		let avatar = row.get::<Option<String>>(5)?;
+		let pronouns = row.get(6)?;
 
 		Ok(Self {
 			uuid: uuid.try_into()?,
 			name,
 			normalized_name,
+			profile: Profile {
+				presence: presence.as_str().try_into()?,
+				color,
+				avatar: avatar.as_deref().map(Avatar::try_from).transpose()?,
+				pronouns,
+			},
 		})
 	}
 }
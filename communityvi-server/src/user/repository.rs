@@ -18,6 +18,19 @@ pub trait UserRepository: Send + Sync + 'static {
 		name: &str,
 		normalized_name: &str,
 	) -> Result<model::User, DatabaseError>;
+	async fn update_name(
+		&self,
+		connection: &mut dyn Connection,
+		user_uuid: Uuid,
+		name: &str,
+		normalized_name: &str,
+	) -> Result<model::User, DatabaseError>;
+	async fn update_profile(
+		&self,
+		connection: &mut dyn Connection,
+		user_uuid: Uuid,
+		profile: &model::Profile,
+	) -> Result<model::User, DatabaseError>;
 	async fn remove(&self, connection: &mut dyn Connection, user_uuid: Uuid) -> Result<(), DatabaseError>;
 }
 
@@ -4,7 +4,7 @@ mod user_tests {
 	use crate::database::libsql::test_utils::LibSqlTestFactory;
 	use crate::database::test::TestFactory;
 	use crate::types::uuid::Uuid;
-	use crate::user::model::User;
+	use crate::user::model::{Avatar, Presence, Profile, User};
 	use crate::user::normalize_name;
 
 	#[tokio::test]
@@ -17,6 +17,7 @@ mod user_tests {
 			uuid,
 			name,
 			normalized_name,
+			profile,
 		} = repository
 			.user()
 			.create(&mut *connection, name, &normalize_name(name))
@@ -26,6 +27,7 @@ mod user_tests {
 		assert_eq!(4, uuid.get_version_num());
 		assert_eq!("user", name);
 		assert_eq!(normalize_name("user"), normalized_name);
+		assert_eq!(Profile::default(), profile);
 	}
 
 	#[tokio::test]
@@ -94,6 +96,7 @@ mod user_tests {
 			uuid,
 			name,
 			normalized_name,
+			profile,
 		} = repository
 			.user()
 			.get(&mut *connection, user.uuid)
@@ -104,6 +107,7 @@ mod user_tests {
 		assert_eq!(user.uuid, uuid);
 		assert_eq!(user.name, name);
 		assert_eq!(user.normalized_name, normalized_name);
+		assert_eq!(user.profile, profile);
 	}
 
 	#[tokio::test]
@@ -120,6 +124,70 @@ mod user_tests {
 		assert!(fetched_user.is_none());
 	}
 
+	#[tokio::test]
+	async fn updates_name<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let user = repository
+			.user()
+			.create(&mut *connection, "user", &normalize_name("user"))
+			.await
+			.expect("Failed to create user");
+		let renamed_user = repository
+			.user()
+			.update_name(&mut *connection, user.uuid, "ℝ𝓊𝓈𝓉", &normalize_name("ℝ𝓊𝓈𝓉"))
+			.await
+			.expect("Failed to update name");
+
+		assert_eq!(
+			User {
+				uuid: user.uuid,
+				name: "ℝ𝓊𝓈𝓉".to_string(),
+				normalized_name: normalize_name("ℝ𝓊𝓈𝓉"),
+				profile: Profile::default(),
+			},
+			renamed_user
+		);
+		let fetched_user = repository
+			.user()
+			.get(&mut *connection, user.uuid)
+			.await
+			.expect("Failed to get user");
+		assert_eq!(Some(renamed_user), fetched_user);
+	}
+
+	#[tokio::test]
+	async fn updates_profile<Factory: TestFactory>() {
+		let mut connection = Factory::connection().await;
+		let repository = Factory::repository();
+
+		let user = repository
+			.user()
+			.create(&mut *connection, "user", &normalize_name("user"))
+			.await
+			.expect("Failed to create user");
+		let profile = Profile {
+			presence: Presence::DoNotDisturb,
+			color: Some("#1a2b3c".to_string()),
+			avatar: Some(Avatar::Penguin),
+			pronouns: Some("they/them".to_string()),
+		};
+		let updated_user = repository
+			.user()
+			.update_profile(&mut *connection, user.uuid, &profile)
+			.await
+			.expect("Failed to update profile");
+
+		assert_eq!(User { profile, ..user }, updated_user);
+		let fetched_user = repository
+			.user()
+			.get(&mut *connection, updated_user.uuid)
+			.await
+			.expect("Failed to get user");
+		assert_eq!(Some(updated_user), fetched_user);
+	}
+
 	#[tokio::test]
 	async fn removes_user<Factory: TestFactory>() {
 		let mut connection = Factory::connection().await;
@@ -3,18 +3,24 @@ use crate::connection::sender::MessageSender;
 use crate::lifecycle::send_broadcasts;
 use crate::message::WebSocketMessage;
 use crate::message::client_request::{ClientRequest, ClientRequestWithId};
+use crate::message::compression::MessageCompression;
+use crate::message::format::MessageFormat;
 use crate::message::outgoing::OutgoingMessage;
 use crate::message::outgoing::broadcast_message::BroadcastMessage;
 use crate::message::outgoing::error_message::ErrorMessage;
+use crate::message::outgoing::moderator_notice::ModeratorNotice;
+use crate::message::outgoing::private_message::PrivateMessage;
 use crate::message::outgoing::success_message::SuccessMessage;
 use crate::room::Room;
 use crate::room::client::Client;
 use anyhow::anyhow;
 use async_trait::async_trait;
+use flate2::read::DeflateDecoder;
 use futures_channel::mpsc;
 use futures_util::{SinkExt, StreamExt};
 use js_int::UInt;
 use std::collections::{BTreeMap, VecDeque};
+use std::io::Read;
 use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::time::timeout;
@@ -22,9 +28,12 @@ use tokio_tungstenite::WebSocketStream;
 
 pub struct WebsocketTestClient {
 	websocket_client: Box<dyn WebSocketClient>,
+	format: MessageFormat,
 	success_messages: BTreeMap<UInt, SuccessMessage>,
 	error_messages: BTreeMap<Option<UInt>, ErrorMessage>,
 	broadcast_messages: VecDeque<BroadcastMessage>,
+	private_messages: VecDeque<PrivateMessage>,
+	moderator_notices: VecDeque<ModeratorNotice>,
 }
 
 impl WebsocketTestClient {
@@ -40,11 +49,25 @@ impl WebsocketTestClient {
 		(message_sender, message_receiver, test_client)
 	}
 
+	#[must_use]
+	pub fn with_format(mut self, format: MessageFormat) -> Self {
+		self.format = format;
+		self
+	}
+
 	// async because it uses tokio::spawn. This make it clear that this should not be run outside of a runtime.
 	pub async fn in_room(name: &'static str, room: &Room) -> (Client, Self) {
+		Self::in_room_with_permissions(name, false, room).await
+	}
+
+	pub async fn in_room_as_moderator(name: &'static str, room: &Room) -> (Client, Self) {
+		Self::in_room_with_permissions(name, true, room).await
+	}
+
+	async fn in_room_with_permissions(name: &'static str, is_moderator: bool, room: &Room) -> (Client, Self) {
 		let (sender, _, test_client) = Self::new();
 		let (client, _) = room
-			.add_client_and_return_existing(name, sender)
+			.add_client_and_return_existing(name, is_moderator, sender)
 			.await
 			.expect("Failed to add client to room");
 		tokio::spawn(send_broadcasts(client.clone()));
@@ -76,7 +99,7 @@ impl WebsocketTestClient {
 			request_id,
 			request: request.into(),
 		};
-		let websocket_message = WebSocketMessage::from(&client_request);
+		let websocket_message = self.format.encode(&client_request);
 		self.send_raw(websocket_message).await;
 	}
 
@@ -107,6 +130,45 @@ impl WebsocketTestClient {
 		}
 	}
 
+	pub async fn receive_private_message(&mut self) -> PrivateMessage {
+		loop {
+			self.receive_outgoing_message().await;
+			if let Some(message) = self.private_messages.pop_front() {
+				return message;
+			}
+		}
+	}
+
+	pub async fn receive_moderator_notice(&mut self) -> ModeratorNotice {
+		loop {
+			self.receive_outgoing_message().await;
+			if let Some(message) = self.moderator_notices.pop_front() {
+				return message;
+			}
+		}
+	}
+
+	/// Reverts the compression of messages that were compressed by [`MessageCompression`].
+	pub fn decompress(&self, websocket_message: WebSocketMessage) -> WebSocketMessage {
+		let WebSocketMessage::Binary(bytes) = &websocket_message else {
+			return websocket_message;
+		};
+		let Some((&MessageCompression::MARKER, compressed)) = bytes.split_first() else {
+			return websocket_message;
+		};
+
+		let mut decompressed = Vec::new();
+		DeflateDecoder::new(compressed)
+			.read_to_end(&mut decompressed)
+			.expect("Failed to decompress message.");
+		match self.format {
+			MessageFormat::Json => {
+				WebSocketMessage::text(String::from_utf8(decompressed).expect("Decompressed JSON was not UTF-8."))
+			}
+			MessageFormat::MessagePack => WebSocketMessage::binary(decompressed),
+		}
+	}
+
 	pub async fn receive_ping(&mut self) -> Vec<u8> {
 		if let WebSocketMessage::Ping(payload) = self.receive_raw().await {
 			payload.to_vec()
@@ -119,8 +181,13 @@ impl WebsocketTestClient {
 		let websocket_message = timeout(Duration::from_secs(1), self.receive_raw())
 			.await
 			.expect("Timeout while waiting for message.");
+		let websocket_message = self.decompress(websocket_message);
 		use OutgoingMessage::*;
-		match OutgoingMessage::try_from(&websocket_message).expect("Failed to deserialize OutgoingMessage") {
+		match self
+			.format
+			.decode::<OutgoingMessage>(&websocket_message)
+			.expect("Failed to deserialize OutgoingMessage")
+		{
 			Success { request_id, message } => {
 				self.success_messages.insert(request_id, message);
 			}
@@ -131,6 +198,12 @@ impl WebsocketTestClient {
 			Broadcast { message } => {
 				self.broadcast_messages.push_back(message);
 			}
+			Private { message } => {
+				self.private_messages.push_back(message);
+			}
+			ModeratorNotice { message } => {
+				self.moderator_notices.push_back(message);
+			}
 		}
 	}
 }
@@ -142,9 +215,12 @@ where
 	fn from(client: Client) -> Self {
 		Self {
 			websocket_client: Box::new(client),
+			format: MessageFormat::default(),
 			success_messages: Default::default(),
 			error_messages: Default::default(),
 			broadcast_messages: Default::default(),
+			private_messages: Default::default(),
+			moderator_notices: Default::default(),
 		}
 	}
 }